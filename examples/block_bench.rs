@@ -0,0 +1,63 @@
+//!
+//!  block_bench.rs
+//!
+//!  Compares the per-frame cost of rendering an enveloped oscillator a frame at a time via
+//!  `Oscillator::next_frame_amp` against rendering it in blocks via `Oscillator::fill_amp_block`.
+//!  Run with the --release flag for meaningful numbers.
+//!
+
+extern crate synth;
+
+use std::time::Instant;
+use synth::{AmpEnvelope, FreqEnvelope, Oscillator, Point};
+use synth::oscillator::{waveform, State};
+
+const BLOCK_FRAMES: usize = 64;
+const FRAMES: usize = 44_100 * 10;
+const SAMPLE_HZ: f64 = 44_100.0;
+
+type Osc = Oscillator<waveform::Sine, AmpEnvelope, FreqEnvelope, ()>;
+
+fn main() {
+    let amp: AmpEnvelope = vec![Point::new(0.0, 0.0, 0.0),
+                                Point::new(0.1, 1.0, 0.0),
+                                Point::new(1.0, 0.0, 0.0)].into();
+    let freq: FreqEnvelope = vec![Point::new(0.0, 0.5, 0.0), Point::new(1.0, 0.6, 0.0)].into();
+    let osc = Oscillator::new(waveform::Sine, amp, freq, ());
+
+    let per_frame_ns = ns_per_frame(osc.clone(), per_frame);
+    let block_ns = ns_per_frame(osc, block);
+    println!("per-frame: {:.1} ns per frame", per_frame_ns);
+    println!("block:     {:.1} ns per frame", block_ns);
+    println!("speedup:   {:.2}x", per_frame_ns / block_ns);
+}
+
+/// Render the whole buffer a frame at a time.
+fn per_frame(osc: &mut Osc, buffer: &mut [f32], state: &mut State) {
+    let perc_delta = 1.0 / buffer.len() as f64;
+    for (i, amp) in buffer.iter_mut().enumerate() {
+        *amp = osc.next_frame_amp(SAMPLE_HZ, i as f64 * perc_delta, 1.0, state);
+    }
+}
+
+/// Render the whole buffer in blocks of `BLOCK_FRAMES` frames.
+fn block(osc: &mut Osc, buffer: &mut [f32], state: &mut State) {
+    let perc_delta = 1.0 / buffer.len() as f64;
+    for (i, chunk) in buffer.chunks_mut(BLOCK_FRAMES).enumerate() {
+        let playhead_perc = (i * BLOCK_FRAMES) as f64 * perc_delta;
+        osc.fill_amp_block(chunk, SAMPLE_HZ, playhead_perc, perc_delta, 1.0, state);
+    }
+}
+
+/// Render `FRAMES` frames with the given function and return the average time taken per frame.
+fn ns_per_frame<R>(mut osc: Osc, render: R) -> f64
+    where R: Fn(&mut Osc, &mut [f32], &mut State),
+{
+    let mut buffer = vec![0.0f32; FRAMES];
+    let mut state = State::new();
+    let start = Instant::now();
+    render(&mut osc, &mut buffer, &mut state);
+    let elapsed = start.elapsed();
+    let ns = elapsed.as_secs() as f64 * 1e9 + elapsed.subsec_nanos() as f64;
+    ns / FRAMES as f64
+}
@@ -99,9 +99,7 @@ fn run() -> Result<(), pa::Error> {
     // The callback we'll use to pass to the Stream.
     let callback = move |pa::OutputStreamCallbackArgs { buffer, time, .. }| {
         let buffer: &mut [[f32; CHANNELS as usize]] = sample::slice::to_frame_slice_mut(buffer).unwrap();
-        sample::slice::equilibrium(buffer);
-
-        synth.fill_slice(buffer, SAMPLE_HZ as f64);
+        synth.fill_slice_replace(buffer, SAMPLE_HZ as f64);
         if timer < 6.0 {
 
             let last_time = prev_time.unwrap_or(time.current);
@@ -0,0 +1,65 @@
+//!
+//!  wavetable_bench.rs
+//!
+//!  Compares the per-frame cost of an analytic sine synth against the same synth after
+//!  `Synth::bake_wavetables`. Run with the --release flag for meaningful numbers.
+//!
+
+extern crate pitch_calc as pitch;
+extern crate synth;
+
+use pitch::{Letter, LetterOctave};
+use std::time::Instant;
+use synth::instrument::{mode, note_freq};
+use synth::oscillator::waveform::{Bakeable, Dynamic};
+use synth::{dynamic, Oscillator};
+
+/// A dynamic synth whose oscillators may be baked into wavetables.
+type Synth = synth::Synth<mode::Dynamic,
+                          note_freq::DynamicGenerator,
+                          Bakeable,
+                          dynamic::Amplitude,
+                          dynamic::Frequency,
+                          dynamic::FreqWarp>;
+
+const CHANNELS: usize = 2;
+const FRAMES: usize = 44_100 * 10;
+const SAMPLE_HZ: f64 = 44_100.0;
+
+fn main() {
+    let synth = {
+        let mut synth = Synth::new(mode::Dynamic::poly(), note_freq::DynamicGenerator::Constant)
+            .num_voices(8);
+        for i in 0..4 {
+            let osc = Oscillator::new(Bakeable::from(Dynamic::Sine),
+                                      dynamic::Amplitude::Constant(0.2),
+                                      dynamic::Frequency::Hz(110.0 * (i + 1) as f64),
+                                      dynamic::FreqWarp::None);
+            synth.add_oscillator(osc);
+        }
+        synth
+    };
+    let mut baked = synth.clone();
+    baked.bake_wavetables(2048);
+
+    let analytic_ns = ns_per_frame(synth);
+    let baked_ns = ns_per_frame(baked);
+    println!("analytic: {:.1} ns per frame", analytic_ns);
+    println!("baked:    {:.1} ns per frame", baked_ns);
+    println!("speedup:  {:.2}x", analytic_ns / baked_ns);
+}
+
+/// Render `FRAMES` frames of a chord and return the average time taken per frame.
+fn ns_per_frame(mut synth: Synth) -> f64 {
+    for &letter in [Letter::C, Letter::E, Letter::G, Letter::B].iter() {
+        synth.note_on(LetterOctave(letter, 3), 1.0);
+    }
+    let mut buffer = vec![[0.0f32; CHANNELS]; 512];
+    let start = Instant::now();
+    for _ in 0..FRAMES / buffer.len() {
+        synth.fill_slice(&mut buffer, SAMPLE_HZ);
+    }
+    let elapsed = start.elapsed();
+    let ns = elapsed.as_secs() as f64 * 1e9 + elapsed.subsec_nanos() as f64;
+    ns / FRAMES as f64
+}
@@ -0,0 +1,103 @@
+//!
+//! An arpeggiator that steps through the notes held on a `Synth` at a steady rate.
+//!
+
+use instrument::unit::NoteVelocity;
+use pitch;
+use time;
+
+
+/// The order in which the held notes are stepped through.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub enum ArpPattern {
+    /// From the lowest held note to the highest.
+    Up,
+    /// From the highest held note to the lowest.
+    Down,
+    /// Up and then back down again without repeating the outermost notes.
+    UpDown,
+}
+
+/// Cycles through the held notes, triggering one at a time every `rate`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct Arpeggiator {
+    /// The duration of each step.
+    #[cfg_attr(feature="serde1", serde(with="::serde1::ms"))]
+    pub rate: time::Ms,
+    /// The order in which the held notes are played.
+    pub pattern: ArpPattern,
+    /// The notes that are currently held, sorted from lowest to highest.
+    pub held_notes: Vec<(pitch::calc::Hz, NoteVelocity)>,
+    /// The number of steps taken since the arpeggiator was started.
+    pub step: usize,
+    /// The number of samples remaining until the next step.
+    pub samples_until_step: time::calc::Samples,
+    /// The note that is currently sounding, if any.
+    pub playing: Option<pitch::calc::Hz>,
+}
+
+
+impl Arpeggiator {
+
+    /// Constructor for a new Arpeggiator.
+    pub fn new(rate: time::Ms, pattern: ArpPattern) -> Self {
+        Arpeggiator {
+            rate: rate,
+            pattern: pattern,
+            held_notes: Vec::new(),
+            step: 0,
+            samples_until_step: 0,
+            playing: None,
+        }
+    }
+
+    /// Add a note to the set of held notes.
+    ///
+    /// If the note is already held, only its velocity is updated.
+    pub fn note_on(&mut self, hz: pitch::calc::Hz, vel: NoteVelocity) {
+        if let Some(note) = self.held_notes.iter_mut().find(|note| note.0 == hz) {
+            note.1 = vel;
+            return;
+        }
+        let idx = self.held_notes.iter().position(|note| note.0 > hz)
+            .unwrap_or(self.held_notes.len());
+        self.held_notes.insert(idx, (hz, vel));
+    }
+
+    /// Remove a note from the set of held notes.
+    pub fn note_off(&mut self, hz: pitch::calc::Hz) {
+        self.held_notes.retain(|note| note.0 != hz);
+    }
+
+    /// Release all held notes and restart the pattern.
+    pub fn clear(&mut self) {
+        self.held_notes.clear();
+        self.step = 0;
+        self.samples_until_step = 0;
+        self.playing = None;
+    }
+
+    /// Return the note for the current step and advance to the next one.
+    ///
+    /// Returns `None` if there are no held notes.
+    pub fn next_note(&mut self) -> Option<(pitch::calc::Hz, NoteVelocity)> {
+        let n = self.held_notes.len();
+        if n == 0 {
+            return None;
+        }
+        let idx = match self.pattern {
+            ArpPattern::Up => self.step % n,
+            ArpPattern::Down => n - 1 - self.step % n,
+            ArpPattern::UpDown => {
+                let period = if n > 1 { 2 * n - 2 } else { 1 };
+                let step = self.step % period;
+                if step < n { step } else { period - step }
+            },
+        };
+        self.step = self.step.wrapping_add(1);
+        Some(self.held_notes[idx])
+    }
+
+}
@@ -0,0 +1,73 @@
+//! 
+//! A synth whose oscillators may each be made up of different concrete types.
+//!
+
+use synth;
+
+pub use self::oscillator::{Oscillator, Waveform, Amplitude, Frequency, FreqWarp};
+pub use self::oscillator::new as new_oscillator;
+
+
+pub mod oscillator {
+    use oscillator::Oscillator as Osc;
+    use oscillator::{waveform, amplitude, frequency, freq_warp};
+
+    /// Any boxed waveform.
+    pub type Waveform = Box<dyn waveform::Waveform + Send>;
+    /// Any boxed amplitude.
+    pub type Amplitude = Box<dyn amplitude::Amplitude + Send>;
+    /// Any boxed frequency.
+    pub type Frequency = Box<dyn frequency::Frequency + Send>;
+    /// Any boxed frequency warp.
+    pub type FreqWarp = Box<dyn freq_warp::FreqWarp + Send>;
+
+    /// An alias for an Oscillator whose components are boxed, allowing oscillators built from
+    /// different concrete types to be played by the same `Synth`.
+    pub type Oscillator = Osc<Waveform, Amplitude, Frequency, FreqWarp>;
+
+    /// Construct a new boxed oscillator from the given components.
+    pub fn new<W, A, F, FW>(waveform: W, amplitude: A, frequency: F, freq_warp: FW) -> Oscillator
+        where W: waveform::Waveform + Send + 'static,
+              A: amplitude::Amplitude + Send + 'static,
+              F: frequency::Frequency + Send + 'static,
+              FW: freq_warp::FreqWarp + Send + 'static,
+    {
+        Osc::new(Box::new(waveform), Box::new(amplitude), Box::new(frequency), Box::new(freq_warp))
+    }
+}
+
+
+/// An alias for a synth whose oscillators may each be of a different concrete type.
+///
+/// Every component of every oscillator is boxed, so each call to the components is dynamically
+/// dispatched. Prefer a concrete or `Dynamic` synth where the oscillators share their types.
+pub type Synth<M, NFG> = synth::Synth<M,
+                                      NFG,
+                                      oscillator::Waveform,
+                                      oscillator::Amplitude,
+                                      oscillator::Frequency,
+                                      oscillator::FreqWarp>;
+
+
+#[test]
+fn test_mixed_oscillators() {
+    use envelope::Point;
+    use instrument::mode::Poly;
+    use oscillator::{waveform, AmpEnvelope};
+    use pitch;
+
+    // A constant sine alongside an enveloped saw.
+    let env: AmpEnvelope = vec![Point::new(0.0, 1.0, 0.0), Point::new(1.0, 0.0, 0.0)].into();
+    let mut synth: Synth<Poly, ()> = synth::Synth::poly(())
+        .oscillator(new_oscillator(waveform::Sine, 0.5f32, 440.0f64, ()))
+        .oscillator(new_oscillator(waveform::Saw, env, 440.0f64, ()));
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 256];
+    synth.fill_slice(&mut buffer, 44_100.0);
+    assert!(buffer.iter().any(|f| f[0] != 0.0));
+
+    // Each boxed oscillator sounds exactly as its concrete counterpart.
+    let osc = new_oscillator(waveform::Saw, 0.5f32, 440.0f64, ());
+    let concrete = ::oscillator::Oscillator::new(waveform::Saw, 0.5f32, 440.0f64, ());
+    assert_eq!(osc.render(44_100.0, 440.0, 64), concrete.render(44_100.0, 440.0, 64));
+}
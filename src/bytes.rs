@@ -0,0 +1,91 @@
+//!
+//! Compact binary serialization of a `Synth` via `bincode`.
+//!
+
+extern crate bincode;
+extern crate serde;
+
+use instrument::NoteFreqGenerator;
+use synth::Synth;
+
+
+impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
+    where NFG: NoteFreqGenerator,
+{
+
+    /// Serialize the synth to a compact binary representation using `bincode`.
+    ///
+    /// This is much smaller than JSON for synths with large envelopes or wavetables.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::serde::SerializeError>
+        where M: serde::Serialize,
+              NFG: serde::Serialize,
+              NFG::NoteFreq: serde::Serialize,
+              W: serde::Serialize,
+              A: serde::Serialize,
+              F: serde::Serialize,
+              FW: serde::Serialize,
+    {
+        bincode::serde::serialize(self, bincode::SizeLimit::Infinite)
+    }
+
+    /// Deserialize a synth from bytes produced by `Synth::to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::serde::DeserializeError>
+        where M: serde::Deserialize,
+              NFG: serde::Deserialize,
+              NFG::NoteFreq: serde::Deserialize,
+              W: serde::Deserialize,
+              A: serde::Deserialize,
+              F: serde::Deserialize,
+              FW: serde::Deserialize,
+    {
+        bincode::serde::deserialize(bytes)
+    }
+
+}
+
+
+#[test]
+fn test_round_trip() {
+    use dynamic::{self, Synth};
+    use envelope::{Envelope, Point};
+
+    let env: Envelope = vec![Point::new(0.0, 0.0, 0.0),
+                             Point::new(0.1, 1.0, 0.0),
+                             Point::new(1.0, 0.0, 0.0)].into();
+    let synth = Synth::dynamic_poly()
+        .oscillator(dynamic::new_oscillator()
+            .waveform(dynamic::Waveform::Saw)
+            .amplitude(dynamic::Amplitude::Envelope(env)))
+        .duration(2000.0)
+        .loop_points(0.25, 0.75);
+
+    let bytes = synth.to_bytes().unwrap();
+    let deserialized = Synth::from_bytes(&bytes).unwrap();
+    assert_eq!(synth, deserialized);
+}
+
+#[test]
+fn test_round_trip_optional_state() {
+    use arpeggiator::ArpPattern;
+    use dynamic::Synth;
+    use pitch;
+    use time;
+    use tuning::Tuning;
+
+    let mut synth = Synth::default()
+        .arpeggiate(125.0, ArpPattern::UpDown)
+        .oversample(4)
+        .tuning(Tuning::just_intonation())
+        .dc_block(true);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    synth.note_sequence(vec![(pitch::Hz(220.0), time::Ms(50.0)),
+                             (pitch::Hz(330.0), time::Ms(25.0))]);
+
+    // Render a little so that the arpeggiator, oversampler and sequence all carry some state.
+    let mut buffer = [[0.0f32; 2]; 64];
+    synth.fill_slice(&mut buffer, 1_000.0);
+
+    let bytes = synth.to_bytes().unwrap();
+    let deserialized = Synth::from_bytes(&bytes).unwrap();
+    assert_eq!(synth, deserialized);
+}
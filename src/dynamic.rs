@@ -71,3 +71,18 @@ impl Synth {
     }
 
 }
+
+impl Default for Synth {
+    /// A polyphonic dynamic synth with a single default oscillator (see `new_oscillator`).
+    fn default() -> Self {
+        Synth::dynamic_poly().oscillator(oscillator::new())
+    }
+}
+
+
+#[test]
+fn test_default() {
+    let synth = Synth::default();
+    assert_eq!(synth.oscillators, vec![oscillator::new()]);
+    assert_eq!(synth.instrument.mode, mode::Dynamic::poly());
+}
@@ -8,21 +8,31 @@ pub type Point = envelope_lib::BezierPoint<f64, f64>;
 
 /// An alias for the envelope to be used used for amp and freq interpolation.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct Envelope {
+    #[cfg_attr(feature="serde1", serde(with="::serde1::points"))]
     pub points: Vec<Point>,
+    /// The index of the point at which the envelope holds its level while the note is held.
+    pub sustain_point: Option<usize>,
+    /// The indices of the first and last points of a region that repeats while the note is held.
+    pub loop_region: Option<(usize, usize)>,
 }
 
 impl std::iter::FromIterator<Point> for Envelope {
     fn from_iter<T>(iter: T) -> Self
         where T: IntoIterator<Item=Point>
     {
-        Envelope { points: iter.into_iter().collect() }
+        Envelope::from(iter.into_iter().collect::<Vec<_>>())
     }
 }
 
 impl std::convert::From<Vec<Point>> for Envelope {
     fn from(points: Vec<Point>) -> Self {
-        Envelope { points: points }
+        Envelope {
+            points: points,
+            sustain_point: None,
+            loop_region: None,
+        }
     }
 }
 
@@ -34,3 +44,199 @@ impl<'a> Trait<'a> for Envelope {
     #[inline]
     fn points(&'a self) -> Self::Points { self.points.iter() }
 }
+
+impl Envelope {
+
+    /// Construct an attack, decay, sustain, release envelope from durations in milliseconds.
+    ///
+    /// The X of each point is the percentage of `total_ms` at which each stage ends, while the
+    /// amplitude rises to `1.0` over the attack, falls to `sustain` over the decay and falls to
+    /// `0.0` over the release at the end of the envelope.
+    ///
+    /// If the attack, decay and release sum to more than `total_ms` they are scaled down
+    /// proportionally to fit. Stages of zero length are collapsed so that no two points share the
+    /// same X, e.g. a zero attack begins the envelope at full amplitude.
+    pub fn adsr(attack_ms: f64, decay_ms: f64, sustain: f64, release_ms: f64, total_ms: f64)
+        -> Envelope
+    {
+        let perc = |ms: f64| if total_ms > 0.0 { ms.max(0.0) / total_ms } else { 0.0 };
+        let (mut attack, mut decay, mut release) = (perc(attack_ms), perc(decay_ms), perc(release_ms));
+        let sum = attack + decay + release;
+        if sum > 1.0 {
+            attack /= sum;
+            decay /= sum;
+            release /= sum;
+        }
+
+        let stages = [
+            (0.0, 0.0),
+            (attack, 1.0),
+            (attack + decay, sustain),
+            (1.0 - release, sustain),
+            (1.0, 0.0),
+        ];
+        let mut points: Vec<Point> = Vec::with_capacity(stages.len());
+        for &(x, y) in stages.iter() {
+            let x = x.min(1.0);
+            // The later stage replaces any zero length stage before it.
+            if let Some(last) = points.last_mut() {
+                if last.x >= x {
+                    last.y = y;
+                    continue;
+                }
+            }
+            points.push(Point::new(x, y, 0.0));
+        }
+        Envelope::from(points)
+    }
+
+    /// Produce a new envelope that is a mirror image of `self` in time.
+    ///
+    /// Each point's X is mirrored around the centre (`x' = 1.0 - x`) and the points are reordered
+    /// so that they remain sorted by X. The curve of each segment is carried over to the point
+    /// that now begins the segment.
+    pub fn reversed(&self) -> Envelope {
+        let n = self.points.len();
+        self.points.iter().rev().enumerate().map(|(i, point)| {
+            // The curve between two points is stored on the first, which is now the last.
+            let curve = if i + 1 < n { self.points[n - 2 - i].curve } else { self.points[n - 1].curve };
+            Point::new(1.0 - point.x, point.y, curve)
+        }).collect()
+    }
+
+    /// Return the Y value at the given X, clamping X to the range covered by the points.
+    ///
+    /// Unlike `Trait::y` this never fails, making it safe to call from the audio thread. Returns
+    /// `0.0` if the envelope has no points.
+    pub fn y_clamped(&self, x: f64) -> f64 {
+        match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => {
+                if x <= first.x {
+                    first.y
+                } else if x >= last.x {
+                    last.y
+                } else {
+                    Trait::y(self, x).unwrap_or(last.y)
+                }
+            },
+            _ => 0.0,
+        }
+    }
+
+    /// Return the Y value at the given X as with `y_clamped`, taking into account whether or not
+    /// the note is still held.
+    ///
+    /// While `note_held` is `true` the envelope holds at the level of the `sustain_point` once X
+    /// reaches it, or otherwise repeats the `loop_region` once X passes its end. The sustain point
+    /// takes precedence over the loop region. Once the note is released the envelope is read at X
+    /// as normal. Indices that are out of bounds are ignored.
+    ///
+    /// Only amplitude envelopes are read this way. A note can only sustain until the playhead
+    /// reaches the end of the synth's duration, so long notes should be combined with the synth's
+    /// loop points.
+    pub fn y_held(&self, x: f64, note_held: bool) -> f64 {
+        if !note_held {
+            return self.y_clamped(x);
+        }
+        if let Some(point) = self.sustain_point.and_then(|idx| self.points.get(idx)) {
+            return if x >= point.x { point.y } else { self.y_clamped(x) };
+        }
+        let region = self.loop_region.and_then(|(start, end)| {
+            match (self.points.get(start), self.points.get(end)) {
+                (Some(start), Some(end)) if start.x < end.x => Some((start.x, end.x)),
+                _ => None,
+            }
+        });
+        match region {
+            Some((start, end)) if x >= end => self.y_clamped(start + (x - start) % (end - start)),
+            _ => self.y_clamped(x),
+        }
+    }
+
+    /// Insert the given point at the position that keeps the points sorted by X.
+    ///
+    /// The point's X is clamped to the range `0.0..1.0` so that the envelope can always be safely
+    /// read at any playhead. If some points already share the same X, the new point is inserted
+    /// after them.
+    pub fn insert_point(&mut self, mut point: Point) {
+        point.x = point.x.max(0.0).min(1.0);
+        let idx = self.points.iter().position(|p| p.x > point.x).unwrap_or(self.points.len());
+        self.points.insert(idx, point);
+    }
+
+    /// Remove and return the point at the given index.
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn remove_point(&mut self, idx: usize) -> Point {
+        self.points.remove(idx)
+    }
+
+}
+
+
+#[test]
+fn test_reversed() {
+    let attack: Envelope = vec![
+        Point::new(0.0, 0.0, 0.5),
+        Point::new(0.25, 1.0, 0.0),
+        Point::new(1.0, 1.0, 0.0),
+    ].into();
+    let decay = attack.reversed();
+    assert_eq!(decay.points, vec![
+        Point::new(0.0, 1.0, 0.0),
+        Point::new(0.75, 1.0, 0.5),
+        Point::new(1.0, 0.0, 0.0),
+    ]);
+    assert_eq!(decay.reversed(), attack);
+}
+
+#[test]
+fn test_insert_remove_point() {
+    let mut env: Envelope = vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 0.0, 0.0)].into();
+    env.insert_point(Point::new(0.5, 1.0, 0.0));
+    env.insert_point(Point::new(1.5, 0.5, 0.0));
+    assert_eq!(env.points, vec![
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(0.5, 1.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        Point::new(1.0, 0.5, 0.0),
+    ]);
+    assert_eq!(env.remove_point(1), Point::new(0.5, 1.0, 0.0));
+    assert_eq!(env.points.len(), 3);
+}
+
+#[test]
+fn test_adsr() {
+    fn xys(env: &Envelope) -> Vec<(f64, f64)> {
+        env.points.iter().map(|p| ((p.x * 1000.0).round() / 1000.0, p.y)).collect()
+    }
+
+    let env = Envelope::adsr(100.0, 100.0, 0.5, 200.0, 1000.0);
+    assert_eq!(xys(&env), vec![(0.0, 0.0), (0.1, 1.0), (0.2, 0.5), (0.8, 0.5), (1.0, 0.0)]);
+
+    // Stages longer than the total are scaled down to fit.
+    let env = Envelope::adsr(500.0, 500.0, 0.5, 1000.0, 1000.0);
+    assert_eq!(xys(&env), vec![(0.0, 0.0), (0.25, 1.0), (0.5, 0.5), (1.0, 0.0)]);
+
+    // A zero attack begins at full amplitude.
+    let env = Envelope::adsr(0.0, 100.0, 0.5, 100.0, 1000.0);
+    assert_eq!(xys(&env)[0], (0.0, 1.0));
+}
+
+#[test]
+fn test_y_held() {
+    let mut env = Envelope::adsr(100.0, 100.0, 0.5, 200.0, 1000.0);
+    assert_eq!(env.y_held(0.9, true), env.y_clamped(0.9));
+
+    // The envelope holds at the sustain level while the note is held.
+    env.sustain_point = Some(2);
+    assert_eq!(env.y_held(0.1, true), 1.0);
+    assert_eq!(env.y_held(0.9, true), 0.5);
+    assert_eq!(env.y_held(0.9, false), env.y_clamped(0.9));
+
+    // The region between the attack and the decay repeats while the note is held.
+    env.sustain_point = None;
+    env.loop_region = Some((1, 2));
+    assert!((env.y_held(0.25, true) - env.y_clamped(0.15)).abs() < 1e-9);
+    assert_eq!(env.y_held(0.25, false), 0.5);
+}
@@ -10,19 +10,50 @@ extern crate rand;
 extern crate sample;
 extern crate utils;
 
+#[cfg(feature="serde1")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature="serde1")]
+extern crate serde_v1;
+
+pub use arpeggiator::{ArpPattern, Arpeggiator};
+pub use boxed::Synth as Boxed;
 pub use dynamic::Synth as Dynamic;
 pub use envelope::{Envelope, Point};
 pub use envelope::Trait as EnvelopeTrait;
 pub use oscillator::{AmpEnvelope, FreqEnvelope, Oscillator, Waveform};
-pub use synth::{Synth, Frames};
+pub use synth::{DcBlocker, Frames, GlideSequence, LoopMode, Oversampler, PanLaw, SpreadShape, Synth,
+                SynthError, MAX_INTERLEAVED_CHANNELS};
+pub use tuning::Tuning;
+
+#[cfg(feature="param_queue")]
+pub use param::{param_queue, ParamReceiver, ParamSender, ParamUpdate};
 
+pub mod arpeggiator;
+pub mod boxed;
 pub mod dynamic;
 pub mod envelope;
 pub mod oscillator;
 mod synth;
+pub mod tuning;
+
+#[cfg(feature="bincode_serialization")]
+mod bytes;
 
 #[cfg(feature="dsp-chain")]
 mod dsp_node;
 
+#[cfg(feature="param_queue")]
+pub mod param;
+
 #[cfg(feature="serde_serialization")]
 mod serde;
+
+#[cfg(feature="serde_serialization")]
+pub mod patch;
+
+#[cfg(feature="serde1")]
+mod serde1;
+
+#[cfg(feature="wav")]
+mod wav;
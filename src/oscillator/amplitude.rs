@@ -1,22 +1,42 @@
 use envelope;
-use envelope::Trait as EnvelopeTrait;
 
 
 /// Types for generating the amplitude given some playhead position.
 pub trait Amplitude {
     /// Return the amplitude given some percentage through the duration of the Synth.
     /// - 0.0 < perc < 1.0.
+    ///
+    /// Implementations should not panic for a `perc` outside of this range.
     fn amp_at_playhead(&self, perc: f64) -> f32;
+
+    /// Return the amplitude as with `amp_at_playhead`, given whether or not the note is held.
+    ///
+    /// By default the held state is ignored.
+    #[inline]
+    fn amp_at_playhead_held(&self, perc: f64, _note_held: bool) -> f32 {
+        self.amp_at_playhead(perc)
+    }
 }
 
 /// Alias for the Envelope used.
 pub type Envelope = envelope::Envelope;
 
+/// An amplitude envelope that is played exactly once, holding its final value beyond the end.
+///
+/// Unlike an `Envelope`, the sustain point and loop region of the inner envelope are ignored, so
+/// the envelope plays through to completion regardless of whether or not the note is held. This
+/// suits percussive patches.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct OneShotEnvelope(pub Envelope);
+
 /// A type that allows dynamically switching between constant and enveloped amplitude.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub enum Dynamic {
     Envelope(Envelope),
     Constant(f32),
+    OneShot(OneShotEnvelope),
 }
 
 
@@ -28,6 +48,19 @@ impl Dynamic {
 }
 
 
+impl<T> Amplitude for Box<T>
+    where T: ?Sized + Amplitude,
+{
+    #[inline]
+    fn amp_at_playhead(&self, perc: f64) -> f32 {
+        (**self).amp_at_playhead(perc)
+    }
+    #[inline]
+    fn amp_at_playhead_held(&self, perc: f64, note_held: bool) -> f32 {
+        (**self).amp_at_playhead_held(perc, note_held)
+    }
+}
+
 impl Amplitude for f32 {
     #[inline]
     fn amp_at_playhead(&self, _perc: f64) -> f32 { *self }
@@ -36,7 +69,18 @@ impl Amplitude for f32 {
 impl Amplitude for Envelope {
     #[inline]
     fn amp_at_playhead(&self, perc: f64) -> f32 {
-        self.y(perc).expect("The given playhead position is out of range (0.0..1.0).") as f32
+        self.y_clamped(perc) as f32
+    }
+    #[inline]
+    fn amp_at_playhead_held(&self, perc: f64, note_held: bool) -> f32 {
+        self.y_held(perc, note_held) as f32
+    }
+}
+
+impl Amplitude for OneShotEnvelope {
+    #[inline]
+    fn amp_at_playhead(&self, perc: f64) -> f32 {
+        self.0.y_clamped(perc.max(0.0).min(1.0)) as f32
     }
 }
 
@@ -46,6 +90,44 @@ impl Amplitude for Dynamic {
         match *self {
             Dynamic::Envelope(ref env) => env.amp_at_playhead(perc),
             Dynamic::Constant(amp) => amp,
+            Dynamic::OneShot(ref one_shot) => one_shot.amp_at_playhead(perc),
+        }
+    }
+    #[inline]
+    fn amp_at_playhead_held(&self, perc: f64, note_held: bool) -> f32 {
+        match *self {
+            Dynamic::Envelope(ref env) => env.amp_at_playhead_held(perc, note_held),
+            Dynamic::Constant(amp) => amp,
+            Dynamic::OneShot(ref one_shot) => one_shot.amp_at_playhead_held(perc, note_held),
         }
     }
 }
+
+
+#[test]
+fn test_envelope_out_of_range() {
+    use envelope::Point;
+    let env: Envelope = vec![Point::new(0.0, 0.25, 0.0), Point::new(1.0, 0.75, 0.0)].into();
+    assert_eq!(env.amp_at_playhead(-0.1), 0.25);
+    assert_eq!(env.amp_at_playhead(1.1), 0.75);
+}
+
+#[test]
+fn test_one_shot_envelope() {
+    use envelope::Point;
+    let mut env: Envelope = vec![Point::new(0.0, 0.0, 0.0),
+                                 Point::new(0.5, 1.0, 0.0),
+                                 Point::new(0.8, 0.25, 0.0)].into();
+    env.loop_region = Some((0, 1));
+    let one_shot = OneShotEnvelope(env.clone());
+
+    // Past the end of the envelope the final value is held.
+    assert_eq!(one_shot.amp_at_playhead(0.9), 0.25);
+    assert_eq!(one_shot.amp_at_playhead(1.5), 0.25);
+    assert_eq!(one_shot.amp_at_playhead(-0.5), 0.0);
+
+    // The loop region is ignored while the note is held.
+    assert!(env.amp_at_playhead_held(0.75, true) != env.amp_at_playhead(0.75));
+    assert_eq!(one_shot.amp_at_playhead_held(0.75, true), env.amp_at_playhead(0.75));
+    assert_eq!(Dynamic::OneShot(one_shot).amp_at_playhead_held(1.5, true), 0.25);
+}
@@ -1,4 +1,6 @@
+use envelope;
 use pitch;
+use time;
 use super::waveform::{self, Waveform};
 
 
@@ -8,14 +10,40 @@ pub trait FreqWarp {
     fn step_phase(&self, _sample_hz: f64, _freq_warp_phase: &mut f64) {}
     /// Return a warped hz given some hz, sample rate and phase.
     fn warp_hz(&self, hz: f64, freq_warp_phase: f64) -> f64;
+    /// Return a warped hz given some hz, phase and the playhead's percentage through the duration.
+    ///
+    /// This is the method called by the `Oscillator` each frame. By default the playhead is
+    /// ignored and the `warp_hz` is returned. Warps that change across the duration (e.g.
+    /// `PitchDriftEnv`) should override it.
+    #[inline]
+    fn warp_hz_at(&self, hz: f64, freq_warp_phase: f64, _playhead_perc: f64) -> f64 {
+        self.warp_hz(hz, freq_warp_phase)
+    }
 }
 
 /// A type for warping the frequency via gaussian randomness.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct Gaussian(pub f32);
 
+/// A type for warping the frequency via gaussian randomness drawn from a seeded sequence.
+///
+/// Unlike `Gaussian`, the same `seed` always produces the same warping so that renders are
+/// reproducible, and the warp is bounded so that the pitch never strays further than `max_mels`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct SeededGaussian {
+    /// The amount of warping, as with `Gaussian`.
+    pub perc: f32,
+    /// The seed of the random sequence.
+    pub seed: u64,
+    /// The maximum distance in mels that the frequency may be warped by.
+    pub max_mels: f32,
+}
+
 /// A type for slowly drifting an oscillators pitch via a noise walk.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct PitchDrift {
     /// The frequncy at which the pitch should drift.
     pub hz: f64,
@@ -23,12 +51,38 @@ pub struct PitchDrift {
     pub amp: f32,
 }
 
-/// A type that allows switching between various kinds of FreqWarp at runtime.
+/// A `PitchDrift` whose depth in steps is read from an envelope across the playhead.
+///
+/// This allows the drift to intensify or settle over the duration of a note.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct PitchDriftEnv {
+    /// The frequncy at which the pitch should drift.
+    pub hz: f64,
+    /// How much the pitch should drift in steps at each point of the playhead.
+    pub amp_env: envelope::Envelope,
+}
+
+/// A type for periodically modulating an oscillator's pitch via a sine, e.g. for vibrato.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct Lfo {
+    /// The rate of the modulation in hz.
+    pub hz: f64,
+    /// How far the pitch should be modulated either side of the frequency in steps.
+    pub depth: f32,
+}
+
+/// A type that allows switching between various kinds of FreqWarp at runtime.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub enum Dynamic {
     None,
     Gaussian(Gaussian),
     PitchDrift(PitchDrift),
+    SeededGaussian(SeededGaussian),
+    PitchDriftEnv(PitchDriftEnv),
+    Lfo(Lfo),
 }
 
 
@@ -37,13 +91,46 @@ impl Dynamic {
     pub fn gaussian(amt: f32) -> Dynamic {
         Dynamic::Gaussian(Gaussian(amt))
     }
+    /// Construct a seeded gaussian bounded to the given maximum offset in mels.
+    pub fn seeded_gaussian(amt: f32, seed: u64, max_mels: f32) -> Dynamic {
+        Dynamic::SeededGaussian(SeededGaussian { perc: amt, seed: seed, max_mels: max_mels })
+    }
     /// Construct a pitch drift.
     pub fn pitch_drift(amp: f32, hz: f64) -> Dynamic {
         Dynamic::PitchDrift(PitchDrift { amp: amp, hz: hz })
     }
+    /// Construct a pitch drift whose depth follows the given envelope.
+    pub fn pitch_drift_env(amp_env: envelope::Envelope, hz: f64) -> Dynamic {
+        Dynamic::PitchDriftEnv(PitchDriftEnv { amp_env: amp_env, hz: hz })
+    }
+    /// Construct an lfo.
+    pub fn lfo(depth: f32, hz: f64) -> Dynamic {
+        Dynamic::Lfo(Lfo { depth: depth, hz: hz })
+    }
+    /// Construct an lfo whose rate is synced to the given tempo.
+    pub fn synced_lfo(division: f64, bpm: time::Bpm, depth: f32) -> Dynamic {
+        Dynamic::Lfo(Lfo::synced(division, bpm, depth))
+    }
 }
 
 
+impl<T> FreqWarp for Box<T>
+    where T: ?Sized + FreqWarp,
+{
+    #[inline]
+    fn step_phase(&self, sample_hz: f64, freq_warp_phase: &mut f64) {
+        (**self).step_phase(sample_hz, freq_warp_phase)
+    }
+    #[inline]
+    fn warp_hz(&self, hz: f64, freq_warp_phase: f64) -> f64 {
+        (**self).warp_hz(hz, freq_warp_phase)
+    }
+    #[inline]
+    fn warp_hz_at(&self, hz: f64, freq_warp_phase: f64, playhead_perc: f64) -> f64 {
+        (**self).warp_hz_at(hz, freq_warp_phase, playhead_perc)
+    }
+}
+
 impl FreqWarp for () {
     #[inline]
     fn warp_hz(&self, hz: f64, _freq_warp_phase: f64) -> f64 { hz }
@@ -64,6 +151,45 @@ impl FreqWarp for Gaussian {
     }
 }
 
+impl SeededGaussian {
+    /// A standard normal random number for the given step of the seeded sequence.
+    fn normal(&self, step: u64) -> f64 {
+        // Two uniform numbers in (0, 1] via splitmix64, mapped to a normal via Box-Muller.
+        fn uniform(mut x: u64) -> f64 {
+            x = x.wrapping_add(0x9E3779B97F4A7C15);
+            x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+            x = x ^ (x >> 31);
+            ((x >> 11) as f64 + 1.0) / (1u64 << 53) as f64
+        }
+        let key = self.seed ^ step.wrapping_mul(2);
+        let u1 = uniform(key);
+        let u2 = uniform(key ^ 1);
+        (-2.0 * u1.ln()).sqrt() * (2.0 * ::std::f64::consts::PI * u2).cos()
+    }
+}
+
+impl FreqWarp for SeededGaussian {
+    /// The phase counts the steps taken through the seeded sequence.
+    #[inline]
+    fn step_phase(&self, _sample_hz: f64, freq_warp_phase: &mut f64) {
+        *freq_warp_phase += 1.0;
+    }
+    #[inline]
+    fn warp_hz(&self, hz: f64, freq_warp_phase: f64) -> f64 {
+        if self.perc > 0.0 {
+            let max_mels = self.max_mels.abs();
+            let offset = (self.normal(freq_warp_phase as u64) as f32 * self.perc.powf(2.0) * 1000.0)
+                .max(-max_mels)
+                .min(max_mels);
+            let mels = pitch::Hz(hz as f32).mel();
+            pitch::Mel(mels + offset).hz() as f64
+        } else {
+            hz
+        }
+    }
+}
+
 impl FreqWarp for PitchDrift {
     #[inline]
     fn step_phase(&self, sample_hz: f64, freq_warp_phase: &mut f64) {
@@ -77,12 +203,56 @@ impl FreqWarp for PitchDrift {
     }
 }
 
+impl FreqWarp for PitchDriftEnv {
+    #[inline]
+    fn step_phase(&self, sample_hz: f64, freq_warp_phase: &mut f64) {
+        *freq_warp_phase = *freq_warp_phase + self.hz / sample_hz;
+    }
+    /// The depth at the start of the playhead.
+    #[inline]
+    fn warp_hz(&self, hz: f64, freq_warp_phase: f64) -> f64 {
+        self.warp_hz_at(hz, freq_warp_phase, 0.0)
+    }
+    #[inline]
+    fn warp_hz_at(&self, hz: f64, freq_warp_phase: f64, playhead_perc: f64) -> f64 {
+        let amp = self.amp_env.y_clamped(playhead_perc) as f32;
+        PitchDrift { hz: self.hz, amp: amp }.warp_hz(hz, freq_warp_phase)
+    }
+}
+
+impl Lfo {
+    /// Construct an lfo whose rate is synced to the given tempo.
+    ///
+    /// The `division` is the length of one cycle as a fraction of a whole note, e.g. `0.25` for a
+    /// quarter note or `0.125` for an eighth note, where each beat is a quarter note.
+    pub fn synced(division: f64, bpm: time::Bpm, depth: f32) -> Lfo {
+        let cycle_ms = time::Beats(division * 4.0).ms(bpm);
+        let hz = if cycle_ms > 0.0 { 1_000.0 / cycle_ms } else { 0.0 };
+        Lfo { hz: hz, depth: depth }
+    }
+}
+
+impl FreqWarp for Lfo {
+    #[inline]
+    fn step_phase(&self, sample_hz: f64, freq_warp_phase: &mut f64) {
+        *freq_warp_phase = *freq_warp_phase + self.hz / sample_hz;
+    }
+    #[inline]
+    fn warp_hz(&self, hz: f64, freq_warp_phase: f64) -> f64 {
+        let offset_in_steps = waveform::Sine.amp_at_phase(freq_warp_phase) * self.depth;
+        pitch::Step(pitch::Hz(hz as f32).step() + offset_in_steps).hz() as f64
+    }
+}
+
 impl FreqWarp for Dynamic {
     #[inline]
     fn step_phase(&self, sample_hz: f64, freq_warp_phase: &mut f64) {
         match *self {
             Dynamic::None | Dynamic::Gaussian(_) => (),
             Dynamic::PitchDrift(ref pitch_drift) => pitch_drift.step_phase(sample_hz, freq_warp_phase),
+            Dynamic::SeededGaussian(ref gaussian) => gaussian.step_phase(sample_hz, freq_warp_phase),
+            Dynamic::PitchDriftEnv(ref drift) => drift.step_phase(sample_hz, freq_warp_phase),
+            Dynamic::Lfo(ref lfo) => lfo.step_phase(sample_hz, freq_warp_phase),
         }
     }
     #[inline]
@@ -91,6 +261,82 @@ impl FreqWarp for Dynamic {
             Dynamic::None => hz,
             Dynamic::Gaussian(ref gaussian) => gaussian.warp_hz(hz, freq_warp_phase),
             Dynamic::PitchDrift(ref pitch_drift) => pitch_drift.warp_hz(hz, freq_warp_phase),
+            Dynamic::SeededGaussian(ref gaussian) => gaussian.warp_hz(hz, freq_warp_phase),
+            Dynamic::PitchDriftEnv(ref drift) => drift.warp_hz(hz, freq_warp_phase),
+            Dynamic::Lfo(ref lfo) => lfo.warp_hz(hz, freq_warp_phase),
+        }
+    }
+    #[inline]
+    fn warp_hz_at(&self, hz: f64, freq_warp_phase: f64, playhead_perc: f64) -> f64 {
+        match *self {
+            Dynamic::PitchDriftEnv(ref drift) => drift.warp_hz_at(hz, freq_warp_phase, playhead_perc),
+            ref other => other.warp_hz(hz, freq_warp_phase),
         }
     }
 }
+
+
+#[test]
+fn test_seeded_gaussian() {
+    let render = |warp: &SeededGaussian| {
+        let mut phase = 0.0;
+        (0..1_000).map(|_| {
+            warp.step_phase(44_100.0, &mut phase);
+            warp.warp_hz(440.0, phase)
+        }).collect::<Vec<_>>()
+    };
+
+    // The same seed produces the same warping, while different seeds differ.
+    let warp = SeededGaussian { perc: 0.5, seed: 42, max_mels: 1_000.0 };
+    assert_eq!(render(&warp), render(&warp));
+    assert!(render(&warp) != render(&SeededGaussian { seed: 7, ..warp }));
+
+    // The warp never exceeds the maximum offset.
+    let warp = SeededGaussian { perc: 1.0, seed: 42, max_mels: 10.0 };
+    let mels = pitch::Hz(440.0).mel();
+    for hz in render(&warp) {
+        let offset = pitch::Hz(hz as f32).mel() - mels;
+        assert!(offset.abs() <= 10.0 + 1e-3);
+    }
+}
+
+#[test]
+fn test_pitch_drift_env() {
+    use envelope::Point;
+
+    // The largest offset in steps from the given hz over a window of the playhead.
+    let max_drift = |warp: &Dynamic, playhead_perc: f64| {
+        let hz = 440.0;
+        let step = pitch::Hz(hz as f32).step();
+        let mut phase = 0.0;
+        (0..1_000).fold(0.0f32, |max, _| {
+            warp.step_phase(1_000.0, &mut phase);
+            let warped = warp.warp_hz_at(hz, phase, playhead_perc);
+            max.max((pitch::Hz(warped as f32).step() - step).abs())
+        })
+    };
+    let env = vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0)].into();
+    let warp = Dynamic::pitch_drift_env(env, 10.0);
+    let start = max_drift(&warp, 0.0);
+    let middle = max_drift(&warp, 0.5);
+    let end = max_drift(&warp, 1.0);
+    assert!(start < 1e-3);
+    assert!(start < middle && middle < end);
+    assert!(end <= 1.0 + 1e-3);
+}
+
+#[test]
+fn test_synced_lfo() {
+    // A quarter note at 120 BPM lasts half a second.
+    let lfo = Lfo::synced(0.25, 120.0, 1.0);
+    assert!((lfo.hz - 2.0).abs() < 1e-9);
+    assert!((Lfo::synced(0.125, 120.0, 1.0).hz - 4.0).abs() < 1e-9);
+
+    // The pitch peaks a quarter of the way through each cycle.
+    let mut phase = 0.0;
+    for _ in 0..250 {
+        lfo.step_phase(1_000.0, &mut phase);
+    }
+    let step = pitch::Hz(lfo.warp_hz(440.0, phase) as f32).step();
+    assert!((step - pitch::Hz(440.0).step() - 1.0).abs() < 1e-3);
+}
@@ -6,6 +6,8 @@ use pitch;
 pub trait Frequency {
     /// Return the frequency given some playhead percentage through the duration of the Synth.
     /// - 0.0 < perc < 1.0l
+    ///
+    /// Implementations should not panic for a `perc` outside of this range.
     fn hz_at_playhead(&self, perc: f64) -> f64;
     /// Return the frequency as a percentage.
     #[inline]
@@ -17,11 +19,27 @@ pub trait Frequency {
 /// Alias for the Envelope used.
 pub type Envelope = envelope::Envelope;
 
+/// A sequence of notes, each reached at some percentage of the playhead.
+///
+/// Between two notes the frequency either holds the earlier note (stepped) or glides from one note
+/// to the next. Gliding is interpolated in pitch rather than hz so that the glide is heard at a
+/// constant rate.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct NoteSequence {
+    /// The playhead percentage and frequency of each note, ordered by playhead percentage.
+    pub notes: Vec<(f64, pitch::calc::Hz)>,
+    /// Whether to glide between successive notes rather than stepping.
+    pub glide: bool,
+}
+
 /// A type that allows dynamically switching between constant and enveloped frequency.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub enum Dynamic {
     Envelope(Envelope),
     Hz(f64),
+    NoteSequence(NoteSequence),
 }
 
 
@@ -48,6 +66,9 @@ impl Dynamic {
 
     /// Convert the dynamic to its Hz variant.
     pub fn to_hz(&self) -> Dynamic {
+        if let Dynamic::NoteSequence(ref seq) = *self {
+            return Dynamic::Hz(seq.hz_at_playhead(0.0));
+        }
         if let Dynamic::Envelope(ref env) = *self {
             use pitch::{LetterOctave, Letter};
             // Just convert the first point to the constant Hz.
@@ -61,6 +82,19 @@ impl Dynamic {
 }
 
 
+impl<T> Frequency for Box<T>
+    where T: ?Sized + Frequency,
+{
+    #[inline]
+    fn hz_at_playhead(&self, perc: f64) -> f64 {
+        (**self).hz_at_playhead(perc)
+    }
+    #[inline]
+    fn freq_perc_at_playhead(&self, perc: f64) -> f64 {
+        (**self).freq_perc_at_playhead(perc)
+    }
+}
+
 impl Frequency for f64 {
     #[inline]
     fn hz_at_playhead(&self, _perc: f64) -> f64 { *self }
@@ -73,8 +107,35 @@ impl Frequency for Envelope {
     }
     #[inline]
     fn freq_perc_at_playhead(&self, perc: f64) -> f64 {
-        envelope::Trait::y(self, perc)
-            .expect("The given playhead position is out of range (0.0..1.0).")
+        self.y_clamped(perc)
+    }
+}
+
+impl Frequency for NoteSequence {
+    #[inline]
+    fn hz_at_playhead(&self, perc: f64) -> f64 {
+        use pitch::{LetterOctave, Letter};
+        let notes = &self.notes;
+        // The index of the first note that lies beyond the playhead.
+        let idx = match notes.iter().position(|&(note_perc, _)| note_perc > perc) {
+            Some(idx) => idx,
+            None => match notes.last() {
+                Some(&(_, hz)) => return hz as f64,
+                None => return LetterOctave(Letter::C, 1).hz() as f64,
+            },
+        };
+        if idx == 0 {
+            return notes[0].1 as f64;
+        }
+        let (start_perc, start_hz) = notes[idx - 1];
+        if !self.glide {
+            return start_hz as f64;
+        }
+        let (end_perc, end_hz) = notes[idx];
+        let lerp = (perc - start_perc) / (end_perc - start_perc);
+        let start_step = pitch::Hz(start_hz).step() as f64;
+        let end_step = pitch::Hz(end_hz).step() as f64;
+        pitch::Step((start_step + (end_step - start_step) * lerp) as f32).hz() as f64
     }
 }
 
@@ -84,6 +145,37 @@ impl Frequency for Dynamic {
         match *self {
             Dynamic::Envelope(ref env) => env.hz_at_playhead(perc),
             Dynamic::Hz(hz) => hz,
+            Dynamic::NoteSequence(ref seq) => seq.hz_at_playhead(perc),
         }
     }
 }
+
+
+#[test]
+fn test_envelope_out_of_range() {
+    use envelope::Point;
+    let env: Envelope = vec![Point::new(0.0, 0.25, 0.0), Point::new(1.0, 0.75, 0.0)].into();
+    assert_eq!(env.freq_perc_at_playhead(-0.1), 0.25);
+    assert_eq!(env.freq_perc_at_playhead(1.1), 0.75);
+}
+
+#[test]
+fn test_note_sequence() {
+    use pitch::{LetterOctave, Letter};
+    let c2 = LetterOctave(Letter::C, 2).hz();
+    let c3 = LetterOctave(Letter::C, 3).hz();
+    let g2 = LetterOctave(Letter::G, 2).hz();
+    let mut seq = NoteSequence { notes: vec![(0.0, c2), (0.5, g2), (1.0, c3)], glide: false };
+
+    // Stepped sequences hold each note until the next.
+    assert_eq!(seq.hz_at_playhead(0.0), c2 as f64);
+    assert_eq!(seq.hz_at_playhead(0.25), c2 as f64);
+    assert_eq!(seq.hz_at_playhead(0.75), g2 as f64);
+    assert_eq!(seq.hz_at_playhead(1.5), c3 as f64);
+
+    // Glided sequences reach the pitch halfway between two notes at their midpoint.
+    seq.notes = vec![(0.0, c2), (1.0, c3)];
+    seq.glide = true;
+    let mid = LetterOctave(Letter::Gb, 2).hz() as f64;
+    assert!((seq.hz_at_playhead(0.5) - mid).abs() < 0.01);
+}
@@ -16,9 +16,16 @@ pub mod amplitude;
 pub mod frequency;
 pub mod freq_warp;
 
+/// The number of cycles after which the phase of each `State` wraps back to zero.
+///
+/// Wrapping keeps the phase small enough to retain its precision over long renders. The phase is
+/// wrapped after a whole number of cycles rather than every cycle, as waveforms such as
+/// `NoiseWalk` read beyond a single cycle and would otherwise repeat on every cycle.
+pub const PHASE_WRAP: f64 = 1_024.0;
 
 /// The fundamental component of a synthesizer.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct Oscillator<W, A, F, FW> {
     /// Waveform used for phase movement.
     pub waveform: W,
@@ -30,19 +37,49 @@ pub struct Oscillator<W, A, F, FW> {
     pub freq_warp: FW,
     /// Whether or not the Oscillator is currently muted.
     pub is_muted: bool,
+    /// The ratio of the Oscillator's frequency to the frequency of the played note.
+    pub ratio: f64,
+    /// An absolute frequency that, if set, is used regardless of the played note.
+    pub fixed_hz: Option<f64>,
+    /// Whether or not the Oscillator keeps its phase when a voice is reset for a new note.
+    pub free_running: bool,
+    /// An offset added to the phase before the waveform is read, wrapped to a single cycle.
+    pub phase_offset: f64,
+    /// The duration in milliseconds over which a waveform change made via `set_waveform` is
+    /// crossfaded. A duration of `0.0` switches immediately.
+    pub waveform_crossfade_ms: f64,
+    /// The waveform being crossfaded out, if a crossfade is in progress.
+    pub previous_waveform: Option<W>,
+    /// The progress of the crossfade from the `previous_waveform`, from `0.0` to `1.0`.
+    pub waveform_fade: f64,
+    /// Whether the right channel of a stereo output reads the waveform a quarter of a cycle ahead
+    /// of the left.
+    pub quadrature: bool,
 }
 
 /// The state of an Oscillator that is unique to each voice playing it.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct State {
-    /// The Oscillator's current phase.
+    /// The Oscillator's current phase, wrapped to the range `0.0..PHASE_WRAP`.
     pub phase: f64,
     /// The phase of the FreqWarp used to warp the oscillator's frequency.
     pub freq_warp_phase: f64,
+    /// The state of the random number generator used by noise waveforms, if the voice is seeded.
+    ///
+    /// When `None`, noise waveforms draw from the global `rand::random` instead.
+    pub rng: Option<u64>,
+    /// Whether the note played by the voice is still held, allowing the amplitude to sustain.
+    pub note_held: bool,
+    /// The frequency at which the phase was stepped during the previous frame, if any.
+    pub last_hz: Option<f64>,
+    /// The greatest change in frequency allowed between successive frames in hz, if limited.
+    pub max_hz_per_sample: Option<f64>,
 }
 
 /// The state of each oscillator per-voice.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct StatePerVoice(pub Vec<State>);
 
 
@@ -51,8 +88,38 @@ impl State {
         State {
             phase: 0.0,
             freq_warp_phase: 0.0,
+            rng: None,
+            note_held: false,
+            last_hz: None,
+            max_hz_per_sample: None,
         }
     }
+
+    /// Limit the change from the `last_hz` to the given `hz` to the `max_hz_per_sample`, returning
+    /// the frequency to use for the frame.
+    #[inline]
+    fn slew_hz(&mut self, hz: f64) -> f64 {
+        let hz = match (self.last_hz, self.max_hz_per_sample) {
+            (Some(last_hz), Some(max)) => hz.max(last_hz - max).min(last_hz + max),
+            _ => hz,
+        };
+        self.last_hz = Some(hz);
+        hz
+    }
+
+    /// The oscillator's current phase wrapped to the range `0.0..1.0`.
+    #[inline]
+    pub fn normalized_phase(&self) -> f64 {
+        ::utils::fmod(self.phase, 1.0)
+    }
+}
+
+impl StatePerVoice {
+    /// The phase of the oscillator at the given index wrapped to the range `0.0..1.0`, if there is
+    /// one.
+    pub fn normalized_phase(&self, osc_idx: usize) -> Option<f64> {
+        self.0.get(osc_idx).map(State::normalized_phase)
+    }
 }
 
 impl<W, A, F, FW> Oscillator<W, A, F, FW> {
@@ -66,6 +133,14 @@ impl<W, A, F, FW> Oscillator<W, A, F, FW> {
             frequency: frequency,
             freq_warp: freq_warp,
             is_muted: false,
+            ratio: 1.0,
+            fixed_hz: None,
+            free_running: false,
+            phase_offset: 0.0,
+            waveform_crossfade_ms: 0.0,
+            previous_waveform: None,
+            waveform_fade: 0.0,
+            quadrature: false,
         }
     }
 
@@ -90,13 +165,171 @@ impl<W, A, F, FW> Oscillator<W, A, F, FW> {
         self
     }
 
-    /// Calculate and return the amplitude at the given ratio.
+    /// Frequency ratio builder method.
+    ///
+    /// The Oscillator's frequency is multiplied by the given ratio, tuning it relative to the
+    /// played note (i.e. `2.0` plays an octave above). This is useful for FM-style and additive
+    /// patches where each oscillator acts as an operator.
+    #[inline]
+    pub fn ratio(mut self, ratio: f64) -> Self {
+        self.ratio = ratio;
+        self
+    }
+
+    /// Fixed frequency builder method.
+    ///
+    /// The Oscillator will run at the given frequency regardless of the played note, ignoring the
+    /// frequency envelope and `ratio`. This is useful for fixed modulators in FM-style patches.
+    #[inline]
+    pub fn fixed(mut self, hz: f64) -> Self {
+        self.fixed_hz = Some(hz);
+        self
+    }
+
+    /// Free-running builder method.
+    ///
+    /// A free-running Oscillator continues from its current phase when a voice starts a new note
+    /// rather than returning to the voice's starting phase. This is useful for sub oscillators
+    /// and noise that should not restart with every note.
+    #[inline]
+    pub fn free_running(mut self, free_running: bool) -> Self {
+        self.free_running = free_running;
+        self
+    }
+
+    /// Phase offset builder method.
+    ///
+    /// The offset is added to the phase before the waveform is read, allowing fixed phase
+    /// relationships between oscillators (e.g. `0.25` turns a sine into a cosine). The offset is
+    /// wrapped to the range `0.0..1.0`.
+    #[inline]
+    pub fn phase_offset(mut self, phase_offset: f64) -> Self {
+        self.phase_offset = phase_offset;
+        self
+    }
+
+    /// Waveform crossfade builder method.
+    ///
+    /// Changes of waveform made via `set_waveform` are crossfaded with constant power over the
+    /// given duration, avoiding the click of switching waveforms mid-note.
+    #[inline]
+    pub fn waveform_crossfade_ms(mut self, ms: f64) -> Self {
+        self.waveform_crossfade_ms = ms;
+        self
+    }
+
+    /// Quadrature builder method.
+    ///
+    /// For stereo output, the right channel reads the waveform a quarter of a cycle ahead of the
+    /// left, placing the channels 90 degrees apart (i.e. a sine on the left and a cosine on the
+    /// right). This widens the oscillator without detuning it. Other outputs are unaffected.
+    #[inline]
+    pub fn quadrature(mut self, quadrature: bool) -> Self {
+        self.quadrature = quadrature;
+        self
+    }
+
+    /// Switch to the given waveform, crossfading from the current waveform over the
+    /// `waveform_crossfade_ms`.
+    ///
+    /// If a crossfade is already in progress, the fade restarts from the current waveform.
+    pub fn set_waveform(&mut self, waveform: W) {
+        let previous = ::std::mem::replace(&mut self.waveform, waveform);
+        if self.waveform_crossfade_ms > 0.0 {
+            self.previous_waveform = Some(previous);
+            self.waveform_fade = 0.0;
+        } else {
+            self.previous_waveform = None;
+        }
+    }
+
+    /// Step any waveform crossfade forward by a single frame.
+    ///
+    /// This should be called once per frame rather than once per voice, as the crossfade is shared
+    /// by every voice playing the oscillator.
+    #[inline]
+    pub fn step_waveform_fade(&mut self, sample_hz: time::SampleHz) {
+        if self.previous_waveform.is_some() {
+            self.waveform_fade += 1_000.0 / (self.waveform_crossfade_ms * sample_hz);
+            if !(self.waveform_fade < 1.0) {
+                self.previous_waveform = None;
+                self.waveform_fade = 0.0;
+            }
+        }
+    }
+
+    /// The given phase with the `phase_offset` applied.
+    #[inline]
+    fn offset_phase(&self, phase: f64) -> f64 {
+        phase + ::utils::fmod(self.phase_offset, 1.0)
+    }
+
+    /// The frequency before it is scaled by the played note.
+    #[inline]
+    fn hz_at_playhead(&self, playhead_perc: f64) -> f64
+        where F: Frequency,
+    {
+        match self.fixed_hz {
+            Some(hz) => hz,
+            None => self.frequency.hz_at_playhead(playhead_perc),
+        }
+    }
+
+    /// The multiplier applied to the frequency for the played note.
+    #[inline]
+    fn freq_multi(&self, note_freq_multi: f64) -> f64 {
+        match self.fixed_hz {
+            Some(_) => 1.0,
+            None => note_freq_multi * self.ratio,
+        }
+    }
+
+    /// The frequency of the oscillator in hz at the given playhead percentage for a note with the
+    /// given frequency multiplier.
+    ///
+    /// The frequency envelope, the waveform's `process_hz`, the `ratio` and any `fixed` frequency
+    /// are applied, but the `freq_warp` is not. This is useful for checking that a frequency
+    /// envelope produces the intended pitch contour.
+    #[inline]
+    pub fn hz_at(&self, playhead_perc: f64, note_freq_multi: f64) -> f64
+        where W: Waveform,
+              F: Frequency,
+    {
+        let hz = self.waveform.process_hz(self.hz_at_playhead(playhead_perc));
+        hz * self.freq_multi(note_freq_multi)
+    }
+
+    /// Calculate and return the amplitude at the given phase and playhead percentage.
+    ///
+    /// Both the phase and playhead are passed to `Waveform::amp_at`.
     #[inline]
     pub fn amp_at(&self, phase: f64, playhead_perc: f64) -> f32
         where A: Amplitude,
               W: Waveform,
     {
-        self.waveform.amp_at_phase(phase) * self.amplitude.amp_at_playhead(playhead_perc)
+        self.waveform.amp_at(self.offset_phase(phase), playhead_perc)
+            * self.amplitude.amp_at_playhead(playhead_perc)
+    }
+
+    /// The waveform's amplitude at the given state's phase, drawing from the state's random number
+    /// generator if it has one.
+    ///
+    /// While a waveform crossfade is in progress, the `previous_waveform` is blended in with
+    /// constant power.
+    #[inline]
+    fn waveform_amp_at(&self, state: &mut State, playhead_perc: f64) -> f32
+        where W: Waveform,
+    {
+        let phase = self.offset_phase(state.phase);
+        let amp = seeded_amp_at(&self.waveform, phase, playhead_perc, &mut state.rng);
+        match self.previous_waveform {
+            Some(ref previous) => {
+                let previous_amp = seeded_amp_at(previous, phase, playhead_perc, &mut state.rng);
+                let theta = self.waveform_fade.max(0.0).min(1.0) * ::std::f64::consts::PI * 0.5;
+                amp * theta.sin() as f32 + previous_amp * theta.cos() as f32
+            },
+            None => amp,
+        }
     }
 
     /// Calculate and return the phase that should follow some given phase.
@@ -111,12 +344,27 @@ impl<W, A, F, FW> Oscillator<W, A, F, FW> {
               F: Frequency,
               FW: FreqWarp,
     {
-        let hz = self.frequency.hz_at_playhead(playhead_perc);
+        let note_hz =
+            self.next_frame_hz(sample_hz, playhead_perc, note_freq_multi, freq_warp_phase);
+        wrap_phase(phase + (note_hz / sample_hz))
+    }
+
+    /// Step forward the given `freq_warp_phase` and return the frequency of the next frame.
+    #[inline]
+    fn next_frame_hz(&self,
+                     sample_hz: f64,
+                     playhead_perc: f64,
+                     note_freq_multi: f64,
+                     freq_warp_phase: &mut f64) -> f64
+        where W: Waveform,
+              F: Frequency,
+              FW: FreqWarp,
+    {
+        let hz = self.hz_at_playhead(playhead_perc);
         let hz = self.waveform.process_hz(hz);
         self.freq_warp.step_phase(sample_hz, freq_warp_phase);
-        let warped_hz = self.freq_warp.warp_hz(hz, *freq_warp_phase);
-        let note_hz = warped_hz * note_freq_multi;
-        phase + (note_hz / sample_hz)
+        let warped_hz = self.freq_warp.warp_hz_at(hz, *freq_warp_phase, playhead_perc);
+        warped_hz * self.freq_multi(note_freq_multi)
     }
 
     /// Steps forward the given `phase` and `freq_warp_phase` and yields the amplitude for the
@@ -132,14 +380,388 @@ impl<W, A, F, FW> Oscillator<W, A, F, FW> {
               F: Frequency,
               FW: FreqWarp,
     {
-        let amp = self.amp_at(state.phase, playhead_perc);
-        let next_phase = self.next_frame_phase(sample_hz,
-                                               playhead_perc,
-                                               note_freq_multi,
-                                               state.phase,
-                                               &mut state.freq_warp_phase);
-        state.phase = next_phase;
+        let amp = self.waveform_amp_at(state, playhead_perc)
+            * self.amplitude.amp_at_playhead_held(playhead_perc, state.note_held);
+        let note_hz = self.next_frame_hz(sample_hz,
+                                         playhead_perc,
+                                         note_freq_multi,
+                                         &mut state.freq_warp_phase);
+        state.phase = wrap_phase(state.phase + state.slew_hz(note_hz) / sample_hz);
         amp
     }
 
+    /// Steps forward the given `state` exactly as `next_frame_amp` does, yielding the amplitude of
+    /// the next frame for both the left and right channels of a stereo output.
+    ///
+    /// The right channel is read a quarter of a cycle ahead of the left if the oscillator is in
+    /// `quadrature`, and is otherwise equal to the left.
+    #[inline]
+    pub fn next_frame_amp_stereo(&mut self,
+                                 sample_hz: time::SampleHz,
+                                 playhead_perc: f64,
+                                 note_freq_multi: f64,
+                                 state: &mut State) -> (f32, f32)
+        where A: Amplitude,
+              W: Waveform,
+              F: Frequency,
+              FW: FreqWarp,
+    {
+        let right = if self.quadrature {
+            let mut quadrature_state = *state;
+            quadrature_state.phase += 0.25;
+            let amp = self.waveform_amp_at(&mut quadrature_state, playhead_perc)
+                * self.amplitude.amp_at_playhead_held(playhead_perc, state.note_held);
+            Some(amp)
+        } else {
+            None
+        };
+        let left = self.next_frame_amp(sample_hz, playhead_perc, note_freq_multi, state);
+        (left, right.unwrap_or(left))
+    }
+
+    /// Fill the given block with the amplitude of each successive frame, stepping the given
+    /// `state` forward as successive calls to `next_frame_amp` would.
+    ///
+    /// The amplitude and frequency envelopes are only looked up for the first and last frames of
+    /// the block and are linearly interpolated in between, hoisting the envelope lookups out of
+    /// the per-frame loop. The result matches `next_frame_amp` exactly while the envelopes are
+    /// constant and closely follows it otherwise, so long as blocks are kept short (e.g. 64
+    /// frames).
+    ///
+    /// - `playhead_start_perc` is the playhead position of the first frame in the block.
+    /// - `perc_delta` is the distance the playhead moves each frame.
+    pub fn fill_amp_block(&mut self,
+                          out: &mut [f32],
+                          sample_hz: time::SampleHz,
+                          playhead_start_perc: f64,
+                          perc_delta: f64,
+                          note_freq_multi: f64,
+                          state: &mut State)
+        where A: Amplitude,
+              W: Waveform,
+              F: Frequency,
+              FW: FreqWarp,
+    {
+        let len = out.len();
+        if len == 0 {
+            return;
+        }
+
+        let steps = if len > 1 { (len - 1) as f64 } else { 1.0 };
+        let playhead_end_perc = playhead_start_perc + perc_delta * (len - 1) as f64;
+        let start_amp = self.amplitude.amp_at_playhead_held(playhead_start_perc, state.note_held);
+        let end_amp = self.amplitude.amp_at_playhead_held(playhead_end_perc, state.note_held);
+        let start_hz = self.waveform.process_hz(self.hz_at_playhead(playhead_start_perc));
+        let end_hz = self.waveform.process_hz(self.hz_at_playhead(playhead_end_perc));
+        let freq_multi = self.freq_multi(note_freq_multi);
+
+        for (i, amp) in out.iter_mut().enumerate() {
+            let lerp = i as f64 / steps;
+            let playhead_perc = playhead_start_perc + perc_delta * i as f64;
+            let env_amp = start_amp + (end_amp - start_amp) * lerp as f32;
+            let hz = start_hz + (end_hz - start_hz) * lerp;
+            *amp = self.waveform_amp_at(state, playhead_perc) * env_amp;
+            self.freq_warp.step_phase(sample_hz, &mut state.freq_warp_phase);
+            let warped_hz = self.freq_warp.warp_hz_at(hz, state.freq_warp_phase, playhead_perc);
+            let hz = state.slew_hz(warped_hz * freq_multi);
+            state.phase = wrap_phase(state.phase + hz / sample_hz);
+        }
+    }
+
+    /// Render the oscillator into a new buffer of `num_samples` samples using a fresh `State`.
+    ///
+    /// The playhead moves across the envelopes over the length of the buffer. The frequency
+    /// envelope is scaled so that the oscillator begins at `freq_hz` once the waveform's
+    /// `process_hz` has been applied (as in `hz_at`), while an oscillator with a `fixed` frequency
+    /// ignores `freq_hz`. This is useful for testing and analysing an oscillator
+    /// in isolation from the `Synth`.
+    pub fn render(&self, sample_hz: time::SampleHz, freq_hz: f64, num_samples: usize) -> Vec<f32>
+        where A: Amplitude,
+              W: Waveform,
+              F: Frequency,
+              FW: FreqWarp,
+    {
+        let start_hz = self.hz_at(0.0, 1.0);
+        let note_freq_multi = if start_hz != 0.0 { freq_hz / start_hz } else { 0.0 };
+        let mut state = State::new();
+        (0..num_samples).map(|i| {
+            let playhead_perc = i as f64 / num_samples as f64;
+            let amp = self.waveform_amp_at(&mut state, playhead_perc)
+                * self.amplitude.amp_at_playhead_held(playhead_perc, state.note_held);
+            state.phase = self.next_frame_phase(sample_hz,
+                                                playhead_perc,
+                                                note_freq_multi,
+                                                state.phase,
+                                                &mut state.freq_warp_phase);
+            amp
+        }).collect()
+    }
+
+}
+
+
+/// Wrap the given phase to the range `0.0..PHASE_WRAP`.
+#[inline]
+fn wrap_phase(phase: f64) -> f64 {
+    ::utils::fmod(phase, PHASE_WRAP)
+}
+
+/// The amplitude of the given waveform, drawing from the random number generator if there is one.
+#[inline]
+fn seeded_amp_at<W>(waveform: &W, phase: f64, playhead_perc: f64, rng: &mut Option<u64>) -> f32
+    where W: Waveform,
+{
+    match *rng {
+        Some(ref mut rng) => waveform.amp_at_seeded(phase, playhead_perc, rng),
+        None => waveform.amp_at(phase, playhead_perc),
+    }
+}
+
+
+#[test]
+fn test_ratio() {
+    let osc = Oscillator::new(waveform::Sine, 1.0, 100.0, ());
+    let mut freq_warp_phase = 0.0;
+    let phase = osc.next_frame_phase(1_000.0, 0.0, 2.0, 0.0, &mut freq_warp_phase);
+    let ratio_phase = osc.ratio(1.5).next_frame_phase(1_000.0, 0.0, 2.0, 0.0, &mut freq_warp_phase);
+    assert_eq!(phase, 0.2);
+    assert_eq!(ratio_phase, 0.3);
+}
+
+#[test]
+fn test_fixed() {
+    let osc = Oscillator::new(waveform::Sine, 1.0, 100.0, ()).fixed(50.0);
+    let mut freq_warp_phase = 0.0;
+    for &note_freq_multi in [0.5, 1.0, 4.0].iter() {
+        let phase = osc.next_frame_phase(1_000.0, 0.0, note_freq_multi, 0.0, &mut freq_warp_phase);
+        assert_eq!(phase, 0.05);
+    }
+}
+
+#[test]
+fn test_playhead_waveform() {
+    use self::waveform::{Morph, Saw, Sine};
+
+    // A waveform that blends from a sine into a saw across the duration.
+    struct SineToSaw;
+
+    impl Waveform for SineToSaw {
+        fn amp_at_phase(&self, phase: f64) -> f32 {
+            Sine.amp_at_phase(phase)
+        }
+        fn amp_at(&self, phase: f64, playhead_perc: f64) -> f32 {
+            Morph { a: Sine, b: Saw, mix: playhead_perc as f32 }.amp_at_phase(phase)
+        }
+    }
+
+    let osc = Oscillator::new(SineToSaw, 1.0, 100.0, ());
+    for &phase in [0.1, 0.25, 0.6, 0.9].iter() {
+        assert_eq!(osc.amp_at(phase, 0.0), Sine.amp_at_phase(phase));
+        assert_eq!(osc.amp_at(phase, 1.0), Saw.amp_at_phase(phase));
+        let mid = (Sine.amp_at_phase(phase) + Saw.amp_at_phase(phase)) * 0.5;
+        assert!((osc.amp_at(phase, 0.5) - mid).abs() < 1e-6);
+
+        // Wrapping waveforms forward the playhead.
+        let morph = Morph { a: SineToSaw, b: SineToSaw, mix: 0.5 };
+        assert_eq!(morph.amp_at(phase, 1.0), Saw.amp_at_phase(phase));
+    }
+}
+
+#[test]
+fn test_render() {
+    use envelope::Point;
+
+    // A 1hz sine crosses zero once in the middle of a one second buffer.
+    let osc = Oscillator::new(waveform::Sine, 1.0, 440.0, ());
+    let buffer = osc.render(1_000.0, 1.0, 1_000);
+    assert_eq!(buffer.len(), 1_000);
+    let crossings = buffer[1..].windows(2).filter(|w| (w[0] < 0.0) != (w[1] < 0.0)).count();
+    assert_eq!(crossings, 1);
+    assert!(buffer[250] > 0.99 && buffer[750] < -0.99);
+
+    // The amplitude envelope is spread across the buffer.
+    let env: AmpEnvelope = vec![Point::new(0.0, 1.0, 0.0), Point::new(1.0, 0.0, 0.0)].into();
+    let osc = Oscillator::new(waveform::Sine, env, 440.0, ());
+    let buffer = osc.render(1_000.0, 1.0, 1_000);
+    assert!((buffer[250] - 0.75).abs() < 1e-3);
+}
+
+#[test]
+fn test_fill_amp_block() {
+    use envelope::Point;
+
+    // Render `num_frames` frames both a frame at a time and in blocks.
+    fn render<A>(osc: &Oscillator<waveform::Sine, A, f64, ()>, num_frames: usize)
+        -> (Vec<f32>, State, Vec<f32>, State)
+        where A: Amplitude + Clone,
+    {
+        let perc_delta = 1.0 / num_frames as f64;
+        let mut per_frame_osc = osc.clone();
+        let mut per_frame_state = State::new();
+        let per_frame: Vec<f32> = (0..num_frames).map(|i| {
+            let playhead_perc = i as f64 * perc_delta;
+            per_frame_osc.next_frame_amp(44_100.0, playhead_perc, 1.5, &mut per_frame_state)
+        }).collect();
+        let mut block_osc = osc.clone();
+        let mut block_state = State::new();
+        let mut block = vec![0.0; num_frames];
+        for (i, chunk) in block.chunks_mut(64).enumerate() {
+            let playhead_perc = (i * 64) as f64 * perc_delta;
+            block_osc.fill_amp_block(chunk, 44_100.0, playhead_perc, perc_delta, 1.5,
+                                     &mut block_state);
+        }
+        (per_frame, per_frame_state, block, block_state)
+    }
+
+    // With constant envelopes the block matches the per-frame path exactly.
+    let osc = Oscillator::new(waveform::Sine, 0.5, 440.0, ());
+    let (per_frame, per_frame_state, block, block_state) = render(&osc, 1_000);
+    assert_eq!(per_frame, block);
+    assert_eq!(per_frame_state, block_state);
+
+    // An amplitude envelope is interpolated across each block.
+    let env: AmpEnvelope = vec![Point::new(0.0, 1.0, 0.0), Point::new(1.0, 0.0, 0.0)].into();
+    let osc = Oscillator::new(waveform::Sine, env, 440.0, ());
+    let (per_frame, _, block, _) = render(&osc, 1_000);
+    for (a, b) in per_frame.iter().zip(block.iter()) {
+        assert!((a - b).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_render_process_hz() {
+    use pitch;
+    use synth::Synth;
+
+    // Rendering a noise walk at the frequency a one-voice synth plays it at follows the same
+    // phase as the synth, even though `process_hz` changes the walk's frequency.
+    let osc = Oscillator::new(waveform::NoiseWalk, 1.0, 440.0, ());
+    let hz = osc.hz_at(0.0, 1.0);
+    assert!(hz != 440.0);
+    let rendered = osc.render(44_100.0, hz, 1_024);
+    let mut synth = Synth::poly(()).base_pitch(440.0).oscillator(osc);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 1]; 1_024];
+    synth.fill_slice(&mut buffer, 44_100.0);
+    let compared: Vec<_> = rendered.iter().zip(buffer.iter())
+        .filter(|&(r, s)| r.abs() > 1e-3 && s[0].abs() > 1e-3)
+        .map(|(r, s)| (*r > 0.0) == (s[0] > 0.0))
+        .collect();
+    assert!(compared.len() > rendered.len() / 2);
+    assert!(compared.iter().all(|&same_sign| same_sign));
+}
+
+#[test]
+fn test_waveform_crossfade() {
+    use self::waveform::Dynamic;
+
+    // The largest jump between successive samples of a 10hz oscillator at 1khz around a switch
+    // from a sine to a square at the peak of the sine, before the square's next edge.
+    let max_jump = |crossfade_ms: f64| {
+        let mut osc = Oscillator::new(Dynamic::Sine, 1.0, 10.0, ())
+            .waveform_crossfade_ms(crossfade_ms);
+        let mut state = State::new();
+        let mut amps = vec![];
+        for i in 0..1_000 {
+            if i == 525 {
+                osc.set_waveform(Dynamic::Square);
+            }
+            amps.push(osc.next_frame_amp(1_000.0, 0.0, 1.0, &mut state));
+            osc.step_waveform_fade(1_000.0);
+        }
+        assert_eq!(osc.waveform, Dynamic::Square);
+        assert!(osc.previous_waveform.is_none());
+        amps[520..548].windows(2).map(|w| (w[1] - w[0]).abs()).fold(0.0f32, f32::max)
+    };
+
+    // Switching instantly jumps from the peak of the sine to the trough of the square, while
+    // crossfading keeps the jump small.
+    assert!(max_jump(0.0) > 1.5);
+    assert!(max_jump(20.0) < 0.2);
+}
+
+#[test]
+fn test_phase_precision() {
+    // At 44.1khz a 441hz sine steps exactly a hundredth of a cycle each frame, so the phase of
+    // every frame is known exactly. Check that the oscillator does not drift from it over a render
+    // of many cycles.
+    let mut osc = Oscillator::new(waveform::Sine, 1.0, 441.0, ());
+    let mut state = State::new();
+    let num_frames = 2_000_000;
+    for i in 0..num_frames {
+        let expected = waveform::Sine.amp_at_phase((i % 100) as f64 / 100.0);
+        let amp = osc.next_frame_amp(44_100.0, 0.0, 1.0, &mut state);
+        assert!((amp - expected).abs() < 1e-5);
+    }
+    assert!(state.phase >= 0.0 && state.phase < PHASE_WRAP);
+    assert!((state.normalized_phase() - (num_frames % 100) as f64 / 100.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_noise_walk_wrap() {
+    use self::waveform::{NoiseWalk, NoiseWalkSmooth};
+
+    // The amplitude at the start of each of many cycles. A walk that repeated every cycle would
+    // begin each cycle at roughly the same amplitude.
+    fn cycle_start_amps<W: Waveform>(waveform: W) -> Vec<f32> {
+        let mut osc = Oscillator::new(waveform, 1.0, 100.0, ());
+        let mut state = State::new();
+        let mut amps = vec![];
+        let mut cycle_started = false;
+        for _ in 0..176_400 {
+            let prev_cycle_perc = state.normalized_phase();
+            let amp = osc.next_frame_amp(44_100.0, 0.0, 1.0, &mut state);
+            if cycle_started {
+                amps.push(amp);
+            }
+            cycle_started = state.normalized_phase() < prev_cycle_perc;
+        }
+        amps
+    }
+
+    for amps in vec![cycle_start_amps(NoiseWalk), cycle_start_amps(NoiseWalkSmooth(4.0))] {
+        assert!(amps.len() > 10);
+        let min = amps.iter().fold(::std::f32::MAX, |min, &amp| min.min(amp));
+        let max = amps.iter().fold(::std::f32::MIN, |max, &amp| max.max(amp));
+        assert!(max - min > 0.25);
+    }
+}
+
+#[test]
+fn test_hz_at() {
+    let osc = Oscillator::new(waveform::Sine, 1.0, 100.0, ());
+    for &playhead_perc in [0.0, 0.5, 1.0].iter() {
+        assert_eq!(osc.hz_at(playhead_perc, 1.0), 100.0);
+        assert_eq!(osc.hz_at(playhead_perc, 2.5), 250.0);
+    }
+    assert_eq!(osc.clone().ratio(1.5).hz_at(0.5, 2.0), 300.0);
+    assert_eq!(osc.fixed(50.0).hz_at(0.5, 2.0), 50.0);
+
+    // The waveform may process the frequency.
+    let walk = Oscillator::new(waveform::NoiseWalk, 1.0, 100.0, ());
+    assert_eq!(walk.hz_at(0.5, 1.0), waveform::NoiseWalk.process_hz(100.0));
+}
+
+#[test]
+fn test_process_hz() {
+    // Waveforms use the given frequency unless they override `process_hz`.
+    assert_eq!(waveform::Sine.process_hz(440.0), 440.0);
+    assert!(waveform::NoiseWalk.process_hz(440.0) != 440.0);
+
+    // The processed frequency determines how quickly the oscillator's phase moves.
+    let mut freq_warp_phase = 0.0;
+    let sine = Oscillator::new(waveform::Sine, 1.0, 440.0, ());
+    let sine_step = sine.next_frame_phase(44_100.0, 0.0, 1.0, 0.0, &mut freq_warp_phase);
+    let walk = Oscillator::new(waveform::NoiseWalk, 1.0, 440.0, ());
+    let walk_step = walk.next_frame_phase(44_100.0, 0.0, 1.0, 0.0, &mut freq_warp_phase);
+    assert_eq!(sine_step, 440.0 / 44_100.0);
+    assert_eq!(walk_step, waveform::NoiseWalk.process_hz(440.0) / 44_100.0);
+    assert!(walk_step != sine_step);
+
+    // The `Dynamic` waveform processes the frequency of the variant that it wraps.
+    let dynamic_walk = Oscillator::new(waveform::Dynamic::NoiseWalk, 1.0, 440.0, ());
+    let dynamic_walk_step =
+        dynamic_walk.next_frame_phase(44_100.0, 0.0, 1.0, 0.0, &mut freq_warp_phase);
+    assert_eq!(dynamic_walk_step, walk_step);
+    assert_eq!(waveform::Dynamic::Sine.process_hz(440.0), 440.0);
 }
@@ -2,10 +2,29 @@
 //! The Waveform trait along with various Waveform Types and there implementations.
 //!
 
+use envelope;
+
 /// Some type that can return an amplitude given some phase.
 pub trait Waveform {
     /// Return the amplitude given some phase.
     fn amp_at_phase(&self, phase: f64) -> f32;
+    /// Return the amplitude given some phase and the playhead's percentage through the duration.
+    ///
+    /// This is the method called by the `Oscillator` each frame. By default the playhead is
+    /// ignored and the `amp_at_phase` is returned, so existing waveforms need not implement it.
+    /// Waveforms whose shape changes across the duration (e.g. `PulseEnv`) should override it.
+    #[inline]
+    fn amp_at(&self, phase: f64, _playhead_perc: f64) -> f32 { self.amp_at_phase(phase) }
+    /// Return the amplitude given some phase, the playhead's percentage through the duration and
+    /// the state of a seeded random number generator.
+    ///
+    /// This is called in place of `amp_at` for the voices of a seeded `Synth`. By default the
+    /// generator is ignored. Waveforms that produce noise should override it and draw from the
+    /// generator so that seeded renders are reproducible.
+    #[inline]
+    fn amp_at_seeded(&self, phase: f64, playhead_perc: f64, _rng: &mut u64) -> f32 {
+        self.amp_at(phase, playhead_perc)
+    }
     /// An optional method for processing the frequency. 
     #[inline]
     fn process_hz(&self, hz: f64) -> f64 { hz }
@@ -20,6 +39,7 @@ pub type Steepness = f32;
 /// An Oscillator must use one of a variety
 /// of waveform types.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub enum Dynamic {
     /// Sine Wave
     Sine,
@@ -33,32 +53,138 @@ pub enum Dynamic {
     NoiseWalk,
     /// Exponential Saw Wave.
     SawExp(Steepness),
+    /// Noise Walk with the given smoothing.
+    NoiseWalkSmooth(f32),
 }
 
 /// A sine wave.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct Sine;
 
 /// A sawtooth wave.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct Saw;
 
 /// An exponential sawtooth wave.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct SawExp(pub Steepness);
 
 /// A square wave.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct Square;
 
+/// A pulse wave whose duty cycle is read from an envelope across the playhead.
+///
+/// The duty cycle is the fraction of each period spent at `1.0`, so a constant duty of `0.5` is
+/// equivalent to a `Square` wave. Sweeping the duty produces pulse width modulation.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct PulseEnv(pub envelope::Envelope);
+
+/// An exponential sawtooth wave whose steepness is read from an envelope across the playhead.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct SawExpEnv(pub envelope::Envelope);
+
 /// A noise signal.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct Noise;
 
 /// A random noise walk wave.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct NoiseWalk;
 
+/// A random noise walk wave whose rate of change is divided by the given smoothing.
+///
+/// A smoothing of `1.0` is equivalent to `NoiseWalk`, while greater values produce a slower drift
+/// and lesser values a faster one.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct NoiseWalkSmooth(pub f32);
+
+/// A sum of harmonically related sine waves where each element is the amplitude of a harmonic,
+/// starting with the fundamental.
+///
+/// Every harmonic requires its own call to `sin` for every frame, so this becomes expensive for
+/// large numbers of harmonics. It is best suited to pre-rendering a table (i.e. by sampling
+/// `amp_at_phase` over a single period) rather than real-time per-sample use.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct Additive(pub Vec<f32>);
+
+/// A single period of some waveform rendered into a table and read back via linear interpolation.
+///
+/// Reading from the table is far cheaper than evaluating most waveforms (e.g. calling `sin`) every
+/// frame, at the cost of memory and some fidelity at low resolutions.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct Wavetable(pub Vec<f32>);
+
+/// A `Dynamic` waveform that may be baked into a `Wavetable` via `Synth::bake_wavetables`.
+///
+/// This is kept separate from `Dynamic` so that `Dynamic` remains `Copy`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub enum Bakeable {
+    /// A waveform that is evaluated every frame.
+    Dynamic(Dynamic),
+    /// A pre-rendered period of some waveform.
+    Wavetable(Wavetable),
+}
+
+/// A crossfade between two waveforms.
+///
+/// A `mix` of `0.0` produces only `a` while a `mix` of `1.0` produces only `b`. Use
+/// `Morph<Dynamic, Dynamic>` to choose the blended waveforms at runtime.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct Morph<A, B> {
+    pub a: A,
+    pub b: B,
+    pub mix: f32,
+}
+
+
+impl Bakeable {
+    /// Render the waveform into a `Bakeable::Wavetable` with the given number of samples.
+    ///
+    /// Returns `None` for waveforms that cannot be baked: `Noise` and the noise walks are not
+    /// periodic and a `Wavetable` is already baked.
+    pub fn bake(&self, resolution: usize) -> Option<Bakeable> {
+        match *self {
+            Bakeable::Dynamic(Dynamic::Noise) |
+            Bakeable::Dynamic(Dynamic::NoiseWalk) |
+            Bakeable::Dynamic(Dynamic::NoiseWalkSmooth(_)) |
+            Bakeable::Wavetable(_) => None,
+            Bakeable::Dynamic(ref waveform) =>
+                Some(Bakeable::Wavetable(Wavetable::new(waveform, resolution))),
+        }
+    }
+}
+
+impl From<Dynamic> for Bakeable {
+    fn from(waveform: Dynamic) -> Self {
+        Bakeable::Dynamic(waveform)
+    }
+}
+
+impl Wavetable {
+    /// Render a single period of the given waveform into a table of `resolution` samples.
+    pub fn new<W>(waveform: &W, resolution: usize) -> Self
+        where W: Waveform,
+    {
+        let table = (0..resolution)
+            .map(|i| waveform.amp_at_phase(i as f64 / resolution as f64))
+            .collect();
+        Wavetable(table)
+    }
+}
 
 impl Waveform for Dynamic {
     /// Return the amplitude of a waveform at a given phase.
@@ -71,8 +197,93 @@ impl Waveform for Dynamic {
             Dynamic::Noise => Noise.amp_at_phase(phase),
             Dynamic::NoiseWalk => NoiseWalk.amp_at_phase(phase),
             Dynamic::SawExp(steepness) => SawExp(steepness).amp_at_phase(phase),
+            Dynamic::NoiseWalkSmooth(smoothing) => NoiseWalkSmooth(smoothing).amp_at_phase(phase),
+        }
+    }
+    #[inline]
+    fn amp_at(&self, phase: f64, playhead_perc: f64) -> f32 {
+        match *self {
+            Dynamic::Sine => Sine.amp_at(phase, playhead_perc),
+            Dynamic::Saw => Saw.amp_at(phase, playhead_perc),
+            Dynamic::Square => Square.amp_at(phase, playhead_perc),
+            Dynamic::Noise => Noise.amp_at(phase, playhead_perc),
+            Dynamic::NoiseWalk => NoiseWalk.amp_at(phase, playhead_perc),
+            Dynamic::SawExp(steepness) => SawExp(steepness).amp_at(phase, playhead_perc),
+            Dynamic::NoiseWalkSmooth(smoothing) =>
+                NoiseWalkSmooth(smoothing).amp_at(phase, playhead_perc),
+        }
+    }
+    #[inline]
+    fn amp_at_seeded(&self, phase: f64, playhead_perc: f64, rng: &mut u64) -> f32 {
+        match *self {
+            Dynamic::Noise => Noise.amp_at_seeded(phase, playhead_perc, rng),
+            _ => self.amp_at(phase, playhead_perc),
+        }
+    }
+    #[inline]
+    fn process_hz(&self, hz: f64) -> f64 {
+        match *self {
+            Dynamic::Sine => Sine.process_hz(hz),
+            Dynamic::Saw => Saw.process_hz(hz),
+            Dynamic::Square => Square.process_hz(hz),
+            Dynamic::Noise => Noise.process_hz(hz),
+            Dynamic::NoiseWalk => NoiseWalk.process_hz(hz),
+            Dynamic::SawExp(steepness) => SawExp(steepness).process_hz(hz),
+            Dynamic::NoiseWalkSmooth(smoothing) => NoiseWalkSmooth(smoothing).process_hz(hz),
+        }
+    }
+}
+
+impl Waveform for Bakeable {
+    #[inline]
+    fn amp_at_phase(&self, phase: f64) -> f32 {
+        match *self {
+            Bakeable::Dynamic(ref waveform) => waveform.amp_at_phase(phase),
+            Bakeable::Wavetable(ref table) => table.amp_at_phase(phase),
+        }
+    }
+    #[inline]
+    fn amp_at(&self, phase: f64, playhead_perc: f64) -> f32 {
+        match *self {
+            Bakeable::Dynamic(ref waveform) => waveform.amp_at(phase, playhead_perc),
+            Bakeable::Wavetable(ref table) => table.amp_at(phase, playhead_perc),
+        }
+    }
+    #[inline]
+    fn amp_at_seeded(&self, phase: f64, playhead_perc: f64, rng: &mut u64) -> f32 {
+        match *self {
+            Bakeable::Dynamic(ref waveform) => waveform.amp_at_seeded(phase, playhead_perc, rng),
+            Bakeable::Wavetable(ref table) => table.amp_at_seeded(phase, playhead_perc, rng),
         }
     }
+    #[inline]
+    fn process_hz(&self, hz: f64) -> f64 {
+        match *self {
+            Bakeable::Dynamic(ref waveform) => waveform.process_hz(hz),
+            Bakeable::Wavetable(ref table) => table.process_hz(hz),
+        }
+    }
+}
+
+impl<T> Waveform for Box<T>
+    where T: ?Sized + Waveform,
+{
+    #[inline]
+    fn amp_at_phase(&self, phase: f64) -> f32 {
+        (**self).amp_at_phase(phase)
+    }
+    #[inline]
+    fn amp_at(&self, phase: f64, playhead_perc: f64) -> f32 {
+        (**self).amp_at(phase, playhead_perc)
+    }
+    #[inline]
+    fn amp_at_seeded(&self, phase: f64, playhead_perc: f64, rng: &mut u64) -> f32 {
+        (**self).amp_at_seeded(phase, playhead_perc, rng)
+    }
+    #[inline]
+    fn process_hz(&self, hz: f64) -> f64 {
+        (**self).process_hz(hz)
+    }
 }
 
 impl Waveform for Sine {
@@ -105,11 +316,40 @@ impl Waveform for Square {
     }
 }
 
+impl Waveform for PulseEnv {
+    /// The duty at the start of the playhead.
+    #[inline]
+    fn amp_at_phase(&self, phase: f64) -> f32 {
+        self.amp_at(phase, 0.0)
+    }
+    #[inline]
+    fn amp_at(&self, phase: f64, playhead_perc: f64) -> f32 {
+        let duty = self.0.y_clamped(playhead_perc).max(0.0).min(1.0);
+        (if ::utils::fmod(phase, 1.0) < 1.0 - duty { -1.0 } else { 1.0 }) as f32
+    }
+}
+
+impl Waveform for SawExpEnv {
+    /// The steepness at the start of the playhead.
+    #[inline]
+    fn amp_at_phase(&self, phase: f64) -> f32 {
+        self.amp_at(phase, 0.0)
+    }
+    #[inline]
+    fn amp_at(&self, phase: f64, playhead_perc: f64) -> f32 {
+        SawExp(self.0.y_clamped(playhead_perc) as Steepness).amp_at_phase(phase)
+    }
+}
+
 impl Waveform for Noise {
     #[inline]
     fn amp_at_phase(&self, _phase: f64) -> f32 {
         ::rand::random::<f32>() * 2.0 - 1.0
     }
+    #[inline]
+    fn amp_at_seeded(&self, _phase: f64, _playhead_perc: f64, rng: &mut u64) -> f32 {
+        next_random(rng) * 2.0 - 1.0
+    }
 }
 
 impl Waveform for NoiseWalk {
@@ -124,3 +364,163 @@ impl Waveform for NoiseWalk {
         pitch::ScaledPerc(perc, 0.6).hz() as f64
     }
 }
+
+impl Waveform for NoiseWalkSmooth {
+    #[inline]
+    fn amp_at_phase(&self, phase: f64) -> f32 {
+        let NoiseWalkSmooth(smoothing) = *self;
+        NoiseWalk.amp_at_phase(phase / smoothing.max(::std::f32::EPSILON) as f64)
+    }
+    #[inline]
+    fn process_hz(&self, hz: f64) -> f64 {
+        NoiseWalk.process_hz(hz)
+    }
+}
+
+impl Waveform for Additive {
+    #[inline]
+    fn amp_at_phase(&self, phase: f64) -> f32 {
+        self.0.iter().enumerate().fold(0.0, |sum, (n, &amp)| {
+            sum + amp * (PI_2 * (n + 1) as f64 * phase).sin() as f32
+        })
+    }
+}
+
+impl Waveform for Wavetable {
+    #[inline]
+    fn amp_at_phase(&self, phase: f64) -> f32 {
+        let table = &self.0;
+        if table.is_empty() {
+            return 0.0;
+        }
+        let len = table.len();
+        let pos = ::utils::fmod(phase, 1.0) * len as f64;
+        let idx = pos as usize % len;
+        let next = (idx + 1) % len;
+        let frac = (pos - pos.floor()) as f32;
+        table[idx] + (table[next] - table[idx]) * frac
+    }
+}
+
+impl<A, B> Waveform for Morph<A, B>
+    where A: Waveform,
+          B: Waveform,
+{
+    #[inline]
+    fn amp_at_phase(&self, phase: f64) -> f32 {
+        self.a.amp_at_phase(phase) * (1.0 - self.mix) + self.b.amp_at_phase(phase) * self.mix
+    }
+    #[inline]
+    fn amp_at(&self, phase: f64, playhead_perc: f64) -> f32 {
+        let a = self.a.amp_at(phase, playhead_perc);
+        let b = self.b.amp_at(phase, playhead_perc);
+        a * (1.0 - self.mix) + b * self.mix
+    }
+    #[inline]
+    fn amp_at_seeded(&self, phase: f64, playhead_perc: f64, rng: &mut u64) -> f32 {
+        let a = self.a.amp_at_seeded(phase, playhead_perc, rng);
+        let b = self.b.amp_at_seeded(phase, playhead_perc, rng);
+        a * (1.0 - self.mix) + b * self.mix
+    }
+    #[inline]
+    fn process_hz(&self, hz: f64) -> f64 {
+        let mix = self.mix as f64;
+        self.a.process_hz(hz) * (1.0 - mix) + self.b.process_hz(hz) * mix
+    }
+}
+
+
+/// Step the given random number generator state (splitmix64), returning a number in `[0, 1)`.
+#[inline]
+fn next_random(state: &mut u64) -> f32 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut x = *state;
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+    x = x ^ (x >> 31);
+    (x >> 40) as f32 / (1u64 << 24) as f32
+}
+
+
+#[test]
+fn test_additive() {
+    let phases = [0.0, 0.1, 0.25, 0.6, 0.9];
+    for &phase in phases.iter() {
+        assert_eq!(Additive(vec![1.0]).amp_at_phase(phase), Sine.amp_at_phase(phase));
+        let expected = Sine.amp_at_phase(phase) + 0.5 * Sine.amp_at_phase(phase * 3.0);
+        assert!((Additive(vec![1.0, 0.0, 0.5]).amp_at_phase(phase) - expected).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_morph() {
+    let phases = [0.0, 0.1, 0.25, 0.6, 0.9];
+    let morph = |mix| Morph { a: Sine, b: Saw, mix: mix };
+    for &phase in phases.iter() {
+        assert_eq!(morph(0.0).amp_at_phase(phase), Sine.amp_at_phase(phase));
+        assert_eq!(morph(1.0).amp_at_phase(phase), Saw.amp_at_phase(phase));
+    }
+}
+
+#[test]
+fn test_pulse_env() {
+    use envelope::Point;
+
+    // The fraction of a single period spent at `1.0` for the given playhead.
+    let duty_at = |pulse: &PulseEnv, playhead_perc: f64| {
+        let n = 1_000;
+        let high = (0..n)
+            .filter(|&i| pulse.amp_at(i as f64 / n as f64, playhead_perc) > 0.0)
+            .count();
+        high as f64 / n as f64
+    };
+    let pulse = PulseEnv(vec![Point::new(0.0, 0.1, 0.0), Point::new(1.0, 0.9, 0.0)].into());
+    assert!((duty_at(&pulse, 0.0) - 0.1).abs() < 0.01);
+    assert!((duty_at(&pulse, 0.5) - 0.5).abs() < 0.01);
+    assert!((duty_at(&pulse, 1.0) - 0.9).abs() < 0.01);
+
+    let square = PulseEnv(vec![Point::new(0.0, 0.5, 0.0), Point::new(1.0, 0.5, 0.0)].into());
+    for &phase in [0.0, 0.1, 0.25, 0.6, 0.9].iter() {
+        assert_eq!(square.amp_at(phase, 0.3), Square.amp_at_phase(phase));
+    }
+}
+
+#[test]
+fn test_wavetable() {
+    let table = Wavetable::new(&Sine, 2048);
+    assert_eq!(table.0.len(), 2048);
+    for i in 0..1_000 {
+        let phase = i as f64 / 333.0;
+        assert!((table.amp_at_phase(phase) - Sine.amp_at_phase(phase)).abs() < 1e-4);
+    }
+
+    let bake = |waveform: Dynamic| Bakeable::from(waveform).bake(2048);
+    assert!(bake(Dynamic::Noise).is_none());
+    assert!(bake(Dynamic::NoiseWalk).is_none());
+    assert!(bake(Dynamic::NoiseWalkSmooth(2.0)).is_none());
+    assert!(Bakeable::Wavetable(table.clone()).bake(2048).is_none());
+    assert_eq!(bake(Dynamic::Sine), Some(Bakeable::Wavetable(table)));
+}
+
+#[test]
+fn test_noise_walk_smooth() {
+    // The mean change in amplitude between successive samples of the walk.
+    let mean_delta = |walk: NoiseWalkSmooth| {
+        let n = 10_000;
+        let amps: Vec<f32> = (0..n).map(|i| walk.amp_at_phase(i as f64 * 0.01)).collect();
+        amps.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f32>() / (n - 1) as f32
+    };
+    assert!(mean_delta(NoiseWalkSmooth(0.25)) > mean_delta(NoiseWalkSmooth(4.0)));
+
+    for &phase in [0.0, 0.1, 0.25, 0.6, 0.9, 3.7].iter() {
+        assert_eq!(NoiseWalkSmooth(1.0).amp_at_phase(phase), NoiseWalk.amp_at_phase(phase));
+    }
+
+    // The `Dynamic` variant keeps both the smoothing and the walk's frequency processing.
+    let walk = Dynamic::NoiseWalkSmooth(4.0);
+    assert_eq!(walk.process_hz(440.0), NoiseWalk.process_hz(440.0));
+    assert!(walk.process_hz(440.0) != 440.0);
+    for &phase in [0.0, 0.1, 0.25, 0.6, 0.9, 3.7].iter() {
+        assert_eq!(walk.amp_at(phase, 0.5), NoiseWalkSmooth(4.0).amp_at_phase(phase));
+    }
+}
@@ -0,0 +1,87 @@
+//!
+//! A lock-free queue for sending parameter updates to a `Synth` running on the audio thread.
+//!
+
+extern crate ringbuf;
+
+use {Synth, instrument};
+
+
+/// A change to one of a `Synth`'s parameters.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ParamUpdate {
+    /// Set the target `volume`, which is smoothed over the `volume_smoothing_ms`.
+    Volume(f32),
+    /// Set the amount that each voice is spread across the channels.
+    Spread(f32),
+    /// Set the amount that each voice is panned per octave from the base pitch.
+    KeyPan(f32),
+    /// Set the instrument's detune in steps.
+    Detune(f32),
+    /// Set the base pitch in hz.
+    BasePitch(f32),
+}
+
+/// The sending end of a parameter queue, used on the control thread.
+pub struct ParamSender(ringbuf::Producer<ParamUpdate>);
+
+/// The receiving end of a parameter queue, used on the audio thread.
+pub struct ParamReceiver(ringbuf::Consumer<ParamUpdate>);
+
+
+/// Create a new parameter queue that can hold up to `capacity` pending updates.
+pub fn param_queue(capacity: usize) -> (ParamSender, ParamReceiver) {
+    let (producer, consumer) = ringbuf::RingBuffer::new(capacity).split();
+    (ParamSender(producer), ParamReceiver(consumer))
+}
+
+impl ParamSender {
+    /// Push an update onto the queue without blocking.
+    ///
+    /// Returns the update as an `Err` if the queue is full.
+    pub fn send(&mut self, update: ParamUpdate) -> Result<(), ParamUpdate> {
+        self.0.push(update)
+    }
+}
+
+impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
+    where NFG: instrument::NoteFreqGenerator,
+{
+
+    /// Apply the given parameter update.
+    pub fn apply_param_update(&mut self, update: ParamUpdate) {
+        match update {
+            ParamUpdate::Volume(volume) => self.volume = volume,
+            ParamUpdate::Spread(spread) => self.spread = spread,
+            ParamUpdate::KeyPan(key_pan) => self.key_pan = key_pan,
+            ParamUpdate::Detune(detune) => self.instrument.detune = detune,
+            ParamUpdate::BasePitch(hz) => self.base_pitch = hz,
+        }
+    }
+
+    /// Apply every update that is waiting in the queue without blocking or allocating.
+    ///
+    /// This is intended to be called on the audio thread before each call to `fill_slice`, so
+    /// that updates sent from the control thread take effect at the start of the next buffer.
+    pub fn apply_param_updates(&mut self, receiver: &mut ParamReceiver) {
+        while let Some(update) = receiver.0.pop() {
+            self.apply_param_update(update);
+        }
+    }
+
+}
+
+
+#[test]
+fn test() {
+    let mut synth = Synth::<_, _, (), (), (), ()>::poly(());
+    let (mut sender, mut receiver) = param_queue(2);
+    sender.send(ParamUpdate::Volume(0.5)).unwrap();
+    sender.send(ParamUpdate::Spread(0.25)).unwrap();
+    assert_eq!(sender.send(ParamUpdate::KeyPan(1.0)), Err(ParamUpdate::KeyPan(1.0)));
+
+    synth.apply_param_updates(&mut receiver);
+    assert_eq!(synth.volume, 0.5);
+    assert_eq!(synth.spread, 0.25);
+    sender.send(ParamUpdate::KeyPan(1.0)).unwrap();
+}
@@ -0,0 +1,297 @@
+//!
+//! Versioned patch files, allowing a serialized `Synth` to be loaded by later versions of the
+//! crate.
+//!
+
+extern crate serde;
+extern crate serde_json;
+
+use std;
+
+
+/// The version of the patch layout written by `PatchFile::save`.
+///
+/// - `0`: An unversioned JSON `Synth` without the `PatchFile` wrapper.
+/// - `1`: A `Synth` wrapped in a `PatchFile` with a `version`.
+///
+/// Fields added to the `Synth` since a patch was saved are filled with their defaults.
+pub const PATCH_VERSION: u32 = 1;
+
+/// A versioned wrapper around a serialized synth.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatchFile<S> {
+    /// The layout version that the patch was written with.
+    pub version: u32,
+    /// The synth stored within the patch.
+    pub synth: S,
+}
+
+/// The errors that may occur while loading or saving a `PatchFile`.
+#[derive(Debug)]
+pub enum PatchError {
+    /// The patch was not valid JSON or did not describe a valid synth.
+    Json(serde_json::Error),
+    /// The patch was written by a newer version of the crate.
+    UnsupportedVersion(u64),
+    /// The patch has a `version` that is not a non-negative integer.
+    InvalidVersion,
+    /// The patch has a `synth` but no `version`.
+    MissingVersion,
+    /// The patch has a version but no `synth`.
+    MissingSynth,
+}
+
+
+impl<S> PatchFile<S> {
+
+    /// Wrap the given synth in a patch file of the current `PATCH_VERSION`.
+    pub fn new(synth: S) -> Self {
+        PatchFile {
+            version: PATCH_VERSION,
+            synth: synth,
+        }
+    }
+
+    /// Write the patch to the given writer as JSON.
+    pub fn save<W>(&self, writer: &mut W) -> Result<(), PatchError>
+        where W: std::io::Write,
+              S: serde::Serialize,
+    {
+        serde_json::to_writer(writer, self).map_err(PatchError::Json)
+    }
+
+    /// Load a patch from the given reader.
+    ///
+    /// Patches of an older version are loaded as the current version, with any fields that were
+    /// added to the `Synth` since the patch was saved filled with their defaults.
+    pub fn load<R>(reader: R) -> Result<Self, PatchError>
+        where R: std::io::Read,
+              S: serde::Deserialize,
+    {
+        use self::serde_json::Value;
+
+        let value: Value = try!(serde_json::from_reader(reader).map_err(PatchError::Json));
+        let (version, synth) = match value {
+            Value::Object(mut map) => match map.remove("version") {
+                Some(version) => {
+                    let version = match version.as_u64() {
+                        Some(version) => version,
+                        None => return Err(PatchError::InvalidVersion),
+                    };
+                    match map.remove("synth") {
+                        Some(synth) => (version, synth),
+                        None => return Err(PatchError::MissingSynth),
+                    }
+                },
+                None if map.contains_key("synth") => return Err(PatchError::MissingVersion),
+                // Unversioned patches are a bare synth.
+                None => (0, Value::Object(map)),
+            },
+            value => (0, value),
+        };
+
+        if version > PATCH_VERSION as u64 {
+            return Err(PatchError::UnsupportedVersion(version));
+        }
+
+        let synth = try!(serde_json::from_value(synth).map_err(PatchError::Json));
+        Ok(PatchFile::new(synth))
+    }
+
+}
+
+impl std::fmt::Display for PatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            PatchError::Json(ref err) => write!(f, "invalid patch: {}", err),
+            PatchError::UnsupportedVersion(version) =>
+                write!(f, "unsupported patch version {} (the latest is {})", version, PATCH_VERSION),
+            PatchError::InvalidVersion => write!(f, "the patch version is not a valid integer"),
+            PatchError::MissingVersion => write!(f, "the patch has no version"),
+            PatchError::MissingSynth => write!(f, "the patch has no synth"),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {
+    fn description(&self) -> &str {
+        match *self {
+            PatchError::Json(_) => "invalid patch",
+            PatchError::UnsupportedVersion(_) => "unsupported patch version",
+            PatchError::InvalidVersion => "invalid patch version",
+            PatchError::MissingVersion => "the patch has no version",
+            PatchError::MissingSynth => "the patch has no synth",
+        }
+    }
+}
+
+impl<S> serde::Serialize for PatchFile<S>
+    where S: serde::Serialize,
+{
+    fn serialize<Z>(&self, serializer: &mut Z) -> Result<(), Z::Error>
+        where Z: serde::Serializer,
+    {
+        struct Visitor<'a, S: 'a> {
+            t: &'a PatchFile<S>,
+            field_idx: u8,
+        }
+
+        impl<'a, S> serde::ser::MapVisitor for Visitor<'a, S>
+            where S: serde::Serialize,
+        {
+            fn visit<Z>(&mut self, serializer: &mut Z) -> Result<Option<()>, Z::Error>
+                where Z: serde::Serializer,
+            {
+                match self.field_idx {
+                    0 => {
+                        self.field_idx += 1;
+                        Ok(Some(try!(serializer.serialize_struct_elt("version", self.t.version))))
+                    },
+                    1 => {
+                        self.field_idx += 1;
+                        Ok(Some(try!(serializer.serialize_struct_elt("synth", &self.t.synth))))
+                    },
+                    _ => Ok(None),
+                }
+            }
+
+            fn len(&self) -> Option<usize> {
+                Some(2)
+            }
+        }
+
+        serializer.serialize_struct("PatchFile", Visitor { t: self, field_idx: 0 })
+    }
+}
+
+
+#[test]
+fn test_load_missing_field() {
+    use self::serde_json::Value;
+    use dynamic::Synth;
+    use tuning::Tuning;
+
+    let synth = Synth::default();
+    let mut bytes = Vec::new();
+    PatchFile::new(synth.clone()).save(&mut bytes).unwrap();
+
+    // Remove a field to imitate a patch saved before the field was added.
+    let mut value: Value = serde_json::from_slice(&bytes).unwrap();
+    if let Value::Object(ref mut map) = value {
+        if let Some(&mut Value::Object(ref mut synth)) = map.get_mut("synth") {
+            assert!(synth.remove("tuning").is_some());
+        }
+    }
+    let json = serde_json::to_string(&value).unwrap();
+
+    let patch: PatchFile<Synth> = PatchFile::load(json.as_bytes()).unwrap();
+    assert_eq!(patch.version, PATCH_VERSION);
+    assert_eq!(patch.synth.tuning, Tuning::EqualTemperament);
+    assert_eq!(patch.synth, synth);
+
+    // An unversioned synth is loaded as version 0.
+    let json = serde_json::to_string(&synth).unwrap();
+    let patch: PatchFile<Synth> = PatchFile::load(json.as_bytes()).unwrap();
+    assert_eq!(patch.synth, synth);
+
+    let json = "{\"version\":99,\"synth\":null}";
+    match PatchFile::<Synth>::load(json.as_bytes()) {
+        Err(PatchError::UnsupportedVersion(99)) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    for json in &["{\"version\":\"1\",\"synth\":null}", "{\"version\":-1,\"synth\":null}"] {
+        match PatchFile::<Synth>::load(json.as_bytes()) {
+            Err(PatchError::InvalidVersion) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    let json = "{\"synth\":null}";
+    match PatchFile::<Synth>::load(json.as_bytes()) {
+        Err(PatchError::MissingVersion) => (),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_round_trip_every_field() {
+    use arpeggiator::{ArpPattern, Arpeggiator};
+    use dynamic::{self, Synth};
+    use oscillator::Oscillator;
+    use synth::{DcBlocker, GlideSequence, LoopMode, Oversampler, PanLaw, SpreadShape};
+    use time;
+    use tuning::Tuning;
+
+    // Both structs are listed in full (rather than via `..`) so that a new field fails to compile
+    // until it is covered here. Every value differs from its default so that a field missing from
+    // the serde impls cannot silently fall back to it.
+    let oscillator = Oscillator {
+        waveform: dynamic::Waveform::Saw,
+        amplitude: dynamic::Amplitude::Constant(0.5),
+        frequency: dynamic::Frequency::Hz(220.0),
+        freq_warp: dynamic::FreqWarp::None,
+        is_muted: true,
+        ratio: 2.0,
+        fixed_hz: Some(110.0),
+        free_running: true,
+        phase_offset: 0.25,
+        waveform_crossfade_ms: 5.0,
+        previous_waveform: Some(dynamic::Waveform::Square),
+        waveform_fade: 0.5,
+        quadrature: true,
+    };
+    let default = Synth::default();
+    let mut voices = default.voices;
+    voices[0].loop_playhead = 5;
+    voices[0].loop_reversed = true;
+    voices[0].gain = 0.5;
+    let synth = Synth {
+        oscillators: vec![oscillator],
+        voices: voices,
+        instrument: default.instrument,
+        volume: 0.5,
+        spread: 0.25,
+        loop_points: Some((0.25, 0.75)),
+        duration_ms: time::Ms(500.0),
+        base_pitch: 440.0,
+        arpeggiator: Some(Arpeggiator::new(time::Ms(125.0), ArpPattern::Down)),
+        note_match_tolerance: 1.0,
+        held_notes: vec![440.0],
+        high_precision: true,
+        loop_mode: LoopMode::PingPong,
+        one_shot: true,
+        key_pan: 0.5,
+        dc_blocker: Some(DcBlocker { last_input: vec![0.25], last_output: vec![0.5] }),
+        tuning: Tuning::just_intonation(),
+        glide_quantized: true,
+        phase_spread: 0.5,
+        random_start_phase: true,
+        declick_ms: time::Ms(10.0),
+        declick_elapsed_ms: Some(2.5),
+        volume_smoothing_ms: time::Ms(20.0),
+        current_volume: 0.75,
+        pitch_bend: -1.5,
+        sustain: true,
+        sustained_notes: vec![220.0],
+        stereo_detune: 10.0,
+        loop_crossfade_ms: time::Ms(15.0),
+        oversampler: Some(Oversampler::new(2)),
+        seed: Some(42),
+        pan_law: Some(PanLaw::ConstantGain),
+        sostenuto: true,
+        sostenuto_notes: vec![330.0],
+        sostenuto_released_notes: vec![330.0],
+        solo_oscillator: Some(0),
+        freq_slew: Some(2.5),
+        spread_shape: SpreadShape::Alternating,
+        output_enabled: false,
+        ignore_note_off: true,
+        glide_sequence: Some(GlideSequence { steps: vec![(880.0, 100.0)], elapsed_ms: 12.5 }),
+    };
+
+    let mut bytes = Vec::new();
+    PatchFile::new(synth.clone()).save(&mut bytes).unwrap();
+    let patch: PatchFile<Synth> = PatchFile::load(&bytes[..]).unwrap();
+    assert_eq!(patch.synth, synth);
+}
@@ -23,12 +23,22 @@ mod envelope {
                             Ok(Some(try!(serializer.serialize_struct_elt("points",
                                                                          &self.t.points))))
                         },
+                        1 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("sustain_point",
+                                                                         self.t.sustain_point))))
+                        },
+                        2 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("loop_region",
+                                                                         self.t.loop_region))))
+                        },
                         _ => Ok(None),
                     }
                 }
 
                 fn len(&self) -> Option<usize> {
-                    Some(1)
+                    Some(3)
                 }
             }
 
@@ -45,12 +55,39 @@ mod envelope {
             impl serde::de::Visitor for Visitor {
                 type Value = Envelope;
 
+                fn visit_seq<V>(&mut self, mut visitor: V) -> Result<Envelope, V::Error>
+                    where V: serde::de::SeqVisitor,
+                {
+                    let points = try!(visitor.visit());
+                    let sustain_point = try!(visitor.visit());
+                    let loop_region = try!(visitor.visit());
+
+                    let points = match points {
+                        Some(points) => points,
+                        None => return Err(serde::de::Error::missing_field("points")),
+                    };
+
+                    let sustain_point = sustain_point.unwrap_or(None);
+
+                    let loop_region = loop_region.unwrap_or(None);
+
+                    try!(visitor.end());
+
+                    Ok(Envelope {
+                        points: points,
+                        sustain_point: sustain_point,
+                        loop_region: loop_region,
+                    })
+                }
+
                 fn visit_map<V>(&mut self, mut visitor: V) -> Result<Envelope, V::Error>
                     where V: serde::de::MapVisitor,
                 {
                     let mut points = None;
+                    let mut sustain_point = None;
+                    let mut loop_region = None;
 
-                    enum Field { Points }
+                    enum Field { Points, SustainPoint, LoopRegion }
 
                     impl serde::Deserialize for Field {
                         fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
@@ -66,7 +103,11 @@ mod envelope {
                                 {
                                     match value {
                                         "points" => Ok(Field::Points),
-                                        _ => Err(serde::de::Error::custom("expected points")),
+                                        "sustain_point" => Ok(Field::SustainPoint),
+                                        "loop_region" => Ok(Field::LoopRegion),
+                                        _ => Err(serde::de::Error::custom(
+                                            "expected points, sustain_point or loop_region"
+                                        )),
                                     }
                                 }
                             }
@@ -78,6 +119,8 @@ mod envelope {
                     loop {
                         match try!(visitor.visit_key()) {
                             Some(Field::Points) => { points = Some(try!(visitor.visit_value())); },
+                            Some(Field::SustainPoint) => { sustain_point = Some(try!(visitor.visit_value())); },
+                            Some(Field::LoopRegion) => { loop_region = Some(try!(visitor.visit_value())); },
                             None => { break; }
                         }
                     }
@@ -87,13 +130,21 @@ mod envelope {
                         None => return Err(serde::de::Error::missing_field("points")),
                     };
 
+                    let sustain_point = sustain_point.unwrap_or(None);
+
+                    let loop_region = loop_region.unwrap_or(None);
+
                     try!(visitor.end());
 
-                    Ok(Envelope { points: points })
+                    Ok(Envelope {
+                        points: points,
+                        sustain_point: sustain_point,
+                        loop_region: loop_region,
+                    })
                 }
             }
 
-            static FIELDS: &'static [&'static str] = &["points"];
+            static FIELDS: &'static [&'static str] = &["points", "sustain_point", "loop_region"];
 
             deserializer.deserialize_struct("Envelope", FIELDS, Visitor)
         }
@@ -104,11 +155,16 @@ mod envelope {
         use envelope::Point;
         extern crate serde_json;
 
-        let envelope = Envelope { points: vec![Point { x: 0.5, y: 0.5, curve: 0.0 }] };
+        let envelope = Envelope {
+            points: vec![Point { x: 0.5, y: 0.5, curve: 0.0 }],
+            sustain_point: Some(0),
+            loop_region: None,
+        };
         let serialized = serde_json::to_string(&envelope).unwrap();
 
         println!("{}", serialized);
-        assert_eq!("{\"points\":[{\"x\":0.5,\"y\":0.5,\"curve\":0}]}", serialized);
+        assert_eq!("{\"points\":[{\"x\":0.5,\"y\":0.5,\"curve\":0}],\"sustain_point\":0,\"loop_region\":null}",
+                   serialized);
         
         let deserialized: Envelope = serde_json::from_str(&serialized).unwrap();
 
@@ -421,6 +477,61 @@ mod oscillator {
             }
         }
 
+        mod noise_walk_smooth {
+            use oscillator::waveform::NoiseWalkSmooth;
+            use super::super::super::serde;
+
+            impl serde::Serialize for NoiseWalkSmooth {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    serializer.serialize_newtype_struct("NoiseWalkSmooth", self.0)
+                }
+            }
+
+            impl serde::Deserialize for NoiseWalkSmooth {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct Visitor;
+
+                    impl serde::de::Visitor for Visitor {
+                        type Value = NoiseWalkSmooth;
+
+                        fn visit_f32<E>(&mut self, v: f32) -> Result<Self::Value, E>
+                            where E: serde::de::Error,
+                        {
+                            Ok(NoiseWalkSmooth(v))
+                        }
+
+                        fn visit_newtype_struct<D>(&mut self, deserializer: &mut D) -> Result<Self::Value, D::Error>
+                            where D: serde::Deserializer,
+                        {
+                            Ok(NoiseWalkSmooth(try!(serde::de::Deserialize::deserialize(deserializer))))
+                        }
+                    }
+
+                    deserializer.deserialize_newtype_struct("NoiseWalkSmooth", Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                extern crate serde_json;
+
+                let noise_walk_smooth = NoiseWalkSmooth(2.0);
+                let serialized = serde_json::to_string(&noise_walk_smooth).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("2", &serialized);
+
+                let deserialized: NoiseWalkSmooth = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(noise_walk_smooth, deserialized);
+            }
+        }
+
         mod dynamic {
             use oscillator::waveform::Dynamic;
             use super::super::super::serde;
@@ -436,6 +547,7 @@ mod oscillator {
                         Dynamic::Noise => serializer.serialize_unit_variant("Dynamic", 3, "Noise"),
                         Dynamic::NoiseWalk => serializer.serialize_unit_variant("Dynamic", 4, "NoiseWalk"),
                         Dynamic::SawExp(ref s) => serializer.serialize_newtype_variant("Dynamic", 5, "SawExp", s),
+                        Dynamic::NoiseWalkSmooth(ref s) => serializer.serialize_newtype_variant("Dynamic", 6, "NoiseWalkSmooth", s),
                     }
                 }
             }
@@ -444,7 +556,7 @@ mod oscillator {
                 fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
                     where D: serde::Deserializer,
                 {
-                    enum Variant { Sine, Saw, Square, Noise, NoiseWalk, SawExp }
+                    enum Variant { Sine, Saw, Square, Noise, NoiseWalk, SawExp, NoiseWalkSmooth }
 
                     impl serde::de::Deserialize for Variant {
                         fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
@@ -465,9 +577,25 @@ mod oscillator {
                                         "Noise" => Ok(Variant::Noise),
                                         "NoiseWalk" => Ok(Variant::NoiseWalk),
                                         "SawExp" => Ok(Variant::SawExp),
+                                        "NoiseWalkSmooth" => Ok(Variant::NoiseWalkSmooth),
                                         _ => Err(serde::de::Error::unknown_field(value)),
                                     }
                                 }
+
+                                fn visit_usize<E>(&mut self, value: usize) -> Result<Variant, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        0 => Ok(Variant::Sine),
+                                        1 => Ok(Variant::Saw),
+                                        2 => Ok(Variant::Square),
+                                        3 => Ok(Variant::Noise),
+                                        4 => Ok(Variant::NoiseWalk),
+                                        5 => Ok(Variant::SawExp),
+                                        6 => Ok(Variant::NoiseWalkSmooth),
+                                        _ => Err(serde::de::Error::invalid_value("unknown variant index")),
+                                    }
+                                }
                             }
 
                             deserializer.deserialize(VariantVisitor)
@@ -507,12 +635,16 @@ mod oscillator {
                                     let steepness = try!(visitor.visit_newtype());
                                     Ok(Dynamic::SawExp(steepness))
                                 },
+                                Variant::NoiseWalkSmooth => {
+                                    let smoothing = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::NoiseWalkSmooth(smoothing))
+                                },
                             }
                         }
                     }
 
                     const VARIANTS: &'static [&'static str] = &[
-                        "Sine", "Saw", "Square", "Noise", "NoiseWalk", "SawExp"
+                        "Sine", "Saw", "Square", "Noise", "NoiseWalk", "SawExp", "NoiseWalkSmooth"
                     ];
 
                     deserializer.deserialize_enum("Dynamic", VARIANTS, Visitor)
@@ -536,120 +668,113 @@ mod oscillator {
             }
         }
 
-    }
-
-    mod freq_warp {
-
-        mod gaussian {
-            use oscillator::freq_warp::Gaussian;
-            use super::super::super::serde;
-
-            impl serde::Serialize for Gaussian {
-                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
-                    where S: serde::Serializer,
-                {
-                    serializer.serialize_newtype_struct("Gaussian", self.0)
-                }
-            }
-
-            impl serde::Deserialize for Gaussian {
-                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
-                    where D: serde::Deserializer,
-                {
-                    struct Visitor;
-
-                    impl serde::de::Visitor for Visitor {
-                        type Value = Gaussian;
-
-                        fn visit_f32<E>(&mut self, v: f32) -> Result<Self::Value, E>
-                            where E: serde::de::Error,
-                        {
-                            Ok(Gaussian(v))
-                        }
-
-                        fn visit_newtype_struct<D>(&mut self, deserializer: &mut D) -> Result<Self::Value, D::Error>
-                            where D: serde::Deserializer,
-                        {
-                            Ok(Gaussian(try!(serde::de::Deserialize::deserialize(deserializer))))
-                        }
-                    }
-
-                    deserializer.deserialize_newtype_struct("Gaussian", Visitor)
-                }
-            }
-
-            #[test]
-            fn test() {
-                extern crate serde_json;
-
-                let gaussian = Gaussian(2.0);
-                let serialized = serde_json::to_string(&gaussian).unwrap();
-
-                println!("{}", serialized);
-                assert_eq!("2", &serialized);
-
-                let deserialized: Gaussian = serde_json::from_str(&serialized).unwrap();
-
-                println!("{:?}", deserialized);
-                assert_eq!(gaussian, deserialized);
-            }
-        }
-
-        mod pitch_drift {
-            use oscillator::freq_warp::PitchDrift;
+        mod morph {
+            use oscillator::waveform::Morph;
             use super::super::super::serde;
+            use std;
 
-            impl serde::Serialize for PitchDrift {
+            impl<A, B> serde::Serialize for Morph<A, B>
+                where A: serde::Serialize,
+                      B: serde::Serialize,
+            {
                 fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
                     where S: serde::Serializer,
                 {
-                    struct Visitor<'a> {
-                        t: &'a PitchDrift,
+                    struct Visitor<'a, A: 'a, B: 'a> {
+                        t: &'a Morph<A, B>,
                         field_idx: u8,
                     }
 
-                    impl<'a> serde::ser::MapVisitor for Visitor<'a> {
+                    impl<'a, A, B> serde::ser::MapVisitor for Visitor<'a, A, B>
+                        where A: serde::Serialize,
+                              B: serde::Serialize,
+                    {
                         fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
                             where S: serde::Serializer,
                         {
                             match self.field_idx {
                                 0 => {
                                     self.field_idx += 1;
-                                    Ok(Some(try!(serializer.serialize_struct_elt("hz", self.t.hz))))
+                                    Ok(Some(try!(serializer.serialize_struct_elt("a", &self.t.a))))
                                 },
                                 1 => {
                                     self.field_idx += 1;
-                                    Ok(Some(try!(serializer.serialize_struct_elt("amp", self.t.amp))))
+                                    Ok(Some(try!(serializer.serialize_struct_elt("b", &self.t.b))))
+                                },
+                                2 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("mix", self.t.mix))))
                                 },
                                 _ => Ok(None),
                             }
                         }
 
                         fn len(&self) -> Option<usize> {
-                            Some(2)
+                            Some(3)
                         }
                     }
 
-                    serializer.serialize_struct("PitchDrift", Visitor { t: self, field_idx: 0 })
+                    serializer.serialize_struct("Morph", Visitor { t: self, field_idx: 0 })
                 }
             }
 
-            impl serde::Deserialize for PitchDrift {
+            impl<A, B> serde::Deserialize for Morph<A, B>
+                where A: serde::Deserialize,
+                      B: serde::Deserialize,
+            {
                 fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
                     where D: serde::Deserializer,
                 {
-                    struct Visitor;
+                    struct Visitor<A, B> {
+                        a: std::marker::PhantomData<A>,
+                        b: std::marker::PhantomData<B>,
+                    }
 
-                    impl serde::de::Visitor for Visitor {
-                        type Value = PitchDrift;
+                    impl<A, B> serde::de::Visitor for Visitor<A, B>
+                        where A: serde::Deserialize,
+                              B: serde::Deserialize,
+                    {
+                        type Value = Morph<A, B>;
 
-                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<PitchDrift, V::Error>
+                        fn visit_seq<V>(&mut self, mut visitor: V) -> Result<Morph<A, B>, V::Error>
+                            where V: serde::de::SeqVisitor,
+                        {
+                            let a = try!(visitor.visit());
+                            let b = try!(visitor.visit());
+                            let mix = try!(visitor.visit());
+
+                            let a = match a {
+                                Some(a) => a,
+                                None => return Err(serde::de::Error::missing_field("a")),
+                            };
+
+                            let b = match b {
+                                Some(b) => b,
+                                None => return Err(serde::de::Error::missing_field("b")),
+                            };
+
+                            let mix = match mix {
+                                Some(mix) => mix,
+                                None => return Err(serde::de::Error::missing_field("mix")),
+                            };
+
+                            try!(visitor.end());
+
+                            Ok(Morph {
+                                a: a,
+                                b: b,
+                                mix: mix,
+                            })
+                        }
+
+                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<Morph<A, B>, V::Error>
                             where V: serde::de::MapVisitor,
                         {
-                            let mut hz = None;
-                            let mut amp = None;
+                            let mut a = None;
+                            let mut b = None;
+                            let mut mix = None;
 
-                            enum Field { Hz, Amp }
+                            enum Field { A, B, Mix }
 
                             impl serde::Deserialize for Field {
                                 fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
@@ -664,9 +789,10 @@ mod oscillator {
                                             where E: serde::de::Error,
                                         {
                                             match value {
-                                                "hz" => Ok(Field::Hz),
-                                                "amp" => Ok(Field::Amp),
-                                                _ => Err(serde::de::Error::custom("expected hz or amp")),
+                                                "a" => Ok(Field::A),
+                                                "b" => Ok(Field::B),
+                                                "mix" => Ok(Field::Mix),
+                                                _ => Err(serde::de::Error::custom("expected a, b or mix")),
                                             }
                                         }
                                     }
@@ -677,228 +803,143 @@ mod oscillator {
 
                             loop {
                                 match try!(visitor.visit_key()) {
-                                    Some(Field::Hz) => { hz = Some(try!(visitor.visit_value())); },
-                                    Some(Field::Amp) => { amp = Some(try!(visitor.visit_value())); },
+                                    Some(Field::A) => { a = Some(try!(visitor.visit_value())); },
+                                    Some(Field::B) => { b = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Mix) => { mix = Some(try!(visitor.visit_value())); },
                                     None => { break; }
                                 }
                             }
 
-                            let hz = match hz {
-                                Some(hz) => hz,
-                                None => return Err(serde::de::Error::missing_field("hz")),
+                            let a = match a {
+                                Some(a) => a,
+                                None => return Err(serde::de::Error::missing_field("a")),
                             };
 
-                            let amp = match amp {
-                                Some(amp) => amp,
-                                None => return Err(serde::de::Error::missing_field("amp")),
+                            let b = match b {
+                                Some(b) => b,
+                                None => return Err(serde::de::Error::missing_field("b")),
+                            };
+
+                            let mix = match mix {
+                                Some(mix) => mix,
+                                None => return Err(serde::de::Error::missing_field("mix")),
                             };
 
                             try!(visitor.end());
 
-                            Ok(PitchDrift {
-                                hz: hz,
-                                amp: amp
+                            Ok(Morph {
+                                a: a,
+                                b: b,
+                                mix: mix,
                             })
                         }
                     }
 
-                    static FIELDS: &'static [&'static str] = &["hz", "amp"];
+                    static FIELDS: &'static [&'static str] = &["a", "b", "mix"];
 
-                    deserializer.deserialize_struct("PitchDrift", FIELDS, Visitor)
+                    deserializer.deserialize_struct("Morph", FIELDS, Visitor {
+                        a: std::marker::PhantomData,
+                        b: std::marker::PhantomData,
+                    })
                 }
             }
 
             #[test]
             fn test() {
+                use oscillator::waveform::{Dynamic, Sine};
                 extern crate serde_json;
 
-                let pitch_drift = PitchDrift {
-                    hz: 440.0,
-                    amp: 1.0,
-                };
-                let serialized = serde_json::to_string(&pitch_drift).unwrap();
+                let morph = Morph { a: Sine, b: Dynamic::SawExp(2.0), mix: 0.5 };
+                let serialized = serde_json::to_string(&morph).unwrap();
 
                 println!("{}", serialized);
-                assert_eq!("{\"hz\":440,\"amp\":1}", serialized);
-                
-                let deserialized: PitchDrift = serde_json::from_str(&serialized).unwrap();
+                assert_eq!("{\"a\":null,\"b\":{\"SawExp\":2},\"mix\":0.5}", serialized);
+
+                let deserialized: Morph<Sine, Dynamic> = serde_json::from_str(&serialized).unwrap();
 
                 println!("{:?}", deserialized);
-                assert_eq!(pitch_drift, deserialized);
+                assert_eq!(morph, deserialized);
             }
         }
 
-        mod dynamic {
+        mod additive {
+            use oscillator::waveform::Additive;
             use super::super::super::serde;
-            use oscillator::freq_warp::Dynamic;
 
-            impl serde::Serialize for Dynamic {
+            impl serde::Serialize for Additive {
                 fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
                     where S: serde::Serializer,
                 {
-                    match *self {
-                        Dynamic::None => serializer.serialize_unit_variant("Dynamic", 0, "None"),
-                        Dynamic::Gaussian(g) => serializer.serialize_newtype_variant("Dynamic", 1, "Gaussian", g),
-                        Dynamic::PitchDrift(p) => serializer.serialize_newtype_variant("Dynamic", 2, "PitchDrift", p),
-                    }
+                    serializer.serialize_newtype_struct("Additive", &self.0)
                 }
             }
 
-            impl serde::Deserialize for Dynamic {
+            impl serde::Deserialize for Additive {
                 fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
                     where D: serde::Deserializer,
                 {
-                    enum Variant { None, Gaussian, PitchDrift }
+                    struct Visitor;
 
-                    impl serde::de::Deserialize for Variant {
-                        fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                    impl serde::de::Visitor for Visitor {
+                        type Value = Additive;
+
+                        fn visit_newtype_struct<D>(&mut self, deserializer: &mut D) -> Result<Self::Value, D::Error>
                             where D: serde::Deserializer,
                         {
-                            struct VariantVisitor;
+                            Ok(Additive(try!(serde::de::Deserialize::deserialize(deserializer))))
+                        }
+                    }
 
-                            impl serde::de::Visitor for VariantVisitor {
-                                type Value = Variant;
+                    deserializer.deserialize_newtype_struct("Additive", Visitor)
+                }
+            }
 
-                                fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
-                                    where E: serde::de::Error,
-                                {
-                                    match value {
-                                        "None" => Ok(Variant::None),
-                                        "Gaussian" => Ok(Variant::Gaussian),
-                                        "PitchDrift" => Ok(Variant::PitchDrift),
-                                        _ => Err(serde::de::Error::unknown_field(value)),
-                                    }
-                                }
-                            }
+            #[test]
+            fn test() {
+                extern crate serde_json;
 
-                            deserializer.deserialize(VariantVisitor)
-                        }
-                    }
+                let additive = Additive(vec![1.0, 0.5, 0.25]);
+                let serialized = serde_json::to_string(&additive).unwrap();
 
-                    struct Visitor;
+                println!("{}", serialized);
+                assert_eq!("[1,0.5,0.25]", &serialized);
 
-                    impl serde::de::EnumVisitor for Visitor {
-                        type Value = Dynamic;
-
-                        fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
-                            where V: serde::de::VariantVisitor,
-                        {
-                            match try!(visitor.visit_variant()) {
-                                Variant::None => {
-                                    try!(visitor.visit_unit());
-                                    Ok(Dynamic::None)
-                                },
-                                Variant::Gaussian => {
-                                    let gaussian = try!(visitor.visit_newtype());
-                                    Ok(Dynamic::Gaussian(gaussian))
-                                },
-                                Variant::PitchDrift => {
-                                    let drift = try!(visitor.visit_newtype());
-                                    Ok(Dynamic::PitchDrift(drift))
-                                },
-                            }
-                        }
-                    }
-
-                    const VARIANTS: &'static [&'static str] = &[
-                        "None", "Gaussian", "PitchDrift"
-                    ];
-
-                    deserializer.deserialize_enum("Dynamic", VARIANTS, Visitor)
-                }
-            }
-
-            #[test]
-            fn test() {
-                use oscillator::freq_warp::Gaussian;
-                extern crate serde_json;
-
-                let gaussian = Dynamic::Gaussian(Gaussian(2.0));
-                let serialized = serde_json::to_string(&gaussian).unwrap();
-
-                println!("{}", serialized);
-                assert_eq!("{\"Gaussian\":2}", serialized);
-                
-                let deserialized: Dynamic = serde_json::from_str(&serialized).unwrap();
+                let deserialized: Additive = serde_json::from_str(&serialized).unwrap();
 
                 println!("{:?}", deserialized);
-                assert_eq!(gaussian, deserialized);
+                assert_eq!(additive, deserialized);
             }
         }
 
-    }
-
-    mod amplitude {
-
-        mod dynamic {
+        mod wavetable {
+            use oscillator::waveform::Wavetable;
             use super::super::super::serde;
-            use oscillator::amplitude::Dynamic;
 
-            impl serde::Serialize for Dynamic {
+            impl serde::Serialize for Wavetable {
                 fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
                     where S: serde::Serializer,
                 {
-                    match *self {
-                        Dynamic::Envelope(ref e) => serializer.serialize_newtype_variant("Dynamic", 0, "Envelope", e),
-                        Dynamic::Constant(a) => serializer.serialize_newtype_variant("Dynamic", 1, "Constant", a),
-                    }
+                    serializer.serialize_newtype_struct("Wavetable", &self.0)
                 }
             }
 
-            impl serde::Deserialize for Dynamic {
+            impl serde::Deserialize for Wavetable {
                 fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
                     where D: serde::Deserializer,
                 {
-                    enum Variant { Envelope, Constant }
-
-                    impl serde::de::Deserialize for Variant {
-                        fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
-                            where D: serde::Deserializer,
-                        {
-                            struct VariantVisitor;
-
-                            impl serde::de::Visitor for VariantVisitor {
-                                type Value = Variant;
-
-                                fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
-                                    where E: serde::de::Error,
-                                {
-                                    match value {
-                                        "Envelope" => Ok(Variant::Envelope),
-                                        "Constant" => Ok(Variant::Constant),
-                                        _ => Err(serde::de::Error::unknown_field(value)),
-                                    }
-                                }
-                            }
-
-                            deserializer.deserialize(VariantVisitor)
-                        }
-                    }
-
                     struct Visitor;
 
-                    impl serde::de::EnumVisitor for Visitor {
-                        type Value = Dynamic;
+                    impl serde::de::Visitor for Visitor {
+                        type Value = Wavetable;
 
-                        fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
-                            where V: serde::de::VariantVisitor,
+                        fn visit_newtype_struct<D>(&mut self, deserializer: &mut D) -> Result<Self::Value, D::Error>
+                            where D: serde::Deserializer,
                         {
-                            match try!(visitor.visit_variant()) {
-                                Variant::Envelope => {
-                                    let env = try!(visitor.visit_newtype());
-                                    Ok(Dynamic::Envelope(env))
-                                },
-                                Variant::Constant => {
-                                    let amp = try!(visitor.visit_newtype());
-                                    Ok(Dynamic::Constant(amp))
-                                },
-                            }
+                            Ok(Wavetable(try!(serde::de::Deserialize::deserialize(deserializer))))
                         }
                     }
 
-                    const VARIANTS: &'static [&'static str] = &["Envelope", "Constant"];
-
-                    deserializer.deserialize_enum("Dynamic", VARIANTS, Visitor)
+                    deserializer.deserialize_newtype_struct("Wavetable", Visitor)
                 }
             }
 
@@ -906,44 +947,39 @@ mod oscillator {
             fn test() {
                 extern crate serde_json;
 
-                let amp = Dynamic::Constant(1.0);
-                let serialized = serde_json::to_string(&amp).unwrap();
+                let wavetable = Wavetable(vec![1.0, 0.5, 0.25]);
+                let serialized = serde_json::to_string(&wavetable).unwrap();
 
                 println!("{}", serialized);
-                assert_eq!("{\"Constant\":1}", serialized);
-                
-                let deserialized: Dynamic = serde_json::from_str(&serialized).unwrap();
+                assert_eq!("[1,0.5,0.25]", &serialized);
+
+                let deserialized: Wavetable = serde_json::from_str(&serialized).unwrap();
 
                 println!("{:?}", deserialized);
-                assert_eq!(amp, deserialized);
+                assert_eq!(wavetable, deserialized);
             }
         }
 
-
-    }
-
-    mod frequency {
-
-        mod dynamic {
+        mod bakeable {
+            use oscillator::waveform::Bakeable;
             use super::super::super::serde;
-            use oscillator::frequency::Dynamic;
 
-            impl serde::Serialize for Dynamic {
+            impl serde::Serialize for Bakeable {
                 fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
                     where S: serde::Serializer,
                 {
                     match *self {
-                        Dynamic::Envelope(ref e) => serializer.serialize_newtype_variant("Dynamic", 0, "Envelope", e),
-                        Dynamic::Hz(h) => serializer.serialize_newtype_variant("Dynamic", 1, "Hz", h),
+                        Bakeable::Dynamic(ref d) => serializer.serialize_newtype_variant("Bakeable", 0, "Dynamic", d),
+                        Bakeable::Wavetable(ref t) => serializer.serialize_newtype_variant("Bakeable", 1, "Wavetable", t),
                     }
                 }
             }
 
-            impl serde::Deserialize for Dynamic {
+            impl serde::Deserialize for Bakeable {
                 fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
                     where D: serde::Deserializer,
                 {
-                    enum Variant { Envelope, Hz }
+                    enum Variant { Dynamic, Wavetable }
 
                     impl serde::de::Deserialize for Variant {
                         fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
@@ -958,11 +994,21 @@ mod oscillator {
                                     where E: serde::de::Error,
                                 {
                                     match value {
-                                        "Envelope" => Ok(Variant::Envelope),
-                                        "Hz" => Ok(Variant::Hz),
+                                        "Dynamic" => Ok(Variant::Dynamic),
+                                        "Wavetable" => Ok(Variant::Wavetable),
                                         _ => Err(serde::de::Error::unknown_field(value)),
                                     }
                                 }
+
+                                fn visit_usize<E>(&mut self, value: usize) -> Result<Variant, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        0 => Ok(Variant::Dynamic),
+                                        1 => Ok(Variant::Wavetable),
+                                        _ => Err(serde::de::Error::invalid_value("unknown variant index")),
+                                    }
+                                }
                             }
 
                             deserializer.deserialize(VariantVisitor)
@@ -972,341 +1018,295 @@ mod oscillator {
                     struct Visitor;
 
                     impl serde::de::EnumVisitor for Visitor {
-                        type Value = Dynamic;
+                        type Value = Bakeable;
 
                         fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
                             where V: serde::de::VariantVisitor,
                         {
                             match try!(visitor.visit_variant()) {
-                                Variant::Envelope => {
-                                    let env = try!(visitor.visit_newtype());
-                                    Ok(Dynamic::Envelope(env))
+                                Variant::Dynamic => {
+                                    let waveform = try!(visitor.visit_newtype());
+                                    Ok(Bakeable::Dynamic(waveform))
                                 },
-                                Variant::Hz => {
-                                    let hz = try!(visitor.visit_newtype());
-                                    Ok(Dynamic::Hz(hz))
+                                Variant::Wavetable => {
+                                    let table = try!(visitor.visit_newtype());
+                                    Ok(Bakeable::Wavetable(table))
                                 },
                             }
                         }
                     }
 
-                    const VARIANTS: &'static [&'static str] = &["Envelope", "Hz"];
+                    const VARIANTS: &'static [&'static str] = &["Dynamic", "Wavetable"];
 
-                    deserializer.deserialize_enum("Dynamic", VARIANTS, Visitor)
+                    deserializer.deserialize_enum("Bakeable", VARIANTS, Visitor)
                 }
             }
 
             #[test]
             fn test() {
                 extern crate serde_json;
+                use oscillator::waveform::{Dynamic, Wavetable};
 
-                let hz = Dynamic::Hz(440.0);
-                let serialized = serde_json::to_string(&hz).unwrap();
+                let bakeable = Bakeable::Dynamic(Dynamic::SawExp(2.0));
+                let serialized = serde_json::to_string(&bakeable).unwrap();
 
                 println!("{}", serialized);
-                assert_eq!("{\"Hz\":440}", serialized);
-                
-                let deserialized: Dynamic = serde_json::from_str(&serialized).unwrap();
+                assert_eq!("{\"Dynamic\":{\"SawExp\":2}}", serialized);
+
+                let deserialized: Bakeable = serde_json::from_str(&serialized).unwrap();
 
                 println!("{:?}", deserialized);
-                assert_eq!(hz, deserialized);
+                assert_eq!(bakeable, deserialized);
+
+                let baked = Bakeable::Wavetable(Wavetable(vec![1.0, 0.5]));
+                let serialized = serde_json::to_string(&baked).unwrap();
+                assert_eq!("{\"Wavetable\":[1,0.5]}", serialized);
+                let deserialized: Bakeable = serde_json::from_str(&serialized).unwrap();
+                assert_eq!(baked, deserialized);
             }
         }
-
-    }
-
-    mod oscillator {
-
-        mod state {
-            use oscillator::State;
+        mod pulse_env {
+            use oscillator::waveform::PulseEnv;
             use super::super::super::serde;
 
-            impl serde::Serialize for State {
+            impl serde::Serialize for PulseEnv {
                 fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
                     where S: serde::Serializer,
                 {
-                    struct Visitor<'a> {
-                        t: &'a State,
-                        field_idx: u8,
-                    }
-
-                    impl<'a> serde::ser::MapVisitor for Visitor<'a> {
-                        fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
-                            where S: serde::Serializer,
-                        {
-                            match self.field_idx {
-                                0 => {
-                                    self.field_idx += 1;
-                                    Ok(Some(try!(serializer.serialize_struct_elt("phase", self.t.phase))))
-                                },
-                                1 => {
-                                    self.field_idx += 1;
-                                    Ok(Some(try!(serializer.serialize_struct_elt("freq_warp_phase",
-                                                                                 self.t.freq_warp_phase))))
-                                },
-                                _ => Ok(None),
-                            }
-                        }
-
-                        fn len(&self) -> Option<usize> {
-                            Some(2)
-                        }
-                    }
-
-                    serializer.serialize_struct("State", Visitor { t: self, field_idx: 0 })
+                    serializer.serialize_newtype_struct("PulseEnv", &self.0)
                 }
             }
 
-            impl serde::Deserialize for State {
+            impl serde::Deserialize for PulseEnv {
                 fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
                     where D: serde::Deserializer,
                 {
                     struct Visitor;
 
                     impl serde::de::Visitor for Visitor {
-                        type Value = State;
+                        type Value = PulseEnv;
 
-                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<State, V::Error>
-                            where V: serde::de::MapVisitor,
+                        fn visit_newtype_struct<D>(&mut self, deserializer: &mut D) -> Result<Self::Value, D::Error>
+                            where D: serde::Deserializer,
                         {
-                            let mut phase = None;
-                            let mut freq_warp_phase = None;
-
-                            enum Field { Phase, FreqWarpPhase }
-
-                            impl serde::Deserialize for Field {
-                                fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
-                                    where D: serde::de::Deserializer,
-                                {
-                                    struct FieldVisitor;
-
-                                    impl serde::de::Visitor for FieldVisitor {
-                                        type Value = Field;
-
-                                        fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
-                                            where E: serde::de::Error,
-                                        {
-                                            match value {
-                                                "phase" => Ok(Field::Phase),
-                                                "freq_warp_phase" => Ok(Field::FreqWarpPhase),
-                                                _ => Err(serde::de::Error::custom("expected phase or freq_warp_phase")),
-                                            }
-                                        }
-                                    }
-
-                                    deserializer.deserialize(FieldVisitor)
-                                }
-                            }
-
-                            loop {
-                                match try!(visitor.visit_key()) {
-                                    Some(Field::Phase) => { phase = Some(try!(visitor.visit_value())); },
-                                    Some(Field::FreqWarpPhase) => { freq_warp_phase = Some(try!(visitor.visit_value())); },
-                                    None => { break; }
-                                }
-                            }
-
-                            let phase = match phase {
-                                Some(phase) => phase,
-                                None => return Err(serde::de::Error::missing_field("phase")),
-                            };
-
-                            let freq_warp_phase = match freq_warp_phase {
-                                Some(freq_warp_phase) => freq_warp_phase,
-                                None => return Err(serde::de::Error::missing_field("freq_warp_phase")),
-                            };
-
-                            try!(visitor.end());
-
-                            Ok(State {
-                                phase: phase,
-                                freq_warp_phase: freq_warp_phase,
-                            })
+                            Ok(PulseEnv(try!(serde::de::Deserialize::deserialize(deserializer))))
                         }
                     }
 
-                    static FIELDS: &'static [&'static str] = &["phase", "freq_warp_phase"];
-
-                    deserializer.deserialize_struct("State", FIELDS, Visitor)
+                    deserializer.deserialize_newtype_struct("PulseEnv", Visitor)
                 }
             }
 
             #[test]
             fn test() {
+                use envelope::{Envelope, Point};
                 extern crate serde_json;
 
-                let state = State {
-                    phase: 0.0,
-                    freq_warp_phase: 0.0,
-                };
-                let serialized = serde_json::to_string(&state).unwrap();
+                let points = vec![Point { x: 0.5, y: 0.5, curve: 0.0 }];
+                let pulse_env = PulseEnv(Envelope::from(points));
+                let serialized = serde_json::to_string(&pulse_env).unwrap();
 
                 println!("{}", serialized);
-                assert_eq!("{\"phase\":0,\"freq_warp_phase\":0}", serialized);
-                
-                let deserialized: State = serde_json::from_str(&serialized).unwrap();
+                assert_eq!("{\"points\":[{\"x\":0.5,\"y\":0.5,\"curve\":0}],\
+                            \"sustain_point\":null,\"loop_region\":null}", &serialized);
+
+                let deserialized: PulseEnv = serde_json::from_str(&serialized).unwrap();
 
                 println!("{:?}", deserialized);
-                assert_eq!(state, deserialized);
+                assert_eq!(pulse_env, deserialized);
             }
         }
 
-        mod state_per_voice {
-            use oscillator::StatePerVoice;
+        mod saw_exp_env {
+            use oscillator::waveform::SawExpEnv;
             use super::super::super::serde;
 
-            impl serde::Serialize for StatePerVoice {
+            impl serde::Serialize for SawExpEnv {
                 fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
                     where S: serde::Serializer,
                 {
-                    serializer.serialize_newtype_struct("StatePerVoice", &self.0)
+                    serializer.serialize_newtype_struct("SawExpEnv", &self.0)
                 }
             }
 
-            impl serde::Deserialize for StatePerVoice {
+            impl serde::Deserialize for SawExpEnv {
                 fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
                     where D: serde::Deserializer,
                 {
                     struct Visitor;
 
                     impl serde::de::Visitor for Visitor {
-                        type Value = StatePerVoice;
+                        type Value = SawExpEnv;
 
                         fn visit_newtype_struct<D>(&mut self, deserializer: &mut D) -> Result<Self::Value, D::Error>
                             where D: serde::Deserializer,
                         {
-                            Ok(StatePerVoice(try!(serde::de::Deserialize::deserialize(deserializer))))
+                            Ok(SawExpEnv(try!(serde::de::Deserialize::deserialize(deserializer))))
                         }
                     }
 
-                    deserializer.deserialize_newtype_struct("StatePerVoice", Visitor)
+                    deserializer.deserialize_newtype_struct("SawExpEnv", Visitor)
                 }
             }
 
             #[test]
             fn test() {
+                use envelope::{Envelope, Point};
                 extern crate serde_json;
 
-                let state_per_voice = StatePerVoice(vec![]);
-                let serialized = serde_json::to_string(&state_per_voice).unwrap();
+                let points = vec![Point { x: 0.5, y: 0.5, curve: 0.0 }];
+                let saw_exp_env = SawExpEnv(Envelope::from(points));
+                let serialized = serde_json::to_string(&saw_exp_env).unwrap();
 
                 println!("{}", serialized);
-                assert_eq!("[]", &serialized);
+                assert_eq!("{\"points\":[{\"x\":0.5,\"y\":0.5,\"curve\":0}],\
+                            \"sustain_point\":null,\"loop_region\":null}", &serialized);
 
-                let deserialized: StatePerVoice = serde_json::from_str(&serialized).unwrap();
+                let deserialized: SawExpEnv = serde_json::from_str(&serialized).unwrap();
 
                 println!("{:?}", deserialized);
-                assert_eq!(state_per_voice, deserialized);
+                assert_eq!(saw_exp_env, deserialized);
             }
         }
 
-        mod oscillator {
-            use oscillator::Oscillator;
-            use super::super::super::serde;
-            use std;
+    }
 
-            impl<W, A, F, FW> serde::Serialize for Oscillator<W, A, F, FW>
-                where W: serde::Serialize,
-                      A: serde::Serialize,
-                      F: serde::Serialize,
-                      FW: serde::Serialize,
-            {
+    mod freq_warp {
+
+        mod gaussian {
+            use oscillator::freq_warp::Gaussian;
+            use super::super::super::serde;
+
+            impl serde::Serialize for Gaussian {
                 fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
                     where S: serde::Serializer,
                 {
-                    struct Visitor<'a, W: 'a, A: 'a, F: 'a, FW: 'a> {
-                        t: &'a Oscillator<W, A, F, FW>,
+                    serializer.serialize_newtype_struct("Gaussian", self.0)
+                }
+            }
+
+            impl serde::Deserialize for Gaussian {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct Visitor;
+
+                    impl serde::de::Visitor for Visitor {
+                        type Value = Gaussian;
+
+                        fn visit_f32<E>(&mut self, v: f32) -> Result<Self::Value, E>
+                            where E: serde::de::Error,
+                        {
+                            Ok(Gaussian(v))
+                        }
+
+                        fn visit_newtype_struct<D>(&mut self, deserializer: &mut D) -> Result<Self::Value, D::Error>
+                            where D: serde::Deserializer,
+                        {
+                            Ok(Gaussian(try!(serde::de::Deserialize::deserialize(deserializer))))
+                        }
+                    }
+
+                    deserializer.deserialize_newtype_struct("Gaussian", Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                extern crate serde_json;
+
+                let gaussian = Gaussian(2.0);
+                let serialized = serde_json::to_string(&gaussian).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("2", &serialized);
+
+                let deserialized: Gaussian = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(gaussian, deserialized);
+            }
+        }
+
+        mod pitch_drift {
+            use oscillator::freq_warp::PitchDrift;
+            use super::super::super::serde;
+
+            impl serde::Serialize for PitchDrift {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    struct Visitor<'a> {
+                        t: &'a PitchDrift,
                         field_idx: u8,
                     }
 
-                    impl<'a, W, A, F, FW> serde::ser::MapVisitor for Visitor<'a, W, A, F, FW>
-                        where W: serde::Serialize,
-                              A: serde::Serialize,
-                              F: serde::Serialize,
-                              FW: serde::Serialize,
-                    {
+                    impl<'a> serde::ser::MapVisitor for Visitor<'a> {
                         fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
                             where S: serde::Serializer,
                         {
                             match self.field_idx {
                                 0 => {
                                     self.field_idx += 1;
-                                    Ok(Some(try!(serializer.serialize_struct_elt("waveform",
-                                                                                 &self.t.waveform))))
+                                    Ok(Some(try!(serializer.serialize_struct_elt("hz", self.t.hz))))
                                 },
                                 1 => {
                                     self.field_idx += 1;
-                                    Ok(Some(try!(serializer.serialize_struct_elt("amplitude",
-                                                                                 &self.t.amplitude))))
-                                },
-                                2 => {
-                                    self.field_idx += 1;
-                                    Ok(Some(try!(serializer.serialize_struct_elt("frequency",
-                                                                                 &self.t.frequency))))
-                                },
-                                3 => {
-                                    self.field_idx += 1;
-                                    Ok(Some(try!(serializer.serialize_struct_elt("freq_warp",
-                                                                                 &self.t.freq_warp))))
-                                },
-                                4 => {
-                                    self.field_idx += 1;
-                                    Ok(Some(try!(serializer.serialize_struct_elt("is_muted",
-                                                                                 self.t.is_muted))))
+                                    Ok(Some(try!(serializer.serialize_struct_elt("amp", self.t.amp))))
                                 },
                                 _ => Ok(None),
                             }
                         }
 
                         fn len(&self) -> Option<usize> {
-                            Some(5)
+                            Some(2)
                         }
                     }
 
-                    serializer.serialize_struct("Oscillator", Visitor { t: self, field_idx: 0 })
+                    serializer.serialize_struct("PitchDrift", Visitor { t: self, field_idx: 0 })
                 }
             }
 
-            impl<W, A, F, FW> serde::Deserialize for Oscillator<W, A, F, FW>
-                where W: serde::Deserialize,
-                      A: serde::Deserialize,
-                      F: serde::Deserialize,
-                      FW: serde::Deserialize,
-            {
+            impl serde::Deserialize for PitchDrift {
                 fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
                     where D: serde::Deserializer,
                 {
-                    struct Visitor<W, A, F, FW> {
-                        w: std::marker::PhantomData<W>,
-                        a: std::marker::PhantomData<A>,
-                        f: std::marker::PhantomData<F>,
-                        fw: std::marker::PhantomData<FW>,
-                    }
+                    struct Visitor;
 
-                    impl<W, A, F, FW> serde::de::Visitor for Visitor<W, A, F, FW>
-                        where W: serde::Deserialize,
-                              A: serde::Deserialize,
-                              F: serde::Deserialize,
-                              FW: serde::Deserialize,
-                    {
-                        type Value = Oscillator<W, A, F, FW>;
+                    impl serde::de::Visitor for Visitor {
+                        type Value = PitchDrift;
 
-                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<Oscillator<W, A, F, FW>, V::Error>
+                        fn visit_seq<V>(&mut self, mut visitor: V) -> Result<PitchDrift, V::Error>
+                            where V: serde::de::SeqVisitor,
+                        {
+                            let hz = try!(visitor.visit());
+                            let amp = try!(visitor.visit());
+
+                            let hz = match hz {
+                                Some(hz) => hz,
+                                None => return Err(serde::de::Error::missing_field("hz")),
+                            };
+
+                            let amp = match amp {
+                                Some(amp) => amp,
+                                None => return Err(serde::de::Error::missing_field("amp")),
+                            };
+
+                            try!(visitor.end());
+
+                            Ok(PitchDrift {
+                                hz: hz,
+                                amp: amp
+                            })
+                        }
+
+                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<PitchDrift, V::Error>
                             where V: serde::de::MapVisitor,
                         {
-                            let mut waveform = None;
-                            let mut amplitude = None;
-                            let mut frequency = None;
-                            let mut freq_warp = None;
-                            let mut is_muted = None;
+                            let mut hz = None;
+                            let mut amp = None;
 
-                            enum Field {
-                                Waveform,
-                                Amplitude,
-                                Frequency,
-                                FreqWarp,
-                                IsMuted,
-                            }
+                            enum Field { Hz, Amp }
 
                             impl serde::Deserialize for Field {
                                 fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
@@ -1321,15 +1321,9 @@ mod oscillator {
                                             where E: serde::de::Error,
                                         {
                                             match value {
-                                                "waveform" => Ok(Field::Waveform),
-                                                "amplitude" => Ok(Field::Amplitude),
-                                                "frequency" => Ok(Field::Frequency),
-                                                "freq_warp" => Ok(Field::FreqWarp),
-                                                "is_muted" => Ok(Field::IsMuted),
-                                                _ => Err(serde::de::Error::custom(
-                                                    "expected waveform, amplitude, frequency, \
-                                                    start_mel or target_mel"
-                                                )),
+                                                "hz" => Ok(Field::Hz),
+                                                "amp" => Ok(Field::Amp),
+                                                _ => Err(serde::de::Error::custom("expected hz or amp")),
                                             }
                                         }
                                     }
@@ -1340,90 +1334,2979 @@ mod oscillator {
 
                             loop {
                                 match try!(visitor.visit_key()) {
-                                    Some(Field::Waveform) => { waveform = Some(try!(visitor.visit_value())); },
-                                    Some(Field::Amplitude) => { amplitude = Some(try!(visitor.visit_value())); },
-                                    Some(Field::Frequency) => { frequency = Some(try!(visitor.visit_value())); },
-                                    Some(Field::FreqWarp) => { freq_warp = Some(try!(visitor.visit_value())); },
-                                    Some(Field::IsMuted) => { is_muted = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Hz) => { hz = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Amp) => { amp = Some(try!(visitor.visit_value())); },
                                     None => { break; }
                                 }
                             }
 
-                            let waveform = match waveform {
-                                Some(waveform) => waveform,
-                                None => return Err(serde::de::Error::missing_field("waveform")),
-                            };
-
-                            let amplitude = match amplitude {
-                                Some(amplitude) => amplitude,
-                                None => return Err(serde::de::Error::missing_field("amplitude")),
-                            };
-
-                            let frequency = match frequency {
-                                Some(frequency) => frequency,
-                                None => return Err(serde::de::Error::missing_field("frequency")),
-                            };
-
-                            let freq_warp = match freq_warp {
-                                Some(freq_warp) => freq_warp,
-                                None => return Err(serde::de::Error::missing_field("freq_warp")),
+                            let hz = match hz {
+                                Some(hz) => hz,
+                                None => return Err(serde::de::Error::missing_field("hz")),
                             };
 
-                            let is_muted = match is_muted {
-                                Some(is_muted) => is_muted,
-                                None => return Err(serde::de::Error::missing_field("is_muted")),
+                            let amp = match amp {
+                                Some(amp) => amp,
+                                None => return Err(serde::de::Error::missing_field("amp")),
                             };
 
                             try!(visitor.end());
 
-                            Ok(Oscillator {
-                                waveform: waveform,
-                                amplitude: amplitude,
-                                frequency: frequency,
-                                freq_warp: freq_warp,
-                                is_muted: is_muted,
+                            Ok(PitchDrift {
+                                hz: hz,
+                                amp: amp
                             })
                         }
                     }
 
-                    static FIELDS: &'static [&'static str] = &[
-                        "waveform",
-                        "amplitude",
-                        "frequency",
-                        "freq_warp",
-                        "is_muted",
-                    ];
+                    static FIELDS: &'static [&'static str] = &["hz", "amp"];
 
-                    deserializer.deserialize_struct("Oscillator", FIELDS, Visitor {
-                        w: std::marker::PhantomData,
-                        a: std::marker::PhantomData,
-                        f: std::marker::PhantomData,
-                        fw: std::marker::PhantomData,
-                    })
+                    deserializer.deserialize_struct("PitchDrift", FIELDS, Visitor)
                 }
             }
 
             #[test]
             fn test() {
-                use oscillator::waveform;
-
                 extern crate serde_json;
 
-                let osc = Oscillator::new(waveform::Sine, 1.0, 440.0, ());
-                let serialized = serde_json::to_string(&osc).unwrap();
+                let pitch_drift = PitchDrift {
+                    hz: 440.0,
+                    amp: 1.0,
+                };
+                let serialized = serde_json::to_string(&pitch_drift).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"hz\":440,\"amp\":1}", serialized);
+                
+                let deserialized: PitchDrift = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(pitch_drift, deserialized);
+            }
+        }
+
+        mod seeded_gaussian {
+            use oscillator::freq_warp::SeededGaussian;
+            use super::super::super::serde;
+
+            impl serde::Serialize for SeededGaussian {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    struct Visitor<'a> {
+                        t: &'a SeededGaussian,
+                        field_idx: u8,
+                    }
+
+                    impl<'a> serde::ser::MapVisitor for Visitor<'a> {
+                        fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                            where S: serde::Serializer,
+                        {
+                            match self.field_idx {
+                                0 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("perc", self.t.perc))))
+                                },
+                                1 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("seed", self.t.seed))))
+                                },
+                                2 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("max_mels", self.t.max_mels))))
+                                },
+                                _ => Ok(None),
+                            }
+                        }
+
+                        fn len(&self) -> Option<usize> {
+                            Some(3)
+                        }
+                    }
+
+                    serializer.serialize_struct("SeededGaussian", Visitor { t: self, field_idx: 0 })
+                }
+            }
+
+            impl serde::Deserialize for SeededGaussian {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct Visitor;
+
+                    impl serde::de::Visitor for Visitor {
+                        type Value = SeededGaussian;
+
+                        fn visit_seq<V>(&mut self, mut visitor: V) -> Result<SeededGaussian, V::Error>
+                            where V: serde::de::SeqVisitor,
+                        {
+                            let perc = try!(visitor.visit());
+                            let seed = try!(visitor.visit());
+                            let max_mels = try!(visitor.visit());
+
+                            let perc = match perc {
+                                Some(perc) => perc,
+                                None => return Err(serde::de::Error::missing_field("perc")),
+                            };
+
+                            let seed = match seed {
+                                Some(seed) => seed,
+                                None => return Err(serde::de::Error::missing_field("seed")),
+                            };
+
+                            let max_mels = match max_mels {
+                                Some(max_mels) => max_mels,
+                                None => return Err(serde::de::Error::missing_field("max_mels")),
+                            };
+
+                            try!(visitor.end());
+
+                            Ok(SeededGaussian {
+                                perc: perc,
+                                seed: seed,
+                                max_mels: max_mels,
+                            })
+                        }
+
+                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<SeededGaussian, V::Error>
+                            where V: serde::de::MapVisitor,
+                        {
+                            let mut perc = None;
+                            let mut seed = None;
+                            let mut max_mels = None;
+
+                            enum Field { Perc, Seed, MaxMels }
+
+                            impl serde::Deserialize for Field {
+                                fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                                    where D: serde::de::Deserializer,
+                                {
+                                    struct FieldVisitor;
+
+                                    impl serde::de::Visitor for FieldVisitor {
+                                        type Value = Field;
+
+                                        fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                            where E: serde::de::Error,
+                                        {
+                                            match value {
+                                                "perc" => Ok(Field::Perc),
+                                                "seed" => Ok(Field::Seed),
+                                                "max_mels" => Ok(Field::MaxMels),
+                                                _ => Err(serde::de::Error::custom("expected perc, seed or max_mels")),
+                                            }
+                                        }
+                                    }
+
+                                    deserializer.deserialize(FieldVisitor)
+                                }
+                            }
+
+                            loop {
+                                match try!(visitor.visit_key()) {
+                                    Some(Field::Perc) => { perc = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Seed) => { seed = Some(try!(visitor.visit_value())); },
+                                    Some(Field::MaxMels) => { max_mels = Some(try!(visitor.visit_value())); },
+                                    None => { break; }
+                                }
+                            }
+
+                            let perc = match perc {
+                                Some(perc) => perc,
+                                None => return Err(serde::de::Error::missing_field("perc")),
+                            };
+
+                            let seed = match seed {
+                                Some(seed) => seed,
+                                None => return Err(serde::de::Error::missing_field("seed")),
+                            };
+
+                            let max_mels = match max_mels {
+                                Some(max_mels) => max_mels,
+                                None => return Err(serde::de::Error::missing_field("max_mels")),
+                            };
+
+                            try!(visitor.end());
+
+                            Ok(SeededGaussian {
+                                perc: perc,
+                                seed: seed,
+                                max_mels: max_mels,
+                            })
+                        }
+                    }
+
+                    static FIELDS: &'static [&'static str] = &["perc", "seed", "max_mels"];
+
+                    deserializer.deserialize_struct("SeededGaussian", FIELDS, Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                extern crate serde_json;
+
+                let gaussian = SeededGaussian {
+                    perc: 0.5,
+                    seed: 42,
+                    max_mels: 100.0,
+                };
+                let serialized = serde_json::to_string(&gaussian).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"perc\":0.5,\"seed\":42,\"max_mels\":100}", serialized);
+
+                let deserialized: SeededGaussian = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(gaussian, deserialized);
+            }
+        }
+
+        mod pitch_drift_env {
+            use oscillator::freq_warp::PitchDriftEnv;
+            use super::super::super::serde;
+
+            impl serde::Serialize for PitchDriftEnv {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    struct Visitor<'a> {
+                        t: &'a PitchDriftEnv,
+                        field_idx: u8,
+                    }
+
+                    impl<'a> serde::ser::MapVisitor for Visitor<'a> {
+                        fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                            where S: serde::Serializer,
+                        {
+                            match self.field_idx {
+                                0 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("hz", self.t.hz))))
+                                },
+                                1 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("amp_env", &self.t.amp_env))))
+                                },
+                                _ => Ok(None),
+                            }
+                        }
+
+                        fn len(&self) -> Option<usize> {
+                            Some(2)
+                        }
+                    }
+
+                    serializer.serialize_struct("PitchDriftEnv", Visitor { t: self, field_idx: 0 })
+                }
+            }
+
+            impl serde::Deserialize for PitchDriftEnv {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct Visitor;
+
+                    impl serde::de::Visitor for Visitor {
+                        type Value = PitchDriftEnv;
+
+                        fn visit_seq<V>(&mut self, mut visitor: V) -> Result<PitchDriftEnv, V::Error>
+                            where V: serde::de::SeqVisitor,
+                        {
+                            let hz = try!(visitor.visit());
+                            let amp_env = try!(visitor.visit());
+
+                            let hz = match hz {
+                                Some(hz) => hz,
+                                None => return Err(serde::de::Error::missing_field("hz")),
+                            };
+
+                            let amp_env = match amp_env {
+                                Some(amp_env) => amp_env,
+                                None => return Err(serde::de::Error::missing_field("amp_env")),
+                            };
+
+                            try!(visitor.end());
+
+                            Ok(PitchDriftEnv {
+                                hz: hz,
+                                amp_env: amp_env,
+                            })
+                        }
+
+                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<PitchDriftEnv, V::Error>
+                            where V: serde::de::MapVisitor,
+                        {
+                            let mut hz = None;
+                            let mut amp_env = None;
+
+                            enum Field { Hz, AmpEnv }
+
+                            impl serde::Deserialize for Field {
+                                fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                                    where D: serde::de::Deserializer,
+                                {
+                                    struct FieldVisitor;
+
+                                    impl serde::de::Visitor for FieldVisitor {
+                                        type Value = Field;
+
+                                        fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                            where E: serde::de::Error,
+                                        {
+                                            match value {
+                                                "hz" => Ok(Field::Hz),
+                                                "amp_env" => Ok(Field::AmpEnv),
+                                                _ => Err(serde::de::Error::custom("expected hz or amp_env")),
+                                            }
+                                        }
+                                    }
+
+                                    deserializer.deserialize(FieldVisitor)
+                                }
+                            }
+
+                            loop {
+                                match try!(visitor.visit_key()) {
+                                    Some(Field::Hz) => { hz = Some(try!(visitor.visit_value())); },
+                                    Some(Field::AmpEnv) => { amp_env = Some(try!(visitor.visit_value())); },
+                                    None => { break; }
+                                }
+                            }
+
+                            let hz = match hz {
+                                Some(hz) => hz,
+                                None => return Err(serde::de::Error::missing_field("hz")),
+                            };
+
+                            let amp_env = match amp_env {
+                                Some(amp_env) => amp_env,
+                                None => return Err(serde::de::Error::missing_field("amp_env")),
+                            };
+
+                            try!(visitor.end());
+
+                            Ok(PitchDriftEnv {
+                                hz: hz,
+                                amp_env: amp_env,
+                            })
+                        }
+                    }
+
+                    static FIELDS: &'static [&'static str] = &["hz", "amp_env"];
+
+                    deserializer.deserialize_struct("PitchDriftEnv", FIELDS, Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                use envelope::Point;
+                extern crate serde_json;
+
+                let pitch_drift = PitchDriftEnv {
+                    hz: 440.0,
+                    amp_env: vec![Point::new(0.5, 0.5, 0.0)].into(),
+                };
+                let serialized = serde_json::to_string(&pitch_drift).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"hz\":440,\"amp_env\":{\"points\":[{\"x\":0.5,\"y\":0.5,\"curve\":0}],\
+                            \"sustain_point\":null,\"loop_region\":null}}",
+                           serialized);
+
+                let deserialized: PitchDriftEnv = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(pitch_drift, deserialized);
+            }
+        }
+
+        mod lfo {
+            use oscillator::freq_warp::Lfo;
+            use super::super::super::serde;
+
+            impl serde::Serialize for Lfo {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    struct Visitor<'a> {
+                        t: &'a Lfo,
+                        field_idx: u8,
+                    }
+
+                    impl<'a> serde::ser::MapVisitor for Visitor<'a> {
+                        fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                            where S: serde::Serializer,
+                        {
+                            match self.field_idx {
+                                0 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("hz", self.t.hz))))
+                                },
+                                1 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("depth", self.t.depth))))
+                                },
+                                _ => Ok(None),
+                            }
+                        }
+
+                        fn len(&self) -> Option<usize> {
+                            Some(2)
+                        }
+                    }
+
+                    serializer.serialize_struct("Lfo", Visitor { t: self, field_idx: 0 })
+                }
+            }
+
+            impl serde::Deserialize for Lfo {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct Visitor;
+
+                    impl serde::de::Visitor for Visitor {
+                        type Value = Lfo;
+
+                        fn visit_seq<V>(&mut self, mut visitor: V) -> Result<Lfo, V::Error>
+                            where V: serde::de::SeqVisitor,
+                        {
+                            let hz = try!(visitor.visit());
+                            let depth = try!(visitor.visit());
+
+                            let hz = match hz {
+                                Some(hz) => hz,
+                                None => return Err(serde::de::Error::missing_field("hz")),
+                            };
+
+                            let depth = match depth {
+                                Some(depth) => depth,
+                                None => return Err(serde::de::Error::missing_field("depth")),
+                            };
+
+                            try!(visitor.end());
+
+                            Ok(Lfo {
+                                hz: hz,
+                                depth: depth,
+                            })
+                        }
+
+                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<Lfo, V::Error>
+                            where V: serde::de::MapVisitor,
+                        {
+                            let mut hz = None;
+                            let mut depth = None;
+
+                            enum Field { Hz, Depth }
+
+                            impl serde::Deserialize for Field {
+                                fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                                    where D: serde::de::Deserializer,
+                                {
+                                    struct FieldVisitor;
+
+                                    impl serde::de::Visitor for FieldVisitor {
+                                        type Value = Field;
+
+                                        fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                            where E: serde::de::Error,
+                                        {
+                                            match value {
+                                                "hz" => Ok(Field::Hz),
+                                                "depth" => Ok(Field::Depth),
+                                                _ => Err(serde::de::Error::custom("expected hz or depth")),
+                                            }
+                                        }
+                                    }
+
+                                    deserializer.deserialize(FieldVisitor)
+                                }
+                            }
+
+                            loop {
+                                match try!(visitor.visit_key()) {
+                                    Some(Field::Hz) => { hz = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Depth) => { depth = Some(try!(visitor.visit_value())); },
+                                    None => { break; }
+                                }
+                            }
+
+                            let hz = match hz {
+                                Some(hz) => hz,
+                                None => return Err(serde::de::Error::missing_field("hz")),
+                            };
+
+                            let depth = match depth {
+                                Some(depth) => depth,
+                                None => return Err(serde::de::Error::missing_field("depth")),
+                            };
+
+                            try!(visitor.end());
+
+                            Ok(Lfo {
+                                hz: hz,
+                                depth: depth,
+                            })
+                        }
+                    }
+
+                    static FIELDS: &'static [&'static str] = &["hz", "depth"];
+
+                    deserializer.deserialize_struct("Lfo", FIELDS, Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                extern crate serde_json;
+
+                let lfo = Lfo { hz: 2.0, depth: 0.5 };
+                let serialized = serde_json::to_string(&lfo).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"hz\":2,\"depth\":0.5}", serialized);
+
+                let deserialized: Lfo = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(lfo, deserialized);
+            }
+        }
+
+        mod dynamic {
+            use super::super::super::serde;
+            use oscillator::freq_warp::Dynamic;
+
+            impl serde::Serialize for Dynamic {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    match *self {
+                        Dynamic::None => serializer.serialize_unit_variant("Dynamic", 0, "None"),
+                        Dynamic::Gaussian(g) => serializer.serialize_newtype_variant("Dynamic", 1, "Gaussian", g),
+                        Dynamic::PitchDrift(p) => serializer.serialize_newtype_variant("Dynamic", 2, "PitchDrift", p),
+                        Dynamic::SeededGaussian(s) => serializer.serialize_newtype_variant("Dynamic", 3, "SeededGaussian", s),
+                        Dynamic::PitchDriftEnv(ref p) => serializer.serialize_newtype_variant("Dynamic", 4, "PitchDriftEnv", p),
+                        Dynamic::Lfo(l) => serializer.serialize_newtype_variant("Dynamic", 5, "Lfo", l),
+                    }
+                }
+            }
+
+            impl serde::Deserialize for Dynamic {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    enum Variant { None, Gaussian, PitchDrift, SeededGaussian, PitchDriftEnv, Lfo }
+
+                    impl serde::de::Deserialize for Variant {
+                        fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                            where D: serde::Deserializer,
+                        {
+                            struct VariantVisitor;
+
+                            impl serde::de::Visitor for VariantVisitor {
+                                type Value = Variant;
+
+                                fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        "None" => Ok(Variant::None),
+                                        "Gaussian" => Ok(Variant::Gaussian),
+                                        "PitchDrift" => Ok(Variant::PitchDrift),
+                                        "SeededGaussian" => Ok(Variant::SeededGaussian),
+                                        "PitchDriftEnv" => Ok(Variant::PitchDriftEnv),
+                                        "Lfo" => Ok(Variant::Lfo),
+                                        _ => Err(serde::de::Error::unknown_field(value)),
+                                    }
+                                }
+
+                                fn visit_usize<E>(&mut self, value: usize) -> Result<Variant, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        0 => Ok(Variant::None),
+                                        1 => Ok(Variant::Gaussian),
+                                        2 => Ok(Variant::PitchDrift),
+                                        3 => Ok(Variant::SeededGaussian),
+                                        4 => Ok(Variant::PitchDriftEnv),
+                                        5 => Ok(Variant::Lfo),
+                                        _ => Err(serde::de::Error::invalid_value("unknown variant index")),
+                                    }
+                                }
+                            }
+
+                            deserializer.deserialize(VariantVisitor)
+                        }
+                    }
+
+                    struct Visitor;
+
+                    impl serde::de::EnumVisitor for Visitor {
+                        type Value = Dynamic;
+
+                        fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                            where V: serde::de::VariantVisitor,
+                        {
+                            match try!(visitor.visit_variant()) {
+                                Variant::None => {
+                                    try!(visitor.visit_unit());
+                                    Ok(Dynamic::None)
+                                },
+                                Variant::Gaussian => {
+                                    let gaussian = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::Gaussian(gaussian))
+                                },
+                                Variant::PitchDrift => {
+                                    let drift = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::PitchDrift(drift))
+                                },
+                                Variant::SeededGaussian => {
+                                    let gaussian = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::SeededGaussian(gaussian))
+                                },
+                                Variant::PitchDriftEnv => {
+                                    let drift = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::PitchDriftEnv(drift))
+                                },
+                                Variant::Lfo => {
+                                    let lfo = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::Lfo(lfo))
+                                },
+                            }
+                        }
+                    }
+
+                    const VARIANTS: &'static [&'static str] = &[
+                        "None", "Gaussian", "PitchDrift", "SeededGaussian", "PitchDriftEnv", "Lfo"
+                    ];
+
+                    deserializer.deserialize_enum("Dynamic", VARIANTS, Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                use oscillator::freq_warp::Gaussian;
+                extern crate serde_json;
+
+                let gaussian = Dynamic::Gaussian(Gaussian(2.0));
+                let serialized = serde_json::to_string(&gaussian).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"Gaussian\":2}", serialized);
+                
+                let deserialized: Dynamic = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(gaussian, deserialized);
+            }
+        }
+
+    }
+
+    mod amplitude {
+
+        mod one_shot_envelope {
+            use oscillator::amplitude::OneShotEnvelope;
+            use super::super::super::serde;
+
+            impl serde::Serialize for OneShotEnvelope {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    serializer.serialize_newtype_struct("OneShotEnvelope", &self.0)
+                }
+            }
+
+            impl serde::Deserialize for OneShotEnvelope {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct Visitor;
+
+                    impl serde::de::Visitor for Visitor {
+                        type Value = OneShotEnvelope;
+
+                        fn visit_newtype_struct<D>(&mut self, deserializer: &mut D) -> Result<Self::Value, D::Error>
+                            where D: serde::Deserializer,
+                        {
+                            Ok(OneShotEnvelope(try!(serde::de::Deserialize::deserialize(deserializer))))
+                        }
+                    }
+
+                    deserializer.deserialize_newtype_struct("OneShotEnvelope", Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                use envelope::{Envelope, Point};
+                extern crate serde_json;
+
+                let points = vec![Point { x: 0.5, y: 0.5, curve: 0.0 }];
+                let one_shot_envelope = OneShotEnvelope(Envelope::from(points));
+                let serialized = serde_json::to_string(&one_shot_envelope).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"points\":[{\"x\":0.5,\"y\":0.5,\"curve\":0}],\
+                            \"sustain_point\":null,\"loop_region\":null}", &serialized);
+
+                let deserialized: OneShotEnvelope = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(one_shot_envelope, deserialized);
+            }
+        }
+
+        mod dynamic {
+            use super::super::super::serde;
+            use oscillator::amplitude::Dynamic;
+
+            impl serde::Serialize for Dynamic {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    match *self {
+                        Dynamic::Envelope(ref e) => serializer.serialize_newtype_variant("Dynamic", 0, "Envelope", e),
+                        Dynamic::Constant(a) => serializer.serialize_newtype_variant("Dynamic", 1, "Constant", a),
+                        Dynamic::OneShot(ref o) => serializer.serialize_newtype_variant("Dynamic", 2, "OneShot", o),
+                    }
+                }
+            }
+
+            impl serde::Deserialize for Dynamic {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    enum Variant { Envelope, Constant, OneShot }
+
+                    impl serde::de::Deserialize for Variant {
+                        fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                            where D: serde::Deserializer,
+                        {
+                            struct VariantVisitor;
+
+                            impl serde::de::Visitor for VariantVisitor {
+                                type Value = Variant;
+
+                                fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        "Envelope" => Ok(Variant::Envelope),
+                                        "Constant" => Ok(Variant::Constant),
+                                        "OneShot" => Ok(Variant::OneShot),
+                                        _ => Err(serde::de::Error::unknown_field(value)),
+                                    }
+                                }
+
+                                fn visit_usize<E>(&mut self, value: usize) -> Result<Variant, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        0 => Ok(Variant::Envelope),
+                                        1 => Ok(Variant::Constant),
+                                        2 => Ok(Variant::OneShot),
+                                        _ => Err(serde::de::Error::invalid_value("unknown variant index")),
+                                    }
+                                }
+                            }
+
+                            deserializer.deserialize(VariantVisitor)
+                        }
+                    }
+
+                    struct Visitor;
+
+                    impl serde::de::EnumVisitor for Visitor {
+                        type Value = Dynamic;
+
+                        fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                            where V: serde::de::VariantVisitor,
+                        {
+                            match try!(visitor.visit_variant()) {
+                                Variant::Envelope => {
+                                    let env = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::Envelope(env))
+                                },
+                                Variant::Constant => {
+                                    let amp = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::Constant(amp))
+                                },
+                                Variant::OneShot => {
+                                    let one_shot = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::OneShot(one_shot))
+                                },
+                            }
+                        }
+                    }
+
+                    const VARIANTS: &'static [&'static str] = &["Envelope", "Constant", "OneShot"];
+
+                    deserializer.deserialize_enum("Dynamic", VARIANTS, Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                extern crate serde_json;
+
+                let amp = Dynamic::Constant(1.0);
+                let serialized = serde_json::to_string(&amp).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"Constant\":1}", serialized);
+                
+                let deserialized: Dynamic = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(amp, deserialized);
+            }
+        }
+
+
+    }
+
+    mod frequency {
+
+        mod note_sequence {
+            use oscillator::frequency::NoteSequence;
+            use super::super::super::serde;
+
+            impl serde::Serialize for NoteSequence {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    struct Visitor<'a> {
+                        t: &'a NoteSequence,
+                        field_idx: u8,
+                    }
+
+                    impl<'a> serde::ser::MapVisitor for Visitor<'a> {
+                        fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                            where S: serde::Serializer,
+                        {
+                            match self.field_idx {
+                                0 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("notes", &self.t.notes))))
+                                },
+                                1 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("glide", self.t.glide))))
+                                },
+                                _ => Ok(None),
+                            }
+                        }
+
+                        fn len(&self) -> Option<usize> {
+                            Some(2)
+                        }
+                    }
+
+                    serializer.serialize_struct("NoteSequence", Visitor { t: self, field_idx: 0 })
+                }
+            }
+
+            impl serde::Deserialize for NoteSequence {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct Visitor;
+
+                    impl serde::de::Visitor for Visitor {
+                        type Value = NoteSequence;
+
+                        fn visit_seq<V>(&mut self, mut visitor: V) -> Result<NoteSequence, V::Error>
+                            where V: serde::de::SeqVisitor,
+                        {
+                            let notes = try!(visitor.visit());
+                            let glide = try!(visitor.visit());
+
+                            let notes = match notes {
+                                Some(notes) => notes,
+                                None => return Err(serde::de::Error::missing_field("notes")),
+                            };
+
+                            let glide = match glide {
+                                Some(glide) => glide,
+                                None => return Err(serde::de::Error::missing_field("glide")),
+                            };
+
+                            try!(visitor.end());
+
+                            Ok(NoteSequence {
+                                notes: notes,
+                                glide: glide,
+                            })
+                        }
+
+                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<NoteSequence, V::Error>
+                            where V: serde::de::MapVisitor,
+                        {
+                            let mut notes = None;
+                            let mut glide = None;
+
+                            enum Field { Notes, Glide }
+
+                            impl serde::Deserialize for Field {
+                                fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                                    where D: serde::de::Deserializer,
+                                {
+                                    struct FieldVisitor;
+
+                                    impl serde::de::Visitor for FieldVisitor {
+                                        type Value = Field;
+
+                                        fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                            where E: serde::de::Error,
+                                        {
+                                            match value {
+                                                "notes" => Ok(Field::Notes),
+                                                "glide" => Ok(Field::Glide),
+                                                _ => Err(serde::de::Error::custom("expected notes or glide")),
+                                            }
+                                        }
+                                    }
+
+                                    deserializer.deserialize(FieldVisitor)
+                                }
+                            }
+
+                            loop {
+                                match try!(visitor.visit_key()) {
+                                    Some(Field::Notes) => { notes = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Glide) => { glide = Some(try!(visitor.visit_value())); },
+                                    None => { break; }
+                                }
+                            }
+
+                            let notes = match notes {
+                                Some(notes) => notes,
+                                None => return Err(serde::de::Error::missing_field("notes")),
+                            };
+
+                            let glide = match glide {
+                                Some(glide) => glide,
+                                None => return Err(serde::de::Error::missing_field("glide")),
+                            };
+
+                            try!(visitor.end());
+
+                            Ok(NoteSequence {
+                                notes: notes,
+                                glide: glide,
+                            })
+                        }
+                    }
+
+                    static FIELDS: &'static [&'static str] = &["notes", "glide"];
+
+                    deserializer.deserialize_struct("NoteSequence", FIELDS, Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                extern crate serde_json;
+
+                let seq = NoteSequence {
+                    notes: vec![(0.0, 220.0), (1.0, 440.0)],
+                    glide: true,
+                };
+                let serialized = serde_json::to_string(&seq).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"notes\":[[0,220],[1,440]],\"glide\":true}", serialized);
+
+                let deserialized: NoteSequence = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(seq, deserialized);
+            }
+        }
+
+        mod dynamic {
+            use super::super::super::serde;
+            use oscillator::frequency::Dynamic;
+
+            impl serde::Serialize for Dynamic {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    match *self {
+                        Dynamic::Envelope(ref e) => serializer.serialize_newtype_variant("Dynamic", 0, "Envelope", e),
+                        Dynamic::Hz(h) => serializer.serialize_newtype_variant("Dynamic", 1, "Hz", h),
+                        Dynamic::NoteSequence(ref s) => serializer.serialize_newtype_variant("Dynamic", 2, "NoteSequence", s),
+                    }
+                }
+            }
+
+            impl serde::Deserialize for Dynamic {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    enum Variant { Envelope, Hz, NoteSequence }
+
+                    impl serde::de::Deserialize for Variant {
+                        fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                            where D: serde::Deserializer,
+                        {
+                            struct VariantVisitor;
+
+                            impl serde::de::Visitor for VariantVisitor {
+                                type Value = Variant;
+
+                                fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        "Envelope" => Ok(Variant::Envelope),
+                                        "Hz" => Ok(Variant::Hz),
+                                        "NoteSequence" => Ok(Variant::NoteSequence),
+                                        _ => Err(serde::de::Error::unknown_field(value)),
+                                    }
+                                }
+
+                                fn visit_usize<E>(&mut self, value: usize) -> Result<Variant, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        0 => Ok(Variant::Envelope),
+                                        1 => Ok(Variant::Hz),
+                                        2 => Ok(Variant::NoteSequence),
+                                        _ => Err(serde::de::Error::invalid_value("unknown variant index")),
+                                    }
+                                }
+                            }
+
+                            deserializer.deserialize(VariantVisitor)
+                        }
+                    }
+
+                    struct Visitor;
+
+                    impl serde::de::EnumVisitor for Visitor {
+                        type Value = Dynamic;
+
+                        fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                            where V: serde::de::VariantVisitor,
+                        {
+                            match try!(visitor.visit_variant()) {
+                                Variant::Envelope => {
+                                    let env = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::Envelope(env))
+                                },
+                                Variant::Hz => {
+                                    let hz = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::Hz(hz))
+                                },
+                                Variant::NoteSequence => {
+                                    let seq = try!(visitor.visit_newtype());
+                                    Ok(Dynamic::NoteSequence(seq))
+                                },
+                            }
+                        }
+                    }
+
+                    const VARIANTS: &'static [&'static str] = &["Envelope", "Hz", "NoteSequence"];
+
+                    deserializer.deserialize_enum("Dynamic", VARIANTS, Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                extern crate serde_json;
+
+                let hz = Dynamic::Hz(440.0);
+                let serialized = serde_json::to_string(&hz).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"Hz\":440}", serialized);
+                
+                let deserialized: Dynamic = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(hz, deserialized);
+            }
+        }
+
+    }
+
+    mod oscillator {
+
+        mod state {
+            use oscillator::State;
+            use super::super::super::serde;
+
+            impl serde::Serialize for State {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    struct Visitor<'a> {
+                        t: &'a State,
+                        field_idx: u8,
+                    }
+
+                    impl<'a> serde::ser::MapVisitor for Visitor<'a> {
+                        fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                            where S: serde::Serializer,
+                        {
+                            match self.field_idx {
+                                0 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("phase", self.t.phase))))
+                                },
+                                1 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("freq_warp_phase",
+                                                                                 self.t.freq_warp_phase))))
+                                },
+                                2 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("rng", self.t.rng))))
+                                },
+                                3 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("note_held",
+                                                                                 self.t.note_held))))
+                                },
+                                4 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("last_hz",
+                                                                                 self.t.last_hz))))
+                                },
+                                5 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("max_hz_per_sample",
+                                                                                 self.t.max_hz_per_sample))))
+                                },
+                                _ => Ok(None),
+                            }
+                        }
+
+                        fn len(&self) -> Option<usize> {
+                            Some(6)
+                        }
+                    }
+
+                    serializer.serialize_struct("State", Visitor { t: self, field_idx: 0 })
+                }
+            }
+
+            impl serde::Deserialize for State {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct Visitor;
+
+                    impl serde::de::Visitor for Visitor {
+                        type Value = State;
+
+                        fn visit_seq<V>(&mut self, mut visitor: V) -> Result<State, V::Error>
+                            where V: serde::de::SeqVisitor,
+                        {
+                            let phase = try!(visitor.visit());
+                            let freq_warp_phase = try!(visitor.visit());
+                            let rng = try!(visitor.visit());
+                            let note_held = try!(visitor.visit());
+                            let last_hz = try!(visitor.visit());
+                            let max_hz_per_sample = try!(visitor.visit());
+
+                            let phase = match phase {
+                                Some(phase) => phase,
+                                None => return Err(serde::de::Error::missing_field("phase")),
+                            };
+
+                            let freq_warp_phase = match freq_warp_phase {
+                                Some(freq_warp_phase) => freq_warp_phase,
+                                None => return Err(serde::de::Error::missing_field("freq_warp_phase")),
+                            };
+
+                            let rng = rng.unwrap_or(None);
+
+                            let note_held = note_held.unwrap_or(false);
+
+                            let last_hz = last_hz.unwrap_or(None);
+
+                            let max_hz_per_sample = max_hz_per_sample.unwrap_or(None);
+
+                            try!(visitor.end());
+
+                            Ok(State {
+                                phase: phase,
+                                freq_warp_phase: freq_warp_phase,
+                                rng: rng,
+                                note_held: note_held,
+                                last_hz: last_hz,
+                                max_hz_per_sample: max_hz_per_sample,
+                            })
+                        }
+
+                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<State, V::Error>
+                            where V: serde::de::MapVisitor,
+                        {
+                            let mut phase = None;
+                            let mut freq_warp_phase = None;
+                            let mut rng = None;
+                            let mut note_held = None;
+                            let mut last_hz = None;
+                            let mut max_hz_per_sample = None;
+
+                            enum Field { Phase, FreqWarpPhase, Rng, NoteHeld, LastHz, MaxHzPerSample }
+
+                            impl serde::Deserialize for Field {
+                                fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                                    where D: serde::de::Deserializer,
+                                {
+                                    struct FieldVisitor;
+
+                                    impl serde::de::Visitor for FieldVisitor {
+                                        type Value = Field;
+
+                                        fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                            where E: serde::de::Error,
+                                        {
+                                            match value {
+                                                "phase" => Ok(Field::Phase),
+                                                "freq_warp_phase" => Ok(Field::FreqWarpPhase),
+                                                "rng" => Ok(Field::Rng),
+                                                "note_held" => Ok(Field::NoteHeld),
+                                                "last_hz" => Ok(Field::LastHz),
+                                                "max_hz_per_sample" => Ok(Field::MaxHzPerSample),
+                                                _ => Err(serde::de::Error::custom(
+                                                    "expected phase, freq_warp_phase, rng, note_held, last_hz or \
+                                                    max_hz_per_sample"
+                                                )),
+                                            }
+                                        }
+                                    }
+
+                                    deserializer.deserialize(FieldVisitor)
+                                }
+                            }
+
+                            loop {
+                                match try!(visitor.visit_key()) {
+                                    Some(Field::Phase) => { phase = Some(try!(visitor.visit_value())); },
+                                    Some(Field::FreqWarpPhase) => { freq_warp_phase = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Rng) => { rng = Some(try!(visitor.visit_value())); },
+                                    Some(Field::NoteHeld) => { note_held = Some(try!(visitor.visit_value())); },
+                                    Some(Field::LastHz) => { last_hz = Some(try!(visitor.visit_value())); },
+                                    Some(Field::MaxHzPerSample) => { max_hz_per_sample = Some(try!(visitor.visit_value())); },
+                                    None => { break; }
+                                }
+                            }
+
+                            let phase = match phase {
+                                Some(phase) => phase,
+                                None => return Err(serde::de::Error::missing_field("phase")),
+                            };
+
+                            let freq_warp_phase = match freq_warp_phase {
+                                Some(freq_warp_phase) => freq_warp_phase,
+                                None => return Err(serde::de::Error::missing_field("freq_warp_phase")),
+                            };
+
+                            let rng = rng.unwrap_or(None);
+
+                            let note_held = note_held.unwrap_or(false);
+
+                            let last_hz = last_hz.unwrap_or(None);
+
+                            let max_hz_per_sample = max_hz_per_sample.unwrap_or(None);
+
+                            try!(visitor.end());
+
+                            Ok(State {
+                                phase: phase,
+                                freq_warp_phase: freq_warp_phase,
+                                rng: rng,
+                                note_held: note_held,
+                                last_hz: last_hz,
+                                max_hz_per_sample: max_hz_per_sample,
+                            })
+                        }
+                    }
+
+                    static FIELDS: &'static [&'static str] = &["phase", "freq_warp_phase", "rng", "note_held", "last_hz", "max_hz_per_sample"];
+
+                    deserializer.deserialize_struct("State", FIELDS, Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                extern crate serde_json;
+
+                let state = State {
+                    phase: 0.0,
+                    freq_warp_phase: 0.0,
+                    rng: Some(42),
+                    note_held: true,
+                    last_hz: Some(440.0),
+                    max_hz_per_sample: None,
+                };
+                let serialized = serde_json::to_string(&state).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"phase\":0,\"freq_warp_phase\":0,\"rng\":42,\"note_held\":true,\
+                            \"last_hz\":440,\"max_hz_per_sample\":null}", serialized);
+                
+                let deserialized: State = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(state, deserialized);
+            }
+        }
+
+        mod state_per_voice {
+            use oscillator::StatePerVoice;
+            use super::super::super::serde;
+
+            impl serde::Serialize for StatePerVoice {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    serializer.serialize_newtype_struct("StatePerVoice", &self.0)
+                }
+            }
+
+            impl serde::Deserialize for StatePerVoice {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct Visitor;
+
+                    impl serde::de::Visitor for Visitor {
+                        type Value = StatePerVoice;
+
+                        fn visit_newtype_struct<D>(&mut self, deserializer: &mut D) -> Result<Self::Value, D::Error>
+                            where D: serde::Deserializer,
+                        {
+                            Ok(StatePerVoice(try!(serde::de::Deserialize::deserialize(deserializer))))
+                        }
+                    }
+
+                    deserializer.deserialize_newtype_struct("StatePerVoice", Visitor)
+                }
+            }
+
+            #[test]
+            fn test() {
+                extern crate serde_json;
+
+                let state_per_voice = StatePerVoice(vec![]);
+                let serialized = serde_json::to_string(&state_per_voice).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("[]", &serialized);
+
+                let deserialized: StatePerVoice = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(state_per_voice, deserialized);
+            }
+        }
+
+        mod oscillator {
+            use oscillator::Oscillator;
+            use super::super::super::serde;
+            use std;
+
+            impl<W, A, F, FW> serde::Serialize for Oscillator<W, A, F, FW>
+                where W: serde::Serialize,
+                      A: serde::Serialize,
+                      F: serde::Serialize,
+                      FW: serde::Serialize,
+            {
+                fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+                    where S: serde::Serializer,
+                {
+                    struct Visitor<'a, W: 'a, A: 'a, F: 'a, FW: 'a> {
+                        t: &'a Oscillator<W, A, F, FW>,
+                        field_idx: u8,
+                    }
+
+                    impl<'a, W, A, F, FW> serde::ser::MapVisitor for Visitor<'a, W, A, F, FW>
+                        where W: serde::Serialize,
+                              A: serde::Serialize,
+                              F: serde::Serialize,
+                              FW: serde::Serialize,
+                    {
+                        fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                            where S: serde::Serializer,
+                        {
+                            match self.field_idx {
+                                0 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("waveform",
+                                                                                 &self.t.waveform))))
+                                },
+                                1 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("amplitude",
+                                                                                 &self.t.amplitude))))
+                                },
+                                2 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("frequency",
+                                                                                 &self.t.frequency))))
+                                },
+                                3 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("freq_warp",
+                                                                                 &self.t.freq_warp))))
+                                },
+                                4 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("is_muted",
+                                                                                 self.t.is_muted))))
+                                },
+                                5 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("ratio", self.t.ratio))))
+                                },
+                                6 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("fixed_hz", &self.t.fixed_hz))))
+                                },
+                                7 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("free_running",
+                                                                                 self.t.free_running))))
+                                },
+                                8 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("phase_offset", self.t.phase_offset))))
+                                },
+                                9 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("waveform_crossfade_ms", self.t.waveform_crossfade_ms))))
+                                },
+                                10 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("previous_waveform", &self.t.previous_waveform))))
+                                },
+                                11 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("waveform_fade", self.t.waveform_fade))))
+                                },
+                                12 => {
+                                    self.field_idx += 1;
+                                    Ok(Some(try!(serializer.serialize_struct_elt("quadrature", self.t.quadrature))))
+                                },
+                                _ => Ok(None),
+                            }
+                        }
+
+                        fn len(&self) -> Option<usize> {
+                            Some(13)
+                        }
+                    }
+
+                    serializer.serialize_struct("Oscillator", Visitor { t: self, field_idx: 0 })
+                }
+            }
+
+            impl<W, A, F, FW> serde::Deserialize for Oscillator<W, A, F, FW>
+                where W: serde::Deserialize,
+                      A: serde::Deserialize,
+                      F: serde::Deserialize,
+                      FW: serde::Deserialize,
+            {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct Visitor<W, A, F, FW> {
+                        w: std::marker::PhantomData<W>,
+                        a: std::marker::PhantomData<A>,
+                        f: std::marker::PhantomData<F>,
+                        fw: std::marker::PhantomData<FW>,
+                    }
+
+                    impl<W, A, F, FW> serde::de::Visitor for Visitor<W, A, F, FW>
+                        where W: serde::Deserialize,
+                              A: serde::Deserialize,
+                              F: serde::Deserialize,
+                              FW: serde::Deserialize,
+                    {
+                        type Value = Oscillator<W, A, F, FW>;
+
+                        fn visit_seq<V>(&mut self, mut visitor: V) -> Result<Oscillator<W, A, F, FW>, V::Error>
+                            where V: serde::de::SeqVisitor,
+                        {
+                            let waveform = try!(visitor.visit());
+                            let amplitude = try!(visitor.visit());
+                            let frequency = try!(visitor.visit());
+                            let freq_warp = try!(visitor.visit());
+                            let is_muted = try!(visitor.visit());
+                            let ratio = try!(visitor.visit());
+                            let fixed_hz = try!(visitor.visit());
+                            let free_running = try!(visitor.visit());
+                            let phase_offset = try!(visitor.visit());
+                            let waveform_crossfade_ms = try!(visitor.visit());
+                            let previous_waveform = try!(visitor.visit());
+                            let waveform_fade = try!(visitor.visit());
+                            let quadrature = try!(visitor.visit());
+
+                            let waveform = match waveform {
+                                Some(waveform) => waveform,
+                                None => return Err(serde::de::Error::missing_field("waveform")),
+                            };
+
+                            let amplitude = match amplitude {
+                                Some(amplitude) => amplitude,
+                                None => return Err(serde::de::Error::missing_field("amplitude")),
+                            };
+
+                            let frequency = match frequency {
+                                Some(frequency) => frequency,
+                                None => return Err(serde::de::Error::missing_field("frequency")),
+                            };
+
+                            let freq_warp = match freq_warp {
+                                Some(freq_warp) => freq_warp,
+                                None => return Err(serde::de::Error::missing_field("freq_warp")),
+                            };
+
+                            let is_muted = match is_muted {
+                                Some(is_muted) => is_muted,
+                                None => return Err(serde::de::Error::missing_field("is_muted")),
+                            };
+
+                            let ratio = ratio.unwrap_or(1.0);
+
+                            let fixed_hz = fixed_hz.unwrap_or(None);
+
+                            let free_running = free_running.unwrap_or(false);
+
+                            let phase_offset = phase_offset.unwrap_or(0.0);
+
+                            let waveform_crossfade_ms = waveform_crossfade_ms.unwrap_or(0.0);
+
+                            let previous_waveform = previous_waveform.unwrap_or(None);
+
+                            let waveform_fade = waveform_fade.unwrap_or(0.0);
+
+                            let quadrature = quadrature.unwrap_or(false);
+
+                            try!(visitor.end());
+
+                            Ok(Oscillator {
+                                waveform: waveform,
+                                amplitude: amplitude,
+                                frequency: frequency,
+                                freq_warp: freq_warp,
+                                is_muted: is_muted,
+                                ratio: ratio,
+                                fixed_hz: fixed_hz,
+                                free_running: free_running,
+                                phase_offset: phase_offset,
+                                waveform_crossfade_ms: waveform_crossfade_ms,
+                                previous_waveform: previous_waveform,
+                                waveform_fade: waveform_fade,
+                                quadrature: quadrature,
+                            })
+                        }
+
+                        fn visit_map<V>(&mut self, mut visitor: V) -> Result<Oscillator<W, A, F, FW>, V::Error>
+                            where V: serde::de::MapVisitor,
+                        {
+                            let mut waveform = None;
+                            let mut amplitude = None;
+                            let mut frequency = None;
+                            let mut freq_warp = None;
+                            let mut is_muted = None;
+                            let mut ratio = None;
+                            let mut fixed_hz = None;
+                            let mut free_running = None;
+                            let mut phase_offset = None;
+                            let mut waveform_crossfade_ms = None;
+                            let mut previous_waveform = None;
+                            let mut waveform_fade = None;
+                            let mut quadrature = None;
+
+                            enum Field {
+                                Waveform,
+                                Amplitude,
+                                Frequency,
+                                FreqWarp,
+                                IsMuted,
+                                Ratio,
+                                FixedHz,
+                                FreeRunning,
+                                PhaseOffset,
+                                WaveformCrossfadeMs,
+                                PreviousWaveform,
+                                WaveformFade,
+                                Quadrature,
+                            }
+
+                            impl serde::Deserialize for Field {
+                                fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                                    where D: serde::de::Deserializer,
+                                {
+                                    struct FieldVisitor;
+
+                                    impl serde::de::Visitor for FieldVisitor {
+                                        type Value = Field;
+
+                                        fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                            where E: serde::de::Error,
+                                        {
+                                            match value {
+                                                "waveform" => Ok(Field::Waveform),
+                                                "amplitude" => Ok(Field::Amplitude),
+                                                "frequency" => Ok(Field::Frequency),
+                                                "freq_warp" => Ok(Field::FreqWarp),
+                                                "is_muted" => Ok(Field::IsMuted),
+                                                "ratio" => Ok(Field::Ratio),
+                                                "fixed_hz" => Ok(Field::FixedHz),
+                                                "free_running" => Ok(Field::FreeRunning),
+                                                "phase_offset" => Ok(Field::PhaseOffset),
+                                                "waveform_crossfade_ms" => Ok(Field::WaveformCrossfadeMs),
+                                                "previous_waveform" => Ok(Field::PreviousWaveform),
+                                                "waveform_fade" => Ok(Field::WaveformFade),
+                                                "quadrature" => Ok(Field::Quadrature),
+                                                _ => Err(serde::de::Error::custom(
+                                                    "expected waveform, amplitude, frequency, freq_warp, \
+                                                    is_muted, ratio, fixed_hz, free_running, phase_offset, \
+                                                    waveform_crossfade_ms, previous_waveform, waveform_fade or \
+                                                    quadrature"
+                                                )),
+                                            }
+                                        }
+                                    }
+
+                                    deserializer.deserialize(FieldVisitor)
+                                }
+                            }
+
+                            loop {
+                                match try!(visitor.visit_key()) {
+                                    Some(Field::Waveform) => { waveform = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Amplitude) => { amplitude = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Frequency) => { frequency = Some(try!(visitor.visit_value())); },
+                                    Some(Field::FreqWarp) => { freq_warp = Some(try!(visitor.visit_value())); },
+                                    Some(Field::IsMuted) => { is_muted = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Ratio) => { ratio = Some(try!(visitor.visit_value())); },
+                                    Some(Field::FixedHz) => { fixed_hz = Some(try!(visitor.visit_value())); },
+                                    Some(Field::FreeRunning) => { free_running = Some(try!(visitor.visit_value())); },
+                                    Some(Field::PhaseOffset) => { phase_offset = Some(try!(visitor.visit_value())); },
+                                    Some(Field::WaveformCrossfadeMs) => { waveform_crossfade_ms = Some(try!(visitor.visit_value())); },
+                                    Some(Field::PreviousWaveform) => { previous_waveform = Some(try!(visitor.visit_value())); },
+                                    Some(Field::WaveformFade) => { waveform_fade = Some(try!(visitor.visit_value())); },
+                                    Some(Field::Quadrature) => { quadrature = Some(try!(visitor.visit_value())); },
+                                    None => { break; }
+                                }
+                            }
+
+                            let waveform = match waveform {
+                                Some(waveform) => waveform,
+                                None => return Err(serde::de::Error::missing_field("waveform")),
+                            };
+
+                            let amplitude = match amplitude {
+                                Some(amplitude) => amplitude,
+                                None => return Err(serde::de::Error::missing_field("amplitude")),
+                            };
+
+                            let frequency = match frequency {
+                                Some(frequency) => frequency,
+                                None => return Err(serde::de::Error::missing_field("frequency")),
+                            };
+
+                            let freq_warp = match freq_warp {
+                                Some(freq_warp) => freq_warp,
+                                None => return Err(serde::de::Error::missing_field("freq_warp")),
+                            };
+
+                            let is_muted = match is_muted {
+                                Some(is_muted) => is_muted,
+                                None => return Err(serde::de::Error::missing_field("is_muted")),
+                            };
+
+                            let ratio = ratio.unwrap_or(1.0);
+
+                            let fixed_hz = fixed_hz.unwrap_or(None);
+
+                            let free_running = free_running.unwrap_or(false);
+
+                            let phase_offset = phase_offset.unwrap_or(0.0);
+
+                            let waveform_crossfade_ms = waveform_crossfade_ms.unwrap_or(0.0);
+
+                            let previous_waveform = previous_waveform.unwrap_or(None);
+
+                            let waveform_fade = waveform_fade.unwrap_or(0.0);
+
+                            let quadrature = quadrature.unwrap_or(false);
+
+                            try!(visitor.end());
+
+                            Ok(Oscillator {
+                                waveform: waveform,
+                                amplitude: amplitude,
+                                frequency: frequency,
+                                freq_warp: freq_warp,
+                                is_muted: is_muted,
+                                ratio: ratio,
+                                fixed_hz: fixed_hz,
+                                free_running: free_running,
+                                phase_offset: phase_offset,
+                                waveform_crossfade_ms: waveform_crossfade_ms,
+                                previous_waveform: previous_waveform,
+                                waveform_fade: waveform_fade,
+                                quadrature: quadrature,
+                            })
+                        }
+                    }
+
+                    static FIELDS: &'static [&'static str] = &[
+                        "waveform",
+                        "amplitude",
+                        "frequency",
+                        "freq_warp",
+                        "is_muted",
+                        "ratio",
+                        "fixed_hz",
+                        "free_running",
+                        "phase_offset",
+                        "waveform_crossfade_ms",
+                        "previous_waveform",
+                        "waveform_fade",
+                        "quadrature",
+                    ];
+
+                    deserializer.deserialize_struct("Oscillator", FIELDS, Visitor {
+                        w: std::marker::PhantomData,
+                        a: std::marker::PhantomData,
+                        f: std::marker::PhantomData,
+                        fw: std::marker::PhantomData,
+                    })
+                }
+            }
+
+            #[test]
+            fn test() {
+                use oscillator::waveform;
+
+                extern crate serde_json;
+
+                let osc = Oscillator::new(waveform::Sine, 1.0, 440.0, ());
+                let serialized = serde_json::to_string(&osc).unwrap();
+
+                println!("{}", serialized);
+                assert_eq!("{\"waveform\":null,\"amplitude\":1,\"frequency\":440,\"freq_warp\":null,\"is_muted\":false,\"ratio\":1,\"fixed_hz\":null,\"free_running\":false,\
+                            \"phase_offset\":0,\"waveform_crossfade_ms\":0,\"previous_waveform\":null,\"waveform_fade\":0,\
+                            \"quadrature\":false}", serialized);
+                
+                let deserialized: Oscillator<waveform::Sine, f32, f64, ()> = serde_json::from_str(&serialized).unwrap();
+
+                println!("{:?}", deserialized);
+                assert_eq!(osc, deserialized);
+            }
+        }
+
+    }
+
+}
+
+mod arpeggiator {
+    use arpeggiator::{ArpPattern, Arpeggiator};
+    use super::serde;
+
+    impl serde::Serialize for ArpPattern {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            match *self {
+                ArpPattern::Up => serializer.serialize_unit_variant("ArpPattern", 0, "Up"),
+                ArpPattern::Down => serializer.serialize_unit_variant("ArpPattern", 1, "Down"),
+                ArpPattern::UpDown => serializer.serialize_unit_variant("ArpPattern", 2, "UpDown"),
+            }
+        }
+    }
+
+    impl serde::Deserialize for ArpPattern {
+        fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where D: serde::Deserializer,
+        {
+            enum Variant { Up, Down, UpDown }
+
+            impl serde::de::Deserialize for Variant {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct VariantVisitor;
+
+                    impl serde::de::Visitor for VariantVisitor {
+                        type Value = Variant;
+
+                        fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                "Up" => Ok(Variant::Up),
+                                "Down" => Ok(Variant::Down),
+                                "UpDown" => Ok(Variant::UpDown),
+                                _ => Err(serde::de::Error::unknown_field(value)),
+                            }
+                        }
+
+                        fn visit_usize<E>(&mut self, value: usize) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                0 => Ok(Variant::Up),
+                                1 => Ok(Variant::Down),
+                                2 => Ok(Variant::UpDown),
+                                _ => Err(serde::de::Error::invalid_value("unknown variant index")),
+                            }
+                        }
+                    }
+
+                    deserializer.deserialize(VariantVisitor)
+                }
+            }
+
+            struct Visitor;
+
+            impl serde::de::EnumVisitor for Visitor {
+                type Value = ArpPattern;
+
+                fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                    where V: serde::de::VariantVisitor,
+                {
+                    match try!(visitor.visit_variant()) {
+                        Variant::Up => {
+                            try!(visitor.visit_unit());
+                            Ok(ArpPattern::Up)
+                        },
+                        Variant::Down => {
+                            try!(visitor.visit_unit());
+                            Ok(ArpPattern::Down)
+                        },
+                        Variant::UpDown => {
+                            try!(visitor.visit_unit());
+                            Ok(ArpPattern::UpDown)
+                        },
+                    }
+                }
+            }
+
+            const VARIANTS: &'static [&'static str] = &[
+                "Up", "Down", "UpDown"
+            ];
+
+            deserializer.deserialize_enum("ArpPattern", VARIANTS, Visitor)
+        }
+    }
+
+    impl serde::Serialize for Arpeggiator {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            struct Visitor<'a> {
+                t: &'a Arpeggiator,
+                field_idx: u8,
+            }
+
+            impl<'a> serde::ser::MapVisitor for Visitor<'a> {
+                fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                    where S: serde::Serializer,
+                {
+                    match self.field_idx {
+                        0 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("rate", self.t.rate))))
+                        },
+                        1 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("pattern", self.t.pattern))))
+                        },
+                        2 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("held_notes", &self.t.held_notes))))
+                        },
+                        3 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("step", self.t.step))))
+                        },
+                        4 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("samples_until_step", self.t.samples_until_step))))
+                        },
+                        5 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("playing", self.t.playing))))
+                        },
+                        _ => Ok(None),
+                    }
+                }
+
+                fn len(&self) -> Option<usize> {
+                    Some(6)
+                }
+            }
+
+            serializer.serialize_struct("Arpeggiator", Visitor { t: self, field_idx: 0 })
+        }
+    }
+
+    impl serde::Deserialize for Arpeggiator {
+        fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where D: serde::Deserializer,
+        {
+            struct Visitor;
+
+            impl serde::de::Visitor for Visitor {
+                type Value = Arpeggiator;
+
+                fn visit_seq<V>(&mut self, mut visitor: V) -> Result<Arpeggiator, V::Error>
+                    where V: serde::de::SeqVisitor,
+                {
+                    let rate = try!(visitor.visit());
+                    let pattern = try!(visitor.visit());
+                    let held_notes = try!(visitor.visit());
+                    let step = try!(visitor.visit());
+                    let samples_until_step = try!(visitor.visit());
+                    let playing = try!(visitor.visit());
+
+                    let rate = match rate {
+                        Some(rate) => rate,
+                        None => return Err(serde::de::Error::missing_field("rate")),
+                    };
+
+                    let pattern = match pattern {
+                        Some(pattern) => pattern,
+                        None => return Err(serde::de::Error::missing_field("pattern")),
+                    };
+
+                    let held_notes = match held_notes {
+                        Some(held_notes) => held_notes,
+                        None => return Err(serde::de::Error::missing_field("held_notes")),
+                    };
+
+                    let step = match step {
+                        Some(step) => step,
+                        None => return Err(serde::de::Error::missing_field("step")),
+                    };
+
+                    let samples_until_step = match samples_until_step {
+                        Some(samples_until_step) => samples_until_step,
+                        None => return Err(serde::de::Error::missing_field("samples_until_step")),
+                    };
+
+                    let playing = match playing {
+                        Some(playing) => playing,
+                        None => return Err(serde::de::Error::missing_field("playing")),
+                    };
+
+                    try!(visitor.end());
+
+                    Ok(Arpeggiator {
+                        rate: rate,
+                        pattern: pattern,
+                        held_notes: held_notes,
+                        step: step,
+                        samples_until_step: samples_until_step,
+                        playing: playing,
+                    })
+                }
+
+                fn visit_map<V>(&mut self, mut visitor: V) -> Result<Arpeggiator, V::Error>
+                    where V: serde::de::MapVisitor,
+                {
+                    let mut rate = None;
+                    let mut pattern = None;
+                    let mut held_notes = None;
+                    let mut step = None;
+                    let mut samples_until_step = None;
+                    let mut playing = None;
+
+                    enum Field { Rate, Pattern, HeldNotes, Step, SamplesUntilStep, Playing }
+
+                    impl serde::Deserialize for Field {
+                        fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                            where D: serde::de::Deserializer,
+                        {
+                            struct FieldVisitor;
+
+                            impl serde::de::Visitor for FieldVisitor {
+                                type Value = Field;
+
+                                fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        "rate" => Ok(Field::Rate),
+                                        "pattern" => Ok(Field::Pattern),
+                                        "held_notes" => Ok(Field::HeldNotes),
+                                        "step" => Ok(Field::Step),
+                                        "samples_until_step" => Ok(Field::SamplesUntilStep),
+                                        "playing" => Ok(Field::Playing),
+                                        _ => Err(serde::de::Error::custom(
+                                            "expected rate, pattern, held_notes, step, samples_until_step \
+                                            or playing"
+                                        )),
+                                    }
+                                }
+                            }
+
+                            deserializer.deserialize(FieldVisitor)
+                        }
+                    }
+
+                    loop {
+                        match try!(visitor.visit_key()) {
+                            Some(Field::Rate) => { rate = Some(try!(visitor.visit_value())); },
+                            Some(Field::Pattern) => { pattern = Some(try!(visitor.visit_value())); },
+                            Some(Field::HeldNotes) => { held_notes = Some(try!(visitor.visit_value())); },
+                            Some(Field::Step) => { step = Some(try!(visitor.visit_value())); },
+                            Some(Field::SamplesUntilStep) => { samples_until_step = Some(try!(visitor.visit_value())); },
+                            Some(Field::Playing) => { playing = Some(try!(visitor.visit_value())); },
+                            None => { break; }
+                        }
+                    }
+
+                    let rate = match rate {
+                        Some(rate) => rate,
+                        None => return Err(serde::de::Error::missing_field("rate")),
+                    };
+
+                    let pattern = match pattern {
+                        Some(pattern) => pattern,
+                        None => return Err(serde::de::Error::missing_field("pattern")),
+                    };
+
+                    let held_notes = match held_notes {
+                        Some(held_notes) => held_notes,
+                        None => return Err(serde::de::Error::missing_field("held_notes")),
+                    };
+
+                    let step = match step {
+                        Some(step) => step,
+                        None => return Err(serde::de::Error::missing_field("step")),
+                    };
+
+                    let samples_until_step = match samples_until_step {
+                        Some(samples_until_step) => samples_until_step,
+                        None => return Err(serde::de::Error::missing_field("samples_until_step")),
+                    };
+
+                    let playing = match playing {
+                        Some(playing) => playing,
+                        None => return Err(serde::de::Error::missing_field("playing")),
+                    };
+
+                    try!(visitor.end());
+
+                    Ok(Arpeggiator {
+                        rate: rate,
+                        pattern: pattern,
+                        held_notes: held_notes,
+                        step: step,
+                        samples_until_step: samples_until_step,
+                        playing: playing,
+                    })
+                }
+            }
+
+            static FIELDS: &'static [&'static str] = &[
+                "rate",
+                "pattern",
+                "held_notes",
+                "step",
+                "samples_until_step",
+                "playing",
+            ];
+
+            deserializer.deserialize_struct("Arpeggiator", FIELDS, Visitor)
+        }
+    }
+
+    #[test]
+    fn test() {
+        use time;
+        extern crate serde_json;
+
+        let mut arp = Arpeggiator::new(time::Ms(125.0), ArpPattern::UpDown);
+        arp.note_on(440.0, 1.0);
+        let serialized = serde_json::to_string(&arp).unwrap();
+
+        println!("{}", serialized);
+
+        let deserialized: Arpeggiator = serde_json::from_str(&serialized).unwrap();
+
+        println!("{:?}", deserialized);
+        assert_eq!(arp, deserialized);
+    }
+}
+
+mod loop_mode {
+    use super::serde;
+    use synth::LoopMode;
+
+    impl serde::Serialize for LoopMode {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            match *self {
+                LoopMode::Forward => serializer.serialize_unit_variant("LoopMode", 0, "Forward"),
+                LoopMode::PingPong => serializer.serialize_unit_variant("LoopMode", 1, "PingPong"),
+            }
+        }
+    }
+
+    impl serde::Deserialize for LoopMode {
+        fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where D: serde::Deserializer,
+        {
+            enum Variant { Forward, PingPong }
+
+            impl serde::de::Deserialize for Variant {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct VariantVisitor;
+
+                    impl serde::de::Visitor for VariantVisitor {
+                        type Value = Variant;
+
+                        fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                "Forward" => Ok(Variant::Forward),
+                                "PingPong" => Ok(Variant::PingPong),
+                                _ => Err(serde::de::Error::unknown_field(value)),
+                            }
+                        }
+
+                        fn visit_usize<E>(&mut self, value: usize) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                0 => Ok(Variant::Forward),
+                                1 => Ok(Variant::PingPong),
+                                _ => Err(serde::de::Error::invalid_value("unknown variant index")),
+                            }
+                        }
+                    }
+
+                    deserializer.deserialize(VariantVisitor)
+                }
+            }
+
+            struct Visitor;
+
+            impl serde::de::EnumVisitor for Visitor {
+                type Value = LoopMode;
+
+                fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                    where V: serde::de::VariantVisitor,
+                {
+                    match try!(visitor.visit_variant()) {
+                        Variant::Forward => {
+                            try!(visitor.visit_unit());
+                            Ok(LoopMode::Forward)
+                        },
+                        Variant::PingPong => {
+                            try!(visitor.visit_unit());
+                            Ok(LoopMode::PingPong)
+                        },
+                    }
+                }
+            }
+
+            const VARIANTS: &'static [&'static str] = &[
+                "Forward", "PingPong"
+            ];
+
+            deserializer.deserialize_enum("LoopMode", VARIANTS, Visitor)
+        }
+    }
+
+    #[test]
+    fn test() {
+        extern crate serde_json;
+
+        let loop_mode = LoopMode::PingPong;
+        let serialized = serde_json::to_string(&loop_mode).unwrap();
+
+        println!("{}", serialized);
+        assert_eq!("\"PingPong\"", serialized);
+
+        let deserialized: LoopMode = serde_json::from_str(&serialized).unwrap();
+
+        println!("{:?}", deserialized);
+        assert_eq!(loop_mode, deserialized);
+    }
+}
+
+mod pan_law {
+    use super::serde;
+    use synth::PanLaw;
+
+    impl serde::Serialize for PanLaw {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            match *self {
+                PanLaw::ConstantPower => serializer.serialize_unit_variant("PanLaw", 0, "ConstantPower"),
+                PanLaw::ConstantGain => serializer.serialize_unit_variant("PanLaw", 1, "ConstantGain"),
+            }
+        }
+    }
+
+    impl serde::Deserialize for PanLaw {
+        fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where D: serde::Deserializer,
+        {
+            enum Variant { ConstantPower, ConstantGain }
+
+            impl serde::de::Deserialize for Variant {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct VariantVisitor;
+
+                    impl serde::de::Visitor for VariantVisitor {
+                        type Value = Variant;
+
+                        fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                "ConstantPower" => Ok(Variant::ConstantPower),
+                                "ConstantGain" => Ok(Variant::ConstantGain),
+                                _ => Err(serde::de::Error::unknown_field(value)),
+                            }
+                        }
+
+                        fn visit_usize<E>(&mut self, value: usize) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                0 => Ok(Variant::ConstantPower),
+                                1 => Ok(Variant::ConstantGain),
+                                _ => Err(serde::de::Error::invalid_value("unknown variant index")),
+                            }
+                        }
+                    }
+
+                    deserializer.deserialize(VariantVisitor)
+                }
+            }
+
+            struct Visitor;
+
+            impl serde::de::EnumVisitor for Visitor {
+                type Value = PanLaw;
+
+                fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                    where V: serde::de::VariantVisitor,
+                {
+                    match try!(visitor.visit_variant()) {
+                        Variant::ConstantPower => {
+                            try!(visitor.visit_unit());
+                            Ok(PanLaw::ConstantPower)
+                        },
+                        Variant::ConstantGain => {
+                            try!(visitor.visit_unit());
+                            Ok(PanLaw::ConstantGain)
+                        },
+                    }
+                }
+            }
+
+            const VARIANTS: &'static [&'static str] = &[
+                "ConstantPower", "ConstantGain"
+            ];
+
+            deserializer.deserialize_enum("PanLaw", VARIANTS, Visitor)
+        }
+    }
+
+    #[test]
+    fn test() {
+        extern crate serde_json;
+
+        let pan_law = PanLaw::ConstantGain;
+        let serialized = serde_json::to_string(&pan_law).unwrap();
+
+        println!("{}", serialized);
+        assert_eq!("\"ConstantGain\"", serialized);
+
+        let deserialized: PanLaw = serde_json::from_str(&serialized).unwrap();
+
+        println!("{:?}", deserialized);
+        assert_eq!(pan_law, deserialized);
+    }
+}
+
+mod spread_shape {
+    use super::serde;
+    use synth::SpreadShape;
+
+    impl serde::Serialize for SpreadShape {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            match *self {
+                SpreadShape::Linear => serializer.serialize_unit_variant("SpreadShape", 0, "Linear"),
+                SpreadShape::Centered => serializer.serialize_unit_variant("SpreadShape", 1, "Centered"),
+                SpreadShape::Alternating => serializer.serialize_unit_variant("SpreadShape", 2, "Alternating"),
+            }
+        }
+    }
+
+    impl serde::Deserialize for SpreadShape {
+        fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where D: serde::Deserializer,
+        {
+            enum Variant { Linear, Centered, Alternating }
+
+            impl serde::de::Deserialize for Variant {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct VariantVisitor;
+
+                    impl serde::de::Visitor for VariantVisitor {
+                        type Value = Variant;
+
+                        fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                "Linear" => Ok(Variant::Linear),
+                                "Centered" => Ok(Variant::Centered),
+                                "Alternating" => Ok(Variant::Alternating),
+                                _ => Err(serde::de::Error::unknown_field(value)),
+                            }
+                        }
+
+                        fn visit_usize<E>(&mut self, value: usize) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                0 => Ok(Variant::Linear),
+                                1 => Ok(Variant::Centered),
+                                2 => Ok(Variant::Alternating),
+                                _ => Err(serde::de::Error::invalid_value("unknown variant index")),
+                            }
+                        }
+                    }
+
+                    deserializer.deserialize(VariantVisitor)
+                }
+            }
+
+            struct Visitor;
+
+            impl serde::de::EnumVisitor for Visitor {
+                type Value = SpreadShape;
+
+                fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                    where V: serde::de::VariantVisitor,
+                {
+                    match try!(visitor.visit_variant()) {
+                        Variant::Linear => {
+                            try!(visitor.visit_unit());
+                            Ok(SpreadShape::Linear)
+                        },
+                        Variant::Centered => {
+                            try!(visitor.visit_unit());
+                            Ok(SpreadShape::Centered)
+                        },
+                        Variant::Alternating => {
+                            try!(visitor.visit_unit());
+                            Ok(SpreadShape::Alternating)
+                        },
+                    }
+                }
+            }
+
+            const VARIANTS: &'static [&'static str] = &[
+                "Linear", "Centered", "Alternating"
+            ];
+
+            deserializer.deserialize_enum("SpreadShape", VARIANTS, Visitor)
+        }
+    }
+
+    #[test]
+    fn test() {
+        extern crate serde_json;
+
+        let spread_shape = SpreadShape::Alternating;
+        let serialized = serde_json::to_string(&spread_shape).unwrap();
+
+        println!("{}", serialized);
+        assert_eq!("\"Alternating\"", serialized);
+
+        let deserialized: SpreadShape = serde_json::from_str(&serialized).unwrap();
+
+        println!("{:?}", deserialized);
+        assert_eq!(spread_shape, deserialized);
+    }
+}
+
+mod glide_sequence {
+    use super::serde;
+    use synth::GlideSequence;
+
+    impl serde::Serialize for GlideSequence {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            struct Visitor<'a> {
+                t: &'a GlideSequence,
+                field_idx: u8,
+            }
+
+            impl<'a> serde::ser::MapVisitor for Visitor<'a> {
+                fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                    where S: serde::Serializer,
+                {
+                    match self.field_idx {
+                        0 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("steps", &self.t.steps))))
+                        },
+                        1 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("elapsed_ms", self.t.elapsed_ms))))
+                        },
+                        _ => Ok(None),
+                    }
+                }
+
+                fn len(&self) -> Option<usize> {
+                    Some(2)
+                }
+            }
+
+            serializer.serialize_struct("GlideSequence", Visitor { t: self, field_idx: 0 })
+        }
+    }
+
+    impl serde::Deserialize for GlideSequence {
+        fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where D: serde::Deserializer,
+        {
+            struct Visitor;
+
+            impl serde::de::Visitor for Visitor {
+                type Value = GlideSequence;
+
+                fn visit_seq<V>(&mut self, mut visitor: V) -> Result<GlideSequence, V::Error>
+                    where V: serde::de::SeqVisitor,
+                {
+                    let steps = try!(visitor.visit());
+                    let elapsed_ms = try!(visitor.visit());
+
+                    let steps = match steps {
+                        Some(steps) => steps,
+                        None => return Err(serde::de::Error::missing_field("steps")),
+                    };
+
+                    let elapsed_ms = match elapsed_ms {
+                        Some(elapsed_ms) => elapsed_ms,
+                        None => return Err(serde::de::Error::missing_field("elapsed_ms")),
+                    };
+
+                    try!(visitor.end());
+
+                    Ok(GlideSequence {
+                        steps: steps,
+                        elapsed_ms: elapsed_ms,
+                    })
+                }
+
+                fn visit_map<V>(&mut self, mut visitor: V) -> Result<GlideSequence, V::Error>
+                    where V: serde::de::MapVisitor,
+                {
+                    let mut steps = None;
+                    let mut elapsed_ms = None;
+
+                    enum Field { Steps, ElapsedMs }
+
+                    impl serde::Deserialize for Field {
+                        fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                            where D: serde::de::Deserializer,
+                        {
+                            struct FieldVisitor;
+
+                            impl serde::de::Visitor for FieldVisitor {
+                                type Value = Field;
+
+                                fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        "steps" => Ok(Field::Steps),
+                                        "elapsed_ms" => Ok(Field::ElapsedMs),
+                                        _ => Err(serde::de::Error::custom("expected steps or elapsed_ms")),
+                                    }
+                                }
+                            }
+
+                            deserializer.deserialize(FieldVisitor)
+                        }
+                    }
+
+                    loop {
+                        match try!(visitor.visit_key()) {
+                            Some(Field::Steps) => { steps = Some(try!(visitor.visit_value())); },
+                            Some(Field::ElapsedMs) => { elapsed_ms = Some(try!(visitor.visit_value())); },
+                            None => { break; }
+                        }
+                    }
+
+                    let steps = match steps {
+                        Some(steps) => steps,
+                        None => return Err(serde::de::Error::missing_field("steps")),
+                    };
+
+                    let elapsed_ms = match elapsed_ms {
+                        Some(elapsed_ms) => elapsed_ms,
+                        None => return Err(serde::de::Error::missing_field("elapsed_ms")),
+                    };
+
+                    try!(visitor.end());
+
+                    Ok(GlideSequence {
+                        steps: steps,
+                        elapsed_ms: elapsed_ms,
+                    })
+                }
+            }
+
+            static FIELDS: &'static [&'static str] = &["steps", "elapsed_ms"];
+
+            deserializer.deserialize_struct("GlideSequence", FIELDS, Visitor)
+        }
+    }
+}
+
+mod dc_blocker {
+    use super::serde;
+    use synth::DcBlocker;
+
+    impl serde::Serialize for DcBlocker {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            struct Visitor<'a> {
+                t: &'a DcBlocker,
+                field_idx: u8,
+            }
+
+            impl<'a> serde::ser::MapVisitor for Visitor<'a> {
+                fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                    where S: serde::Serializer,
+                {
+                    match self.field_idx {
+                        0 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("last_input", &self.t.last_input))))
+                        },
+                        1 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("last_output", &self.t.last_output))))
+                        },
+                        _ => Ok(None),
+                    }
+                }
+
+                fn len(&self) -> Option<usize> {
+                    Some(2)
+                }
+            }
+
+            serializer.serialize_struct("DcBlocker", Visitor { t: self, field_idx: 0 })
+        }
+    }
+
+    impl serde::Deserialize for DcBlocker {
+        fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where D: serde::Deserializer,
+        {
+            struct Visitor;
+
+            impl serde::de::Visitor for Visitor {
+                type Value = DcBlocker;
+
+                fn visit_seq<V>(&mut self, mut visitor: V) -> Result<DcBlocker, V::Error>
+                    where V: serde::de::SeqVisitor,
+                {
+                    let last_input = try!(visitor.visit());
+                    let last_output = try!(visitor.visit());
+
+                    let last_input = match last_input {
+                        Some(last_input) => last_input,
+                        None => return Err(serde::de::Error::missing_field("last_input")),
+                    };
+
+                    let last_output = match last_output {
+                        Some(last_output) => last_output,
+                        None => return Err(serde::de::Error::missing_field("last_output")),
+                    };
+
+                    try!(visitor.end());
+
+                    Ok(DcBlocker {
+                        last_input: last_input,
+                        last_output: last_output,
+                    })
+                }
+
+                fn visit_map<V>(&mut self, mut visitor: V) -> Result<DcBlocker, V::Error>
+                    where V: serde::de::MapVisitor,
+                {
+                    let mut last_input = None;
+                    let mut last_output = None;
+
+                    enum Field { LastInput, LastOutput }
+
+                    impl serde::Deserialize for Field {
+                        fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                            where D: serde::de::Deserializer,
+                        {
+                            struct FieldVisitor;
+
+                            impl serde::de::Visitor for FieldVisitor {
+                                type Value = Field;
+
+                                fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        "last_input" => Ok(Field::LastInput),
+                                        "last_output" => Ok(Field::LastOutput),
+                                        _ => Err(serde::de::Error::custom("expected last_input or last_output")),
+                                    }
+                                }
+                            }
+
+                            deserializer.deserialize(FieldVisitor)
+                        }
+                    }
+
+                    loop {
+                        match try!(visitor.visit_key()) {
+                            Some(Field::LastInput) => { last_input = Some(try!(visitor.visit_value())); },
+                            Some(Field::LastOutput) => { last_output = Some(try!(visitor.visit_value())); },
+                            None => { break; }
+                        }
+                    }
+
+                    let last_input = match last_input {
+                        Some(last_input) => last_input,
+                        None => return Err(serde::de::Error::missing_field("last_input")),
+                    };
+
+                    let last_output = match last_output {
+                        Some(last_output) => last_output,
+                        None => return Err(serde::de::Error::missing_field("last_output")),
+                    };
+
+                    try!(visitor.end());
+
+                    Ok(DcBlocker {
+                        last_input: last_input,
+                        last_output: last_output,
+                    })
+                }
+            }
+
+            static FIELDS: &'static [&'static str] = &["last_input", "last_output"];
+
+            deserializer.deserialize_struct("DcBlocker", FIELDS, Visitor)
+        }
+    }
+
+    #[test]
+    fn test() {
+        extern crate serde_json;
+
+        let mut dc_blocker = DcBlocker::new();
+        dc_blocker.next_sample(1, 0.5, 0.9);
+        let serialized = serde_json::to_string(&dc_blocker).unwrap();
+
+        println!("{}", serialized);
+
+        let deserialized: DcBlocker = serde_json::from_str(&serialized).unwrap();
+
+        println!("{:?}", deserialized);
+        assert_eq!(dc_blocker, deserialized);
+    }
+}
+
+mod oversampler {
+    use super::serde;
+    use synth::Oversampler;
+
+    impl serde::Serialize for Oversampler {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            struct Visitor<'a> {
+                t: &'a Oversampler,
+                field_idx: u8,
+            }
+
+            impl<'a> serde::ser::MapVisitor for Visitor<'a> {
+                fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+                    where S: serde::Serializer,
+                {
+                    match self.field_idx {
+                        0 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("factor", self.t.factor))))
+                        },
+                        1 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("history", &self.t.history))))
+                        },
+                        _ => Ok(None),
+                    }
+                }
+
+                fn len(&self) -> Option<usize> {
+                    Some(2)
+                }
+            }
+
+            serializer.serialize_struct("Oversampler", Visitor { t: self, field_idx: 0 })
+        }
+    }
+
+    impl serde::Deserialize for Oversampler {
+        fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where D: serde::Deserializer,
+        {
+            struct Visitor;
+
+            impl serde::de::Visitor for Visitor {
+                type Value = Oversampler;
+
+                fn visit_seq<V>(&mut self, mut visitor: V) -> Result<Oversampler, V::Error>
+                    where V: serde::de::SeqVisitor,
+                {
+                    let factor = try!(visitor.visit());
+                    let history = try!(visitor.visit());
+
+                    let factor = match factor {
+                        Some(factor) => factor,
+                        None => return Err(serde::de::Error::missing_field("factor")),
+                    };
+
+                    let history = match history {
+                        Some(history) => history,
+                        None => return Err(serde::de::Error::missing_field("history")),
+                    };
+
+                    try!(visitor.end());
+
+                    // The filter taps are not serialized as they are derived from the factor.
+                    let mut oversampler = Oversampler::new(factor);
+                    oversampler.history = history;
+                    Ok(oversampler)
+                }
+
+                fn visit_map<V>(&mut self, mut visitor: V) -> Result<Oversampler, V::Error>
+                    where V: serde::de::MapVisitor,
+                {
+                    let mut factor = None;
+                    let mut history = None;
+
+                    enum Field { Factor, History }
+
+                    impl serde::Deserialize for Field {
+                        fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
+                            where D: serde::de::Deserializer,
+                        {
+                            struct FieldVisitor;
+
+                            impl serde::de::Visitor for FieldVisitor {
+                                type Value = Field;
+
+                                fn visit_str<E>(&mut self, value: &str) -> Result<Field, E>
+                                    where E: serde::de::Error,
+                                {
+                                    match value {
+                                        "factor" => Ok(Field::Factor),
+                                        "history" => Ok(Field::History),
+                                        _ => Err(serde::de::Error::custom("expected factor or history")),
+                                    }
+                                }
+                            }
+
+                            deserializer.deserialize(FieldVisitor)
+                        }
+                    }
+
+                    loop {
+                        match try!(visitor.visit_key()) {
+                            Some(Field::Factor) => { factor = Some(try!(visitor.visit_value())); },
+                            Some(Field::History) => { history = Some(try!(visitor.visit_value())); },
+                            None => { break; }
+                        }
+                    }
+
+                    let factor = match factor {
+                        Some(factor) => factor,
+                        None => return Err(serde::de::Error::missing_field("factor")),
+                    };
+
+                    let history = match history {
+                        Some(history) => history,
+                        None => return Err(serde::de::Error::missing_field("history")),
+                    };
+
+                    try!(visitor.end());
+
+                    // The filter taps are not serialized as they are derived from the factor.
+                    let mut oversampler = Oversampler::new(factor);
+                    oversampler.history = history;
+                    Ok(oversampler)
+                }
+            }
+
+            static FIELDS: &'static [&'static str] = &["factor", "history"];
+
+            deserializer.deserialize_struct("Oversampler", FIELDS, Visitor)
+        }
+    }
+
+    #[test]
+    fn test() {
+        extern crate serde_json;
+
+        let mut oversampler = Oversampler::new(2);
+        oversampler.push_sample(1, 0.5);
+        let serialized = serde_json::to_string(&oversampler).unwrap();
+
+        println!("{}", serialized);
+        assert_eq!("{\"factor\":2,\"history\":[[],[0.5]]}", serialized);
+
+        let deserialized: Oversampler = serde_json::from_str(&serialized).unwrap();
+
+        println!("{:?}", deserialized);
+        assert_eq!(oversampler, deserialized);
+    }
+}
+
+mod tuning {
+    use super::serde;
+    use tuning::Tuning;
+
+    impl serde::Serialize for Tuning {
+        fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+            where S: serde::Serializer,
+        {
+            match *self {
+                Tuning::EqualTemperament => serializer.serialize_unit_variant("Tuning", 0, "EqualTemperament"),
+                Tuning::Degrees(ref cents) => serializer.serialize_newtype_variant("Tuning", 1, "Degrees", cents),
+            }
+        }
+    }
+
+    impl serde::Deserialize for Tuning {
+        fn deserialize<D>(deserializer: &mut D) -> Result<Self, D::Error>
+            where D: serde::Deserializer,
+        {
+            enum Variant { EqualTemperament, Degrees }
+
+            impl serde::de::Deserialize for Variant {
+                fn deserialize<D>(deserializer: &mut D) -> Result<Variant, D::Error>
+                    where D: serde::Deserializer,
+                {
+                    struct VariantVisitor;
+
+                    impl serde::de::Visitor for VariantVisitor {
+                        type Value = Variant;
+
+                        fn visit_str<E>(&mut self, value: &str) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                "EqualTemperament" => Ok(Variant::EqualTemperament),
+                                "Degrees" => Ok(Variant::Degrees),
+                                _ => Err(serde::de::Error::unknown_field(value)),
+                            }
+                        }
+
+                        fn visit_usize<E>(&mut self, value: usize) -> Result<Variant, E>
+                            where E: serde::de::Error,
+                        {
+                            match value {
+                                0 => Ok(Variant::EqualTemperament),
+                                1 => Ok(Variant::Degrees),
+                                _ => Err(serde::de::Error::invalid_value("unknown variant index")),
+                            }
+                        }
+                    }
+
+                    deserializer.deserialize(VariantVisitor)
+                }
+            }
+
+            struct Visitor;
+
+            impl serde::de::EnumVisitor for Visitor {
+                type Value = Tuning;
+
+                fn visit<V>(&mut self, mut visitor: V) -> Result<Self::Value, V::Error>
+                    where V: serde::de::VariantVisitor,
+                {
+                    match try!(visitor.visit_variant()) {
+                        Variant::EqualTemperament => {
+                            try!(visitor.visit_unit());
+                            Ok(Tuning::EqualTemperament)
+                        },
+                        Variant::Degrees => {
+                            let cents = try!(visitor.visit_newtype());
+                            Ok(Tuning::Degrees(cents))
+                        },
+                    }
+                }
+            }
+
+            const VARIANTS: &'static [&'static str] = &[
+                "EqualTemperament", "Degrees"
+            ];
+
+            deserializer.deserialize_enum("Tuning", VARIANTS, Visitor)
+        }
+    }
+
+    #[test]
+    fn test() {
+        extern crate serde_json;
+
+        let tuning = Tuning::just_intonation();
+        let serialized = serde_json::to_string(&tuning).unwrap();
 
-                println!("{}", serialized);
-                assert_eq!("{\"waveform\":null,\"amplitude\":1,\"frequency\":440,\"freq_warp\":null,\"is_muted\":false}", serialized);
-                
-                let deserialized: Oscillator<waveform::Sine, f32, f64, ()> = serde_json::from_str(&serialized).unwrap();
+        println!("{}", serialized);
 
-                println!("{:?}", deserialized);
-                assert_eq!(osc, deserialized);
-            }
-        }
+        let deserialized: Tuning = serde_json::from_str(&serialized).unwrap();
 
+        println!("{:?}", deserialized);
+        assert_eq!(tuning, deserialized);
+        assert_eq!("\"EqualTemperament\"", serde_json::to_string(&Tuning::EqualTemperament).unwrap());
     }
-
 }
 
 mod voice {
@@ -1452,12 +4335,25 @@ mod voice {
                             self.field_idx += 1;
                             Ok(Some(try!(serializer.serialize_struct_elt("oscillator_states", &self.t.oscillator_states))))
                         },
+                        2 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("loop_reversed", self.t.loop_reversed))))
+                        },
+                        3 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("stereo_oscillator_states",
+                                                                         &self.t.stereo_oscillator_states))))
+                        },
+                        4 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("gain", self.t.gain))))
+                        },
                         _ => Ok(None),
                     }
                 }
 
                 fn len(&self) -> Option<usize> {
-                    Some(2)
+                    Some(5)
                 }
             }
 
@@ -1474,13 +4370,59 @@ mod voice {
             impl serde::de::Visitor for Visitor {
                 type Value = Voice;
 
+                fn visit_seq<V>(&mut self, mut visitor: V) -> Result<Voice, V::Error>
+                    where V: serde::de::SeqVisitor,
+                {
+                    let loop_playhead = try!(visitor.visit());
+                    let oscillator_states = try!(visitor.visit());
+                    let loop_reversed = try!(visitor.visit());
+                    let stereo_oscillator_states = try!(visitor.visit());
+                    let gain = try!(visitor.visit());
+
+                    let loop_playhead = match loop_playhead {
+                        Some(loop_playhead) => loop_playhead,
+                        None => return Err(serde::de::Error::missing_field("loop_playhead")),
+                    };
+
+                    let oscillator_states = match oscillator_states {
+                        Some(oscillator_states) => oscillator_states,
+                        None => return Err(serde::de::Error::missing_field("oscillator_states")),
+                    };
+
+                    let loop_reversed = loop_reversed.unwrap_or(false);
+
+                    let stereo_oscillator_states = stereo_oscillator_states
+                        .unwrap_or(::oscillator::StatePerVoice(Vec::new()));
+
+                    let gain = gain.unwrap_or(1.0);
+
+                    try!(visitor.end());
+
+                    Ok(Voice {
+                        loop_playhead: loop_playhead,
+                        oscillator_states: oscillator_states,
+                        loop_reversed: loop_reversed,
+                        stereo_oscillator_states: stereo_oscillator_states,
+                        gain: gain,
+                    })
+                }
+
                 fn visit_map<V>(&mut self, mut visitor: V) -> Result<Voice, V::Error>
                     where V: serde::de::MapVisitor,
                 {
                     let mut loop_playhead = None;
                     let mut oscillator_states = None;
+                    let mut loop_reversed = None;
+                    let mut stereo_oscillator_states = None;
+                    let mut gain = None;
 
-                    enum Field { LoopPlayhead, OscillatorStates }
+                    enum Field {
+                        LoopPlayhead,
+                        OscillatorStates,
+                        LoopReversed,
+                        StereoOscillatorStates,
+                        Gain,
+                    }
 
                     impl serde::Deserialize for Field {
                         fn deserialize<D>(deserializer: &mut D) -> Result<Field, D::Error>
@@ -1497,8 +4439,12 @@ mod voice {
                                     match value {
                                         "loop_playhead" => Ok(Field::LoopPlayhead),
                                         "oscillator_states" => Ok(Field::OscillatorStates),
+                                        "loop_reversed" => Ok(Field::LoopReversed),
+                                        "stereo_oscillator_states" => Ok(Field::StereoOscillatorStates),
+                                        "gain" => Ok(Field::Gain),
                                         _ => Err(serde::de::Error::custom(
-                                            "expected loop_playhead or oscillator_states"
+                                            "expected loop_playhead, oscillator_states, loop_reversed, \
+                                            stereo_oscillator_states or gain"
                                         )),
                                     }
                                 }
@@ -1512,6 +4458,9 @@ mod voice {
                         match try!(visitor.visit_key()) {
                             Some(Field::LoopPlayhead) => { loop_playhead = Some(try!(visitor.visit_value())); },
                             Some(Field::OscillatorStates) => { oscillator_states = Some(try!(visitor.visit_value())); },
+                            Some(Field::LoopReversed) => { loop_reversed = Some(try!(visitor.visit_value())); },
+                            Some(Field::StereoOscillatorStates) => { stereo_oscillator_states = Some(try!(visitor.visit_value())); },
+                            Some(Field::Gain) => { gain = Some(try!(visitor.visit_value())); },
                             None => { break; }
                         }
                     }
@@ -1526,16 +4475,32 @@ mod voice {
                         None => return Err(serde::de::Error::missing_field("oscillator_states")),
                     };
 
+                    let loop_reversed = loop_reversed.unwrap_or(false);
+
+                    let stereo_oscillator_states = stereo_oscillator_states
+                        .unwrap_or(::oscillator::StatePerVoice(Vec::new()));
+
+                    let gain = gain.unwrap_or(1.0);
+
                     try!(visitor.end());
 
                     Ok(Voice {
                         loop_playhead: loop_playhead,
                         oscillator_states: oscillator_states,
+                        loop_reversed: loop_reversed,
+                        stereo_oscillator_states: stereo_oscillator_states,
+                        gain: gain,
                     })
                 }
             }
 
-            static FIELDS: &'static [&'static str] = &["loop_playhead", "oscillator_states"];
+            static FIELDS: &'static [&'static str] = &[
+                "loop_playhead",
+                "oscillator_states",
+                "loop_reversed",
+                "stereo_oscillator_states",
+                "gain",
+            ];
 
             deserializer.deserialize_struct("Voice", FIELDS, Visitor)
         }
@@ -1549,11 +4514,14 @@ mod voice {
         let voice = Voice {
             loop_playhead: 5,
             oscillator_states: oscillator::StatePerVoice(vec![]),
+            loop_reversed: false,
+            stereo_oscillator_states: oscillator::StatePerVoice(vec![]),
+            gain: 1.0,
         };
         let serialized = serde_json::to_string(&voice).unwrap();
 
         println!("{}", serialized);
-        assert_eq!("{\"loop_playhead\":5,\"oscillator_states\":[]}", serialized);
+        assert_eq!("{\"loop_playhead\":5,\"oscillator_states\":[],\"loop_reversed\":false,\"stereo_oscillator_states\":[],\"gain\":1}", serialized);
         
         let deserialized: Voice = serde_json::from_str(&serialized).unwrap();
 
@@ -1564,7 +4532,7 @@ mod voice {
 
 mod synth {
     use instrument::NoteFreqGenerator;
-    use synth::Synth;
+    use synth::{SpreadShape, Synth};
     use super::serde;
     use std;
 
@@ -1640,12 +4608,144 @@ mod synth {
                             Ok(Some(try!(serializer.serialize_struct_elt("base_pitch",
                                                                          &self.t.base_pitch))))
                         },
+                        8 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("arpeggiator", &self.t.arpeggiator))))
+                        },
+                        9 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("note_match_tolerance", self.t.note_match_tolerance))))
+                        },
+                        10 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("held_notes", &self.t.held_notes))))
+                        },
+                        11 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("high_precision", self.t.high_precision))))
+                        },
+                        12 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("loop_mode", self.t.loop_mode))))
+                        },
+                        13 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("one_shot", self.t.one_shot))))
+                        },
+                        14 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("key_pan", self.t.key_pan))))
+                        },
+                        15 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("dc_blocker", &self.t.dc_blocker))))
+                        },
+                        16 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("tuning", self.t.tuning))))
+                        },
+                        17 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("glide_quantized", self.t.glide_quantized))))
+                        },
+                        18 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("phase_spread", self.t.phase_spread))))
+                        },
+                        19 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("random_start_phase", self.t.random_start_phase))))
+                        },
+                        20 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("declick_ms", &self.t.declick_ms))))
+                        },
+                        21 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("declick_elapsed_ms", self.t.declick_elapsed_ms))))
+                        },
+                        22 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("volume_smoothing_ms", &self.t.volume_smoothing_ms))))
+                        },
+                        23 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("current_volume", self.t.current_volume))))
+                        },
+                        24 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("pitch_bend", self.t.pitch_bend))))
+                        },
+                        25 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("sustain", self.t.sustain))))
+                        },
+                        26 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("sustained_notes", &self.t.sustained_notes))))
+                        },
+                        27 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("stereo_detune", self.t.stereo_detune))))
+                        },
+                        28 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("loop_crossfade_ms", self.t.loop_crossfade_ms))))
+                        },
+                        29 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("oversampler", &self.t.oversampler))))
+                        },
+                        30 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("seed", self.t.seed))))
+                        },
+                        31 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("pan_law", self.t.pan_law))))
+                        },
+                        32 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("sostenuto", self.t.sostenuto))))
+                        },
+                        33 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("sostenuto_notes", &self.t.sostenuto_notes))))
+                        },
+                        34 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("sostenuto_released_notes", &self.t.sostenuto_released_notes))))
+                        },
+                        35 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("solo_oscillator", self.t.solo_oscillator))))
+                        },
+                        36 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("freq_slew", self.t.freq_slew))))
+                        },
+                        37 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("spread_shape", self.t.spread_shape))))
+                        },
+                        38 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("output_enabled", self.t.output_enabled))))
+                        },
+                        39 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("ignore_note_off", self.t.ignore_note_off))))
+                        },
+                        40 => {
+                            self.field_idx += 1;
+                            Ok(Some(try!(serializer.serialize_struct_elt("glide_sequence", &self.t.glide_sequence))))
+                        },
                         _ => Ok(None),
                     }
                 }
 
                 fn len(&self) -> Option<usize> {
-                    Some(8)
+                    Some(41)
                 }
             }
 
@@ -1685,6 +4785,204 @@ mod synth {
             {
                 type Value = Synth<M, NFG, W, A, F, FW>;
 
+                fn visit_seq<V>(&mut self, mut visitor: V) -> Result<Synth<M, NFG, W, A, F, FW>, V::Error>
+                    where V: serde::de::SeqVisitor,
+                {
+                    let oscillators = try!(visitor.visit());
+                    let voices = try!(visitor.visit());
+                    let instrument = try!(visitor.visit());
+                    let volume = try!(visitor.visit());
+                    let spread = try!(visitor.visit());
+                    let loop_points = try!(visitor.visit());
+                    let duration_ms = try!(visitor.visit());
+                    let base_pitch = try!(visitor.visit());
+                    let arpeggiator = try!(visitor.visit());
+                    let note_match_tolerance = try!(visitor.visit());
+                    let held_notes = try!(visitor.visit());
+                    let high_precision = try!(visitor.visit());
+                    let loop_mode = try!(visitor.visit());
+                    let one_shot = try!(visitor.visit());
+                    let key_pan = try!(visitor.visit());
+                    let dc_blocker = try!(visitor.visit());
+                    let tuning = try!(visitor.visit());
+                    let glide_quantized = try!(visitor.visit());
+                    let phase_spread = try!(visitor.visit());
+                    let random_start_phase = try!(visitor.visit());
+                    let declick_ms = try!(visitor.visit());
+                    let declick_elapsed_ms = try!(visitor.visit());
+                    let volume_smoothing_ms = try!(visitor.visit());
+                    let current_volume = try!(visitor.visit());
+                    let pitch_bend = try!(visitor.visit());
+                    let sustain = try!(visitor.visit());
+                    let sustained_notes = try!(visitor.visit());
+                    let stereo_detune = try!(visitor.visit());
+                    let loop_crossfade_ms = try!(visitor.visit());
+                    let oversampler = try!(visitor.visit());
+                    let seed = try!(visitor.visit());
+                    let pan_law = try!(visitor.visit());
+                    let sostenuto = try!(visitor.visit());
+                    let sostenuto_notes = try!(visitor.visit());
+                    let sostenuto_released_notes = try!(visitor.visit());
+                    let solo_oscillator = try!(visitor.visit());
+                    let freq_slew = try!(visitor.visit());
+                    let spread_shape = try!(visitor.visit());
+                    let output_enabled = try!(visitor.visit());
+                    let ignore_note_off = try!(visitor.visit());
+                    let glide_sequence = try!(visitor.visit());
+
+                    let oscillators = match oscillators {
+                        Some(oscillators) => oscillators,
+                        None => return Err(serde::de::Error::missing_field("oscillators")),
+                    };
+
+                    let voices = match voices {
+                        Some(voices) => voices,
+                        None => return Err(serde::de::Error::missing_field("voices")),
+                    };
+
+                    let instrument = match instrument {
+                        Some(instrument) => instrument,
+                        None => return Err(serde::de::Error::missing_field("instrument")),
+                    };
+
+                    let volume = match volume {
+                        Some(volume) => volume,
+                        None => return Err(serde::de::Error::missing_field("volume")),
+                    };
+
+                    let spread = match spread {
+                        Some(spread) => spread,
+                        None => return Err(serde::de::Error::missing_field("spread")),
+                    };
+
+                    let loop_points = match loop_points {
+                        Some(loop_points) => loop_points,
+                        None => return Err(serde::de::Error::missing_field("loop_points")),
+                    };
+
+                    let duration_ms = match duration_ms {
+                        Some(duration_ms) => duration_ms,
+                        None => return Err(serde::de::Error::missing_field("duration_ms")),
+                    };
+
+                    let base_pitch = match base_pitch {
+                        Some(base_pitch) => base_pitch,
+                        None => return Err(serde::de::Error::missing_field("base_pitch")),
+                    };
+
+                    let arpeggiator = arpeggiator.unwrap_or(None);
+
+                    let note_match_tolerance = note_match_tolerance.unwrap_or(::synth::DEFAULT_NOTE_MATCH_TOLERANCE);
+
+                    let held_notes = held_notes.unwrap_or(Vec::new());
+
+                    let high_precision = high_precision.unwrap_or(false);
+
+                    let loop_mode = loop_mode.unwrap_or(::synth::LoopMode::Forward);
+
+                    let one_shot = one_shot.unwrap_or(false);
+
+                    let key_pan = key_pan.unwrap_or(0.0);
+
+                    let dc_blocker = dc_blocker.unwrap_or(None);
+
+                    let tuning = tuning.unwrap_or(::tuning::Tuning::EqualTemperament);
+
+                    let glide_quantized = glide_quantized.unwrap_or(false);
+
+                    let phase_spread = phase_spread.unwrap_or(0.0);
+
+                    let random_start_phase = random_start_phase.unwrap_or(false);
+
+                    let declick_ms = declick_ms.unwrap_or(::time::Ms(0.0));
+
+                    let declick_elapsed_ms = declick_elapsed_ms.unwrap_or(None);
+
+                    let volume_smoothing_ms = volume_smoothing_ms.unwrap_or(::synth::DEFAULT_VOLUME_SMOOTHING_MS);
+
+                    let current_volume = current_volume.unwrap_or(volume);
+
+                    let pitch_bend = pitch_bend.unwrap_or(0.0);
+
+                    let sustain = sustain.unwrap_or(false);
+
+                    let sustained_notes = sustained_notes.unwrap_or(Vec::new());
+
+                    let stereo_detune = stereo_detune.unwrap_or(0.0);
+
+                    let loop_crossfade_ms = loop_crossfade_ms.unwrap_or(::time::Ms(0.0));
+
+                    let oversampler = oversampler.unwrap_or(None);
+
+                    let seed = seed.unwrap_or(None);
+
+                    let pan_law = pan_law.unwrap_or(None);
+
+                    let sostenuto = sostenuto.unwrap_or(false);
+
+                    let sostenuto_notes = sostenuto_notes.unwrap_or(Vec::new());
+
+                    let sostenuto_released_notes = sostenuto_released_notes.unwrap_or(Vec::new());
+
+                    let solo_oscillator = solo_oscillator.unwrap_or(None);
+
+                    let freq_slew = freq_slew.unwrap_or(None);
+
+                    let spread_shape = spread_shape.unwrap_or(SpreadShape::Linear);
+
+                    let output_enabled = output_enabled.unwrap_or(true);
+
+                    let ignore_note_off = ignore_note_off.unwrap_or(false);
+
+                    let glide_sequence = glide_sequence.unwrap_or(None);
+
+                    try!(visitor.end());
+
+                    Ok(Synth {
+                        oscillators: oscillators,
+                        voices: voices,
+                        instrument: instrument,
+                        volume: volume,
+                        spread: spread,
+                        loop_points: loop_points,
+                        duration_ms: duration_ms,
+                        base_pitch: base_pitch,
+                        arpeggiator: arpeggiator,
+                        note_match_tolerance: note_match_tolerance,
+                        held_notes: held_notes,
+                        high_precision: high_precision,
+                        loop_mode: loop_mode,
+                        one_shot: one_shot,
+                        key_pan: key_pan,
+                        dc_blocker: dc_blocker,
+                        tuning: tuning,
+                        glide_quantized: glide_quantized,
+                        phase_spread: phase_spread,
+                        random_start_phase: random_start_phase,
+                        declick_ms: declick_ms,
+                        declick_elapsed_ms: declick_elapsed_ms,
+                        volume_smoothing_ms: volume_smoothing_ms,
+                        current_volume: current_volume,
+                        pitch_bend: pitch_bend,
+                        sustain: sustain,
+                        sustained_notes: sustained_notes,
+                        stereo_detune: stereo_detune,
+                        loop_crossfade_ms: loop_crossfade_ms,
+                        oversampler: oversampler,
+                        seed: seed,
+                        pan_law: pan_law,
+                        sostenuto: sostenuto,
+                        sostenuto_notes: sostenuto_notes,
+                        sostenuto_released_notes: sostenuto_released_notes,
+                        solo_oscillator: solo_oscillator,
+                        freq_slew: freq_slew,
+                        spread_shape: spread_shape,
+                        output_enabled: output_enabled,
+                        ignore_note_off: ignore_note_off,
+                        glide_sequence: glide_sequence,
+                    })
+                }
+
                 fn visit_map<V>(&mut self, mut visitor: V) -> Result<Synth<M, NFG, W, A, F, FW>, V::Error>
                     where V: serde::de::MapVisitor,
                 {
@@ -1696,6 +4994,39 @@ mod synth {
                     let mut loop_points = None;
                     let mut duration_ms = None;
                     let mut base_pitch = None;
+                    let mut arpeggiator = None;
+                    let mut note_match_tolerance = None;
+                    let mut held_notes = None;
+                    let mut high_precision = None;
+                    let mut loop_mode = None;
+                    let mut one_shot = None;
+                    let mut key_pan = None;
+                    let mut dc_blocker = None;
+                    let mut tuning = None;
+                    let mut glide_quantized = None;
+                    let mut phase_spread = None;
+                    let mut random_start_phase = None;
+                    let mut declick_ms = None;
+                    let mut declick_elapsed_ms = None;
+                    let mut volume_smoothing_ms = None;
+                    let mut current_volume = None;
+                    let mut pitch_bend = None;
+                    let mut sustain = None;
+                    let mut sustained_notes = None;
+                    let mut stereo_detune = None;
+                    let mut loop_crossfade_ms = None;
+                    let mut oversampler = None;
+                    let mut seed = None;
+                    let mut pan_law = None;
+                    let mut sostenuto = None;
+                    let mut sostenuto_notes = None;
+                    let mut sostenuto_released_notes = None;
+                    let mut solo_oscillator = None;
+                    let mut freq_slew = None;
+                    let mut spread_shape = None;
+                    let mut output_enabled = None;
+                    let mut ignore_note_off = None;
+                    let mut glide_sequence = None;
 
                     enum Field {
                         Oscillators,
@@ -1706,6 +5037,39 @@ mod synth {
                         LoopPoints,
                         DurationMs,
                         BasePitch,
+                        Arpeggiator,
+                        NoteMatchTolerance,
+                        HeldNotes,
+                        HighPrecision,
+                        LoopMode,
+                        OneShot,
+                        KeyPan,
+                        DcBlocker,
+                        Tuning,
+                        GlideQuantized,
+                        PhaseSpread,
+                        RandomStartPhase,
+                        DeclickMs,
+                        DeclickElapsedMs,
+                        VolumeSmoothingMs,
+                        CurrentVolume,
+                        PitchBend,
+                        Sustain,
+                        SustainedNotes,
+                        StereoDetune,
+                        LoopCrossfadeMs,
+                        Oversampler,
+                        Seed,
+                        PanLaw,
+                        Sostenuto,
+                        SostenutoNotes,
+                        SostenutoReleasedNotes,
+                        SoloOscillator,
+                        FreqSlew,
+                        SpreadShape,
+                        OutputEnabled,
+                        IgnoreNoteOff,
+                        GlideSequence,
                     }
 
                     impl serde::Deserialize for Field {
@@ -1729,9 +5093,51 @@ mod synth {
                                         "loop_points" => Ok(Field::LoopPoints),
                                         "duration_ms" => Ok(Field::DurationMs),
                                         "base_pitch" => Ok(Field::BasePitch),
+                                        "arpeggiator" => Ok(Field::Arpeggiator),
+                                        "note_match_tolerance" => Ok(Field::NoteMatchTolerance),
+                                        "held_notes" => Ok(Field::HeldNotes),
+                                        "high_precision" => Ok(Field::HighPrecision),
+                                        "loop_mode" => Ok(Field::LoopMode),
+                                        "one_shot" => Ok(Field::OneShot),
+                                        "key_pan" => Ok(Field::KeyPan),
+                                        "dc_blocker" => Ok(Field::DcBlocker),
+                                        "tuning" => Ok(Field::Tuning),
+                                        "glide_quantized" => Ok(Field::GlideQuantized),
+                                        "phase_spread" => Ok(Field::PhaseSpread),
+                                        "random_start_phase" => Ok(Field::RandomStartPhase),
+                                        "declick_ms" => Ok(Field::DeclickMs),
+                                        "declick_elapsed_ms" => Ok(Field::DeclickElapsedMs),
+                                        "volume_smoothing_ms" => Ok(Field::VolumeSmoothingMs),
+                                        "current_volume" => Ok(Field::CurrentVolume),
+                                        "pitch_bend" => Ok(Field::PitchBend),
+                                        "sustain" => Ok(Field::Sustain),
+                                        "sustained_notes" => Ok(Field::SustainedNotes),
+                                        "stereo_detune" => Ok(Field::StereoDetune),
+                                        "loop_crossfade_ms" => Ok(Field::LoopCrossfadeMs),
+                                        "oversampler" => Ok(Field::Oversampler),
+                                        "seed" => Ok(Field::Seed),
+                                        "pan_law" => Ok(Field::PanLaw),
+                                        "sostenuto" => Ok(Field::Sostenuto),
+                                        "sostenuto_notes" => Ok(Field::SostenutoNotes),
+                                        "sostenuto_released_notes" => Ok(Field::SostenutoReleasedNotes),
+                                        "solo_oscillator" => Ok(Field::SoloOscillator),
+                                        "freq_slew" => Ok(Field::FreqSlew),
+                                        "spread_shape" => Ok(Field::SpreadShape),
+                                        "output_enabled" => Ok(Field::OutputEnabled),
+                                        "ignore_note_off" => Ok(Field::IgnoreNoteOff),
+                                        "glide_sequence" => Ok(Field::GlideSequence),
                                         _ => Err(serde::de::Error::custom(
-                                            "expected oscillators, voices, instrument, \
-                                            volume, spread, loop_points, duration_ms or base_pitch"
+                                            "expected oscillators, voices, instrument, volume, spread, \
+                                            loop_points, duration_ms, base_pitch, arpeggiator, \
+                                            note_match_tolerance, held_notes, high_precision, loop_mode, \
+                                            one_shot, key_pan, dc_blocker, tuning, glide_quantized, \
+                                            phase_spread, random_start_phase, declick_ms, \
+                                            declick_elapsed_ms, volume_smoothing_ms, current_volume, \
+                                            pitch_bend, sustain, sustained_notes, stereo_detune, \
+                                            loop_crossfade_ms, oversampler, seed, pan_law, sostenuto, \
+                                            sostenuto_notes, sostenuto_released_notes, solo_oscillator, \
+                                            freq_slew, spread_shape, output_enabled, ignore_note_off or \
+                                            glide_sequence"
                                         )),
                                     }
                                 }
@@ -1751,6 +5157,39 @@ mod synth {
                             Some(Field::LoopPoints) => { loop_points = Some(try!(visitor.visit_value())); },
                             Some(Field::DurationMs) => { duration_ms = Some(try!(visitor.visit_value())); },
                             Some(Field::BasePitch) => { base_pitch = Some(try!(visitor.visit_value())); },
+                            Some(Field::Arpeggiator) => { arpeggiator = Some(try!(visitor.visit_value())); },
+                            Some(Field::NoteMatchTolerance) => { note_match_tolerance = Some(try!(visitor.visit_value())); },
+                            Some(Field::HeldNotes) => { held_notes = Some(try!(visitor.visit_value())); },
+                            Some(Field::HighPrecision) => { high_precision = Some(try!(visitor.visit_value())); },
+                            Some(Field::LoopMode) => { loop_mode = Some(try!(visitor.visit_value())); },
+                            Some(Field::OneShot) => { one_shot = Some(try!(visitor.visit_value())); },
+                            Some(Field::KeyPan) => { key_pan = Some(try!(visitor.visit_value())); },
+                            Some(Field::DcBlocker) => { dc_blocker = Some(try!(visitor.visit_value())); },
+                            Some(Field::Tuning) => { tuning = Some(try!(visitor.visit_value())); },
+                            Some(Field::GlideQuantized) => { glide_quantized = Some(try!(visitor.visit_value())); },
+                            Some(Field::PhaseSpread) => { phase_spread = Some(try!(visitor.visit_value())); },
+                            Some(Field::RandomStartPhase) => { random_start_phase = Some(try!(visitor.visit_value())); },
+                            Some(Field::DeclickMs) => { declick_ms = Some(try!(visitor.visit_value())); },
+                            Some(Field::DeclickElapsedMs) => { declick_elapsed_ms = Some(try!(visitor.visit_value())); },
+                            Some(Field::VolumeSmoothingMs) => { volume_smoothing_ms = Some(try!(visitor.visit_value())); },
+                            Some(Field::CurrentVolume) => { current_volume = Some(try!(visitor.visit_value())); },
+                            Some(Field::PitchBend) => { pitch_bend = Some(try!(visitor.visit_value())); },
+                            Some(Field::Sustain) => { sustain = Some(try!(visitor.visit_value())); },
+                            Some(Field::SustainedNotes) => { sustained_notes = Some(try!(visitor.visit_value())); },
+                            Some(Field::StereoDetune) => { stereo_detune = Some(try!(visitor.visit_value())); },
+                            Some(Field::LoopCrossfadeMs) => { loop_crossfade_ms = Some(try!(visitor.visit_value())); },
+                            Some(Field::Oversampler) => { oversampler = Some(try!(visitor.visit_value())); },
+                            Some(Field::Seed) => { seed = Some(try!(visitor.visit_value())); },
+                            Some(Field::PanLaw) => { pan_law = Some(try!(visitor.visit_value())); },
+                            Some(Field::Sostenuto) => { sostenuto = Some(try!(visitor.visit_value())); },
+                            Some(Field::SostenutoNotes) => { sostenuto_notes = Some(try!(visitor.visit_value())); },
+                            Some(Field::SostenutoReleasedNotes) => { sostenuto_released_notes = Some(try!(visitor.visit_value())); },
+                            Some(Field::SoloOscillator) => { solo_oscillator = Some(try!(visitor.visit_value())); },
+                            Some(Field::FreqSlew) => { freq_slew = Some(try!(visitor.visit_value())); },
+                            Some(Field::SpreadShape) => { spread_shape = Some(try!(visitor.visit_value())); },
+                            Some(Field::OutputEnabled) => { output_enabled = Some(try!(visitor.visit_value())); },
+                            Some(Field::IgnoreNoteOff) => { ignore_note_off = Some(try!(visitor.visit_value())); },
+                            Some(Field::GlideSequence) => { glide_sequence = Some(try!(visitor.visit_value())); },
                             None => { break; }
                         }
                     }
@@ -1795,6 +5234,72 @@ mod synth {
                         None => return Err(serde::de::Error::missing_field("base_pitch")),
                     };
 
+                    let arpeggiator = arpeggiator.unwrap_or(None);
+
+                    let note_match_tolerance = note_match_tolerance.unwrap_or(::synth::DEFAULT_NOTE_MATCH_TOLERANCE);
+
+                    let held_notes = held_notes.unwrap_or(Vec::new());
+
+                    let high_precision = high_precision.unwrap_or(false);
+
+                    let loop_mode = loop_mode.unwrap_or(::synth::LoopMode::Forward);
+
+                    let one_shot = one_shot.unwrap_or(false);
+
+                    let key_pan = key_pan.unwrap_or(0.0);
+
+                    let dc_blocker = dc_blocker.unwrap_or(None);
+
+                    let tuning = tuning.unwrap_or(::tuning::Tuning::EqualTemperament);
+
+                    let glide_quantized = glide_quantized.unwrap_or(false);
+
+                    let phase_spread = phase_spread.unwrap_or(0.0);
+
+                    let random_start_phase = random_start_phase.unwrap_or(false);
+
+                    let declick_ms = declick_ms.unwrap_or(::time::Ms(0.0));
+
+                    let declick_elapsed_ms = declick_elapsed_ms.unwrap_or(None);
+
+                    let volume_smoothing_ms = volume_smoothing_ms.unwrap_or(::synth::DEFAULT_VOLUME_SMOOTHING_MS);
+
+                    let current_volume = current_volume.unwrap_or(volume);
+
+                    let pitch_bend = pitch_bend.unwrap_or(0.0);
+
+                    let sustain = sustain.unwrap_or(false);
+
+                    let sustained_notes = sustained_notes.unwrap_or(Vec::new());
+
+                    let stereo_detune = stereo_detune.unwrap_or(0.0);
+
+                    let loop_crossfade_ms = loop_crossfade_ms.unwrap_or(::time::Ms(0.0));
+
+                    let oversampler = oversampler.unwrap_or(None);
+
+                    let seed = seed.unwrap_or(None);
+
+                    let pan_law = pan_law.unwrap_or(None);
+
+                    let sostenuto = sostenuto.unwrap_or(false);
+
+                    let sostenuto_notes = sostenuto_notes.unwrap_or(Vec::new());
+
+                    let sostenuto_released_notes = sostenuto_released_notes.unwrap_or(Vec::new());
+
+                    let solo_oscillator = solo_oscillator.unwrap_or(None);
+
+                    let freq_slew = freq_slew.unwrap_or(None);
+
+                    let spread_shape = spread_shape.unwrap_or(SpreadShape::Linear);
+
+                    let output_enabled = output_enabled.unwrap_or(true);
+
+                    let ignore_note_off = ignore_note_off.unwrap_or(false);
+
+                    let glide_sequence = glide_sequence.unwrap_or(None);
+
                     try!(visitor.end());
 
                     Ok(Synth {
@@ -1806,6 +5311,39 @@ mod synth {
                         loop_points: loop_points,
                         duration_ms: duration_ms,
                         base_pitch: base_pitch,
+                        arpeggiator: arpeggiator,
+                        note_match_tolerance: note_match_tolerance,
+                        held_notes: held_notes,
+                        high_precision: high_precision,
+                        loop_mode: loop_mode,
+                        one_shot: one_shot,
+                        key_pan: key_pan,
+                        dc_blocker: dc_blocker,
+                        tuning: tuning,
+                        glide_quantized: glide_quantized,
+                        phase_spread: phase_spread,
+                        random_start_phase: random_start_phase,
+                        declick_ms: declick_ms,
+                        declick_elapsed_ms: declick_elapsed_ms,
+                        volume_smoothing_ms: volume_smoothing_ms,
+                        current_volume: current_volume,
+                        pitch_bend: pitch_bend,
+                        sustain: sustain,
+                        sustained_notes: sustained_notes,
+                        stereo_detune: stereo_detune,
+                        loop_crossfade_ms: loop_crossfade_ms,
+                        oversampler: oversampler,
+                        seed: seed,
+                        pan_law: pan_law,
+                        sostenuto: sostenuto,
+                        sostenuto_notes: sostenuto_notes,
+                        sostenuto_released_notes: sostenuto_released_notes,
+                        solo_oscillator: solo_oscillator,
+                        freq_slew: freq_slew,
+                        spread_shape: spread_shape,
+                        output_enabled: output_enabled,
+                        ignore_note_off: ignore_note_off,
+                        glide_sequence: glide_sequence,
                     })
                 }
             }
@@ -1819,6 +5357,39 @@ mod synth {
                 "loop_points",
                 "duration_ms",
                 "base_pitch",
+                "arpeggiator",
+                "note_match_tolerance",
+                "held_notes",
+                "high_precision",
+                "loop_mode",
+                "one_shot",
+                "key_pan",
+                "dc_blocker",
+                "tuning",
+                "glide_quantized",
+                "phase_spread",
+                "random_start_phase",
+                "declick_ms",
+                "declick_elapsed_ms",
+                "volume_smoothing_ms",
+                "current_volume",
+                "pitch_bend",
+                "sustain",
+                "sustained_notes",
+                "stereo_detune",
+                "loop_crossfade_ms",
+                "oversampler",
+                "seed",
+                "pan_law",
+                "sostenuto",
+                "sostenuto_notes",
+                "sostenuto_released_notes",
+                "solo_oscillator",
+                "freq_slew",
+                "spread_shape",
+                "output_enabled",
+                "ignore_note_off",
+                "glide_sequence",
             ];
 
             deserializer.deserialize_struct("Synth", FIELDS, Visitor {
@@ -1868,3 +5439,20 @@ fn test_dynamic_synth() {
     println!("{:?}", deserialized);
     assert_eq!(synth, deserialized);
 }
+
+#[test]
+fn test_default_dynamic_synth() {
+    use dynamic::Synth;
+
+    extern crate serde_json;
+
+    let synth = Synth::default();
+    let serialized = serde_json::to_string(&synth).unwrap();
+
+    println!("{}", serialized);
+
+    let deserialized: Synth = serde_json::from_str(&serialized).unwrap();
+
+    println!("{:?}", deserialized);
+    assert_eq!(synth, deserialized);
+}
@@ -0,0 +1,173 @@
+//!
+//! Support for serde 1.0 via `#[derive(Serialize, Deserialize)]` behind the `serde1` feature.
+//!
+//! The serialized forms use the same field names as the hand-written `serde_serialization`
+//! impls. `Synth` itself is not supported as the `instrument` crate only provides serde 0.7
+//! impls for the `Instrument`.
+//!
+
+#[cfg(test)]
+extern crate serde_json_v1 as serde_json;
+
+
+/// (De)serialize an `envelope::Point` list as `{"x", "y", "curve"}` maps.
+pub mod points {
+    use envelope::Point;
+    use serde_v1::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(crate="serde_v1")]
+    struct PointDef {
+        x: f64,
+        y: f64,
+        curve: f64,
+    }
+
+    pub fn serialize<S>(points: &Vec<Point>, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer,
+    {
+        let points: Vec<PointDef> = points.iter()
+            .map(|p| PointDef { x: p.x, y: p.y, curve: p.curve })
+            .collect();
+        points.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Point>, D::Error>
+        where D: Deserializer<'de>,
+    {
+        let points: Vec<PointDef> = try!(Deserialize::deserialize(deserializer));
+        Ok(points.into_iter().map(|p| Point::new(p.x, p.y, p.curve)).collect())
+    }
+}
+
+/// (De)serialize a `time::Ms` as a number of milliseconds.
+pub mod ms {
+    use serde_v1::{Deserialize, Deserializer, Serialize, Serializer};
+    use time;
+
+    pub fn serialize<S>(ms: &time::Ms, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer,
+    {
+        ms.0.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<time::Ms, D::Error>
+        where D: Deserializer<'de>,
+    {
+        Ok(time::Ms(try!(Deserialize::deserialize(deserializer))))
+    }
+}
+
+/// (De)serialize an `Oversampler` without its filter taps, which are derived from the `factor`.
+mod oversampler {
+    use serde_v1::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::VecDeque;
+    use synth::Oversampler;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(crate="serde_v1", rename="Oversampler")]
+    struct OversamplerDef<H> {
+        factor: u8,
+        history: H,
+    }
+
+    impl Serialize for Oversampler {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where S: Serializer,
+        {
+            OversamplerDef { factor: self.factor, history: &self.history }.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Oversampler {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where D: Deserializer<'de>,
+        {
+            let def: OversamplerDef<Vec<VecDeque<f32>>> =
+                try!(Deserialize::deserialize(deserializer));
+            let mut oversampler = Oversampler::new(def.factor);
+            oversampler.history = def.history;
+            Ok(oversampler)
+        }
+    }
+}
+
+
+#[test]
+fn test_envelope() {
+    use envelope::{Envelope, Point};
+
+    let envelope = Envelope::from(vec![Point::new(0.5, 0.5, 0.0)]);
+    let serialized = serde_json::to_string(&envelope).unwrap();
+    assert_eq!("{\"points\":[{\"x\":0.5,\"y\":0.5,\"curve\":0.0}],\"sustain_point\":null,\"loop_region\":null}", serialized);
+    let deserialized: Envelope = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(envelope, deserialized);
+}
+
+#[test]
+fn test_oscillator() {
+    use oscillator::{Oscillator, waveform};
+
+    let osc = Oscillator::new(waveform::Sine, 1.0f32, 440.0f64, ());
+    let serialized = serde_json::to_string(&osc).unwrap();
+    assert_eq!("{\"waveform\":null,\"amplitude\":1.0,\"frequency\":440.0,\"freq_warp\":null,\
+               \"is_muted\":false,\"ratio\":1.0,\"fixed_hz\":null,\"free_running\":false,\
+               \"phase_offset\":0.0,\"waveform_crossfade_ms\":0.0,\"previous_waveform\":null,\
+               \"waveform_fade\":0.0,\"quadrature\":false}", serialized);
+    let deserialized: Oscillator<waveform::Sine, f32, f64, ()> =
+        serde_json::from_str(&serialized).unwrap();
+    assert_eq!(osc, deserialized);
+}
+
+#[test]
+fn test_waveform_dynamic() {
+    use oscillator::waveform::Dynamic;
+
+    let saw_exp = Dynamic::SawExp(2.0);
+    let serialized = serde_json::to_string(&saw_exp).unwrap();
+    assert_eq!("{\"SawExp\":2.0}", serialized);
+    let deserialized: Dynamic = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(saw_exp, deserialized);
+}
+
+#[test]
+fn test_voice() {
+    use oscillator;
+    use synth::Voice;
+
+    let voice = Voice {
+        loop_playhead: 5,
+        oscillator_states: oscillator::StatePerVoice(vec![]),
+        loop_reversed: false,
+        stereo_oscillator_states: oscillator::StatePerVoice(vec![]),
+        gain: 1.0,
+    };
+    let serialized = serde_json::to_string(&voice).unwrap();
+    assert_eq!("{\"loop_playhead\":5,\"oscillator_states\":[],\"loop_reversed\":false,\"stereo_oscillator_states\":[],\"gain\":1.0}", serialized);
+    let deserialized: Voice = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(voice, deserialized);
+}
+
+#[test]
+fn test_arpeggiator() {
+    use arpeggiator::{ArpPattern, Arpeggiator};
+    use time;
+
+    let mut arp = Arpeggiator::new(time::Ms(125.0), ArpPattern::UpDown);
+    arp.note_on(440.0, 1.0);
+    let serialized = serde_json::to_string(&arp).unwrap();
+    let deserialized: Arpeggiator = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(arp, deserialized);
+}
+
+#[test]
+fn test_oversampler() {
+    use synth::Oversampler;
+
+    let mut oversampler = Oversampler::new(2);
+    oversampler.push_sample(1, 0.5);
+    let serialized = serde_json::to_string(&oversampler).unwrap();
+    assert_eq!("{\"factor\":2,\"history\":[[],[0.5]]}", serialized);
+    let deserialized: Oversampler = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(oversampler, deserialized);
+}
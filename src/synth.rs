@@ -1,6 +1,7 @@
 //!  Implementation of the `Synth` struct for basic multi-voice, multi-oscillator envelope
 //!  synthesis.
 
+use arpeggiator::{ArpPattern, Arpeggiator};
 use instrument::{self, Instrument, NoteFreq, NoteFreqGenerator};
 use instrument::unit::NoteVelocity;
 use oscillator::{self, Amplitude, Frequency, FreqWarp, Oscillator, Waveform};
@@ -8,7 +9,9 @@ use panning::stereo;
 use pitch;
 use sample::{self, Frame, Sample};
 use std;
+use std::collections::VecDeque;
 use time;
+use tuning::Tuning;
 
 
 pub type LoopStartPerc = f64;
@@ -16,6 +19,82 @@ pub type LoopEndPerc = f64;
 pub type Duration = time::Ms;
 pub type BasePitch = pitch::calc::Hz;
 
+/// The way in which the loop playhead moves between the loop points.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub enum LoopMode {
+    /// Jump back to the loop start upon reaching the loop end.
+    Forward,
+    /// Reverse direction upon reaching either of the loop points.
+    PingPong,
+}
+
+/// The law by which a voice's gain is shared between channels as it is panned.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub enum PanLaw {
+    /// The summed power of the channels is constant, placing each channel at -3dB in the centre.
+    ConstantPower,
+    /// The summed gain of the channels is constant, placing each channel at -6dB in the centre.
+    ConstantGain,
+}
+
+/// The strategy by which active voices are positioned across the channels by the `spread`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub enum SpreadShape {
+    /// Voices are positioned evenly from left to right in order of their index.
+    Linear,
+    /// The first voice is centred while the rest fan out alternately to the left and right.
+    Centered,
+    /// Voices alternate between the far left and far right.
+    Alternating,
+}
+
+/// The ways in which a `Synth` may be misconfigured, as reported by `Synth::try_frames`, or
+/// misused.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SynthError {
+    /// The synth has no oscillators, so it cannot make any sound.
+    NoOscillators,
+    /// The synth has no voices with which to play notes.
+    NoVoices,
+    /// The loop start lies after the loop end or either lies outside of `0.0..1.0`.
+    InvalidLoopPoints(LoopStartPerc, LoopEndPerc),
+    /// The number of channels passed to `Synth::fill_interleaved` is zero or greater than
+    /// `MAX_INTERLEAVED_CHANNELS`.
+    UnsupportedChannels(usize),
+    /// The length of the slice passed to `Synth::fill_interleaved` is not a multiple of the given
+    /// number of channels.
+    InvalidInterleavedLength(usize, usize),
+}
+
+/// The greatest number of channels supported by `Synth::fill_interleaved`.
+pub const MAX_INTERLEAVED_CHANNELS: usize = 8;
+
+/// The number of taps in the `Oversampler`'s filter for each multiple of the sample rate.
+pub const OVERSAMPLER_TAPS_PER_FACTOR: usize = 32;
+
+/// The number of frames rendered at a time while `Synth::render_note` collects a release tail.
+pub const TAIL_BLOCK_FRAMES: usize = 64;
+
+/// The cutoff frequency of the `DcBlocker` in hz.
+pub const DC_BLOCKER_CUTOFF_HZ: f64 = 20.0;
+
+/// The default time constant over which changes to a `Synth`'s volume are smoothed.
+pub const DEFAULT_VOLUME_SMOOTHING_MS: time::Ms = time::Ms(5.0);
+
+/// The default tolerance used to match a `note_off` to a playing note.
+pub const DEFAULT_NOTE_MATCH_TOLERANCE: pitch::calc::Hz = 0.25;
+
+/// The smallest `base_pitch` in hz that a `Synth` will use.
+///
+/// The base pitch divides the frequency of every note, so it must remain positive.
+pub const MIN_BASE_PITCH: BasePitch = 0.01;
+
+/// The range in steps of a MIDI pitch bend message handled by `Synth::handle_midi`.
+pub const MIDI_PITCH_BEND_RANGE: f32 = 2.0;
+
 
 /// The `Synth` generates audio via a vector of `Voice`s, while a `Voice` generates audio via a
 /// vector of `Oscillator`s, creating a small DSP tree.
@@ -39,6 +118,83 @@ pub struct Synth<M, NFG, W, A, F, FW>
     pub duration_ms: Duration,
     /// Base pitch of the Synth instrument in Steps.
     pub base_pitch: BasePitch,
+    /// An optional arpeggiator that steps through the held notes.
+    pub arpeggiator: Option<Arpeggiator>,
+    /// The maximum distance in hz between a `note_off` and the note it releases.
+    pub note_match_tolerance: pitch::calc::Hz,
+    /// The exact frequency of each note that is currently held as retuned by the `tuning`, in the
+    /// order they were pressed.
+    pub held_notes: Vec<pitch::calc::Hz>,
+    /// Whether or not to sum each voice's oscillators using `f64` rather than `f32`.
+    pub high_precision: bool,
+    /// How the playhead moves between the loop points.
+    pub loop_mode: LoopMode,
+    /// Whether notes are freed as soon as their playhead reaches the end of the duration.
+    pub one_shot: bool,
+    /// The amount each voice is panned per octave away from the `base_pitch`.
+    pub key_pan: f32,
+    /// An optional filter that removes any DC offset from the output.
+    pub dc_blocker: Option<DcBlocker>,
+    /// The tuning used to map each incoming note to the frequency that is played.
+    pub tuning: Tuning,
+    /// Whether each voice's frequency is snapped to the nearest equal-tempered semitone.
+    pub glide_quantized: bool,
+    /// The amount that the starting phase of each voice is offset by its index, as a fraction of a cycle.
+    pub phase_spread: f32,
+    /// Whether each note starts its oscillators at a random phase.
+    pub random_start_phase: bool,
+    /// The duration of the fade applied when the synth is stopped while notes are sounding.
+    pub declick_ms: time::Ms,
+    /// The time elapsed since a declicked stop began, if one is in progress.
+    pub declick_elapsed_ms: Option<f64>,
+    /// The time constant over which changes to the `volume` are smoothed.
+    pub volume_smoothing_ms: time::Ms,
+    /// The smoothed volume that is currently applied, which moves toward the `volume` each frame.
+    pub current_volume: f32,
+    /// The current pitch bend applied to every voice, in steps (semitones).
+    pub pitch_bend: f32,
+    /// Whether the sustain pedal is held, deferring each `note_off` until it is released.
+    pub sustain: bool,
+    /// The notes released while the sustain pedal was held, which are stopped once it is released.
+    ///
+    /// Each note is stored as retuned by the `tuning`.
+    pub sustained_notes: Vec<pitch::calc::Hz>,
+    /// The amount in cents that the right channel is detuned relative to the left.
+    pub stereo_detune: f32,
+    /// The duration over which the end of the loop is crossfaded into the material before the loop start.
+    pub loop_crossfade_ms: time::Ms,
+    /// An optional oversampler that renders each output frame at a multiple of the sample rate
+    /// before decimating it, reducing aliasing.
+    pub oversampler: Option<Oversampler>,
+    /// The seed from which the random number generator of each voice is seeded, if any.
+    pub seed: Option<u64>,
+    /// The pan law used to spread voices across the channels, if any.
+    ///
+    /// When `None`, stereo output is panned via `panning::stereo::pan`.
+    pub pan_law: Option<PanLaw>,
+    /// Whether the sostenuto pedal is held, deferring the `note_off` of each note in
+    /// `sostenuto_notes` until it is released.
+    pub sostenuto: bool,
+    /// The notes that were held when the sostenuto pedal was pressed.
+    pub sostenuto_notes: Vec<pitch::calc::Hz>,
+    /// The notes in `sostenuto_notes` released while the sostenuto pedal was held, which are
+    /// stopped once it is released. Each note is stored as retuned by the `tuning`.
+    pub sostenuto_released_notes: Vec<pitch::calc::Hz>,
+    /// The index of the only oscillator that should sound, if any, regardless of each oscillator's
+    /// `is_muted` flag.
+    pub solo_oscillator: Option<usize>,
+    /// The greatest change in hz allowed in each voice's frequency between frames, if limited.
+    pub freq_slew: Option<f64>,
+    /// The strategy by which voices are positioned across the channels by the `spread`.
+    pub spread_shape: SpreadShape,
+    /// Whether or not the synth's output is audible.
+    ///
+    /// While `false` the state of each voice is still advanced, but silence is yielded.
+    pub output_enabled: bool,
+    /// Whether `note_off` is ignored, leaving each note to play through to the end of the duration.
+    pub ignore_note_off: bool,
+    /// The sequence of frequencies that sounding voices are currently gliding through, if any.
+    pub glide_sequence: Option<GlideSequence>,
 }
 
 impl<M, NFG, W, A, F, FW> PartialEq for Synth<M, NFG, W, A, F, FW>
@@ -59,15 +215,104 @@ impl<M, NFG, W, A, F, FW> PartialEq for Synth<M, NFG, W, A, F, FW>
         && self.loop_points == other.loop_points
         && self.duration_ms == other.duration_ms
         && self.base_pitch == other.base_pitch
+        && self.arpeggiator == other.arpeggiator
+        && self.note_match_tolerance == other.note_match_tolerance
+        && self.held_notes == other.held_notes
+        && self.high_precision == other.high_precision
+        && self.loop_mode == other.loop_mode
+        && self.one_shot == other.one_shot
+        && self.key_pan == other.key_pan
+        && self.dc_blocker == other.dc_blocker
+        && self.tuning == other.tuning
+        && self.glide_quantized == other.glide_quantized
+        && self.phase_spread == other.phase_spread
+        && self.random_start_phase == other.random_start_phase
+        && self.declick_ms == other.declick_ms
+        && self.declick_elapsed_ms == other.declick_elapsed_ms
+        && self.volume_smoothing_ms == other.volume_smoothing_ms
+        && self.current_volume == other.current_volume
+        && self.pitch_bend == other.pitch_bend
+        && self.sustain == other.sustain
+        && self.sustained_notes == other.sustained_notes
+        && self.stereo_detune == other.stereo_detune
+        && self.loop_crossfade_ms == other.loop_crossfade_ms
+        && self.oversampler == other.oversampler
+        && self.seed == other.seed
+        && self.pan_law == other.pan_law
+        && self.sostenuto == other.sostenuto
+        && self.sostenuto_notes == other.sostenuto_notes
+        && self.sostenuto_released_notes == other.sostenuto_released_notes
+        && self.solo_oscillator == other.solo_oscillator
+        && self.freq_slew == other.freq_slew
+        && self.spread_shape == other.spread_shape
+        && self.output_enabled == other.output_enabled
+        && self.ignore_note_off == other.ignore_note_off
+        && self.glide_sequence == other.glide_sequence
     }
 }
 
 /// Per-`instrument::Voice` state that is unique to the `Synth`.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
 pub struct Voice {
     pub loop_playhead: time::calc::Samples,
     /// The state of each oscillator unique to each voice.
     pub oscillator_states: oscillator::StatePerVoice,
+    /// Whether the loop playhead is currently moving backwards (see `LoopMode::PingPong`).
+    pub loop_reversed: bool,
+    /// The state of each oscillator used for the right channel while stereo detuning.
+    ///
+    /// See `Synth::stereo_detune`.
+    pub stereo_oscillator_states: oscillator::StatePerVoice,
+    /// The gain applied to the voice's note in addition to its velocity.
+    ///
+    /// See `Synth::note_on_with_gain`.
+    pub gain: f32,
+}
+
+/// A one-pole high-pass filter used to remove any DC offset from the `Synth`'s output.
+///
+/// The previous input and output of each channel is kept so that the filter state persists
+/// between frames.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct DcBlocker {
+    /// The last input sample for each channel.
+    pub last_input: Vec<f32>,
+    /// The last output sample for each channel.
+    pub last_output: Vec<f32>,
+}
+
+/// A FIR low-pass decimator used to bring the output of an oversampled `Synth` back down to the
+/// output sample rate.
+///
+/// The most recent oversampled input of each channel is kept so that the filter state persists
+/// between frames.
+///
+/// The filter taps are computed once from the `factor` by `Oversampler::new` and are not
+/// serialized, so a new oversampler should be constructed whenever the factor changes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Oversampler {
+    /// The number of frames rendered for every output frame.
+    pub factor: u8,
+    /// The taps of the decimation filter.
+    pub taps: Vec<f32>,
+    /// The most recent oversampled input samples for each channel, oldest first.
+    pub history: Vec<VecDeque<f32>>,
+}
+
+/// A sequence of frequencies that each sounding voice glides through in turn, regardless of the
+/// notes that are played.
+///
+/// Each step glides from the previous step's frequency (or the voice's own note for the first
+/// step) to its target over its glide time. The last target is held once the sequence finishes.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub struct GlideSequence {
+    /// The target frequency of each step, along with the milliseconds taken to glide to it.
+    pub steps: Vec<(pitch::calc::Hz, f64)>,
+    /// The time elapsed since the sequence began.
+    pub elapsed_ms: f64,
 }
 
 /// An iterator that uniquely borrows the `Synth` and endlessly yields `Frame`s.
@@ -83,11 +328,33 @@ pub struct Frames<'a, FRM, NF: 'a, W: 'a, A: 'a, F: 'a, FW: 'a> {
     oscillators: &'a mut [Oscillator<W, A, F, FW>],
     voices: &'a mut [Voice],
     loop_points: Option<(time::calc::Samples, time::calc::Samples)>,
+    loop_mode: LoopMode,
     instrument_frames: instrument::Frames<'a, NF>,
     duration: time::calc::Samples,
     base_pitch: BasePitch,
     volume: f32,
     spread: f32,
+    high_precision: bool,
+    key_pan: f32,
+    dc_blocker: Option<&'a mut DcBlocker>,
+    glide_quantized: bool,
+    phase_spread: f32,
+    random_start_phase: bool,
+    declick_ms: f64,
+    current_volume: &'a mut f32,
+    volume_smoothing_ms: f64,
+    declick_elapsed_ms: Option<&'a mut f64>,
+    pitch_bend: f32,
+    stereo_detune: f32,
+    loop_crossfade: time::calc::Samples,
+    oversampler: Option<&'a mut Oversampler>,
+    pan_law: Option<PanLaw>,
+    held_notes: &'a [pitch::calc::Hz],
+    solo_oscillator: Option<usize>,
+    freq_slew: Option<f64>,
+    spread_shape: SpreadShape,
+    output_enabled: bool,
+    glide_sequence: Option<&'a mut GlideSequence>,
     frame: std::marker::PhantomData<FRM>,
 }
 
@@ -116,6 +383,25 @@ impl<NFG, W, A, F, FW> Synth<instrument::mode::Poly, NFG, W, A, F, FW>
     }
 }
 
+impl<M, NFG, A, F, FW> Synth<M, NFG, oscillator::waveform::Bakeable, A, F, FW>
+    where NFG: NoteFreqGenerator,
+{
+    /// Render each oscillator's waveform into a `Wavetable` of `resolution` samples and swap the
+    /// oscillator over to reading from the table.
+    ///
+    /// This trades a little memory and fidelity for a much cheaper per-frame cost. Only static
+    /// waveforms are baked; `Noise` and the noise walks are skipped as they have no fixed period.
+    /// Oscillators added afterwards are not baked. Only available for synths whose oscillators use
+    /// the `Bakeable` waveform.
+    pub fn bake_wavetables(&mut self, resolution: usize) {
+        for osc in self.oscillators.iter_mut() {
+            if let Some(baked) = osc.waveform.bake(resolution) {
+                osc.waveform = baked;
+            }
+        }
+    }
+}
+
 impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
     where NFG: NoteFreqGenerator,
 {
@@ -130,6 +416,9 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
         let default_voice = Voice {
             loop_playhead: 0,
             oscillator_states: oscillator::StatePerVoice(Vec::new()),
+            loop_reversed: false,
+            stereo_oscillator_states: oscillator::StatePerVoice(Vec::new()),
+            gain: 1.0,
         };
         Synth {
             oscillators: Vec::new(),
@@ -141,6 +430,39 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
             base_pitch: C_1,
             loop_points: None,
             instrument: instrument,
+            arpeggiator: None,
+            note_match_tolerance: DEFAULT_NOTE_MATCH_TOLERANCE,
+            held_notes: Vec::new(),
+            high_precision: false,
+            loop_mode: LoopMode::Forward,
+            one_shot: false,
+            key_pan: 0.0,
+            dc_blocker: None,
+            tuning: Tuning::EqualTemperament,
+            glide_quantized: false,
+            phase_spread: 0.0,
+            random_start_phase: false,
+            declick_ms: time::Ms(0.0),
+            declick_elapsed_ms: None,
+            volume_smoothing_ms: DEFAULT_VOLUME_SMOOTHING_MS,
+            current_volume: 1.0,
+            pitch_bend: 0.0,
+            sustain: false,
+            sustained_notes: Vec::new(),
+            stereo_detune: 0.0,
+            loop_crossfade_ms: time::Ms(0.0),
+            oversampler: None,
+            seed: None,
+            pan_law: None,
+            sostenuto: false,
+            sostenuto_notes: Vec::new(),
+            sostenuto_released_notes: Vec::new(),
+            solo_oscillator: None,
+            freq_slew: None,
+            spread_shape: SpreadShape::Linear,
+            output_enabled: true,
+            ignore_note_off: false,
+            glide_sequence: None,
         }
     }
 
@@ -152,23 +474,81 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
     }
 
     /// Set the number of voices that the Synth shall use.
+    ///
+    /// A Synth must have at least one voice, so a `num_voices` of `0` is treated as `1`.
     #[inline]
     pub fn set_num_voices(&mut self, num_voices: usize) {
+        let num_voices = std::cmp::max(1, num_voices);
         self.instrument.set_num_voices(num_voices);
-        if num_voices == 0 {
-            println!("A Synth must have at least one voice, but the requested number is 0.");
-        } else {
-            let len = self.voices.len();
-            if len < num_voices {
-                let last_voice = self.voices[len-1].clone();
-                let extension = std::iter::repeat(last_voice).take(num_voices - len);
-                self.voices.extend(extension);
-            } else if len > num_voices {
-                self.voices.truncate(num_voices);
+        let len = self.voices.len();
+        if len < num_voices {
+            let last_voice = self.voices[len-1].clone();
+            let extension = std::iter::repeat(last_voice).take(num_voices - len);
+            self.voices.extend(extension);
+        } else if len > num_voices {
+            self.voices.truncate(num_voices);
+        }
+        self.sync_oscillator_states();
+    }
+
+    /// Ensure that every voice has exactly one `oscillator::State` per oscillator, adding new
+    /// states or removing trailing states as necessary.
+    fn sync_oscillator_states(&mut self) {
+        let num_oscillators = self.oscillators.len();
+        let num_voices = self.voices.len();
+        for (i, voice) in self.voices.iter_mut().enumerate() {
+            let states = &mut voice.oscillator_states.0;
+            states.truncate(num_oscillators);
+            while states.len() < num_oscillators {
+                states.push(oscillator::State {
+                    phase: voice_start_phase(self.phase_spread, self.random_start_phase, i, num_voices),
+                    freq_warp_phase: 0.0,
+                    rng: None,
+                    note_held: false,
+                    last_hz: None,
+                    max_hz_per_sample: None,
+                });
+            }
+            // Every state is reseeded so that voices cloned from one another remain independent.
+            if let Some(seed) = self.seed {
+                for (j, state) in states.iter_mut().enumerate() {
+                    state.rng = Some(voice_rng_seed(seed, i, j));
+                }
+            }
+        }
+    }
+
+    /// Reset the oscillator states of every voice to their starting phase.
+    ///
+    /// Free-running oscillators keep their phase.
+    fn reset_phases(&mut self) {
+        let num_voices = self.voices.len();
+        for (i, voice) in self.voices.iter_mut().enumerate() {
+            voice.stereo_oscillator_states.0.clear();
+            let states = voice.oscillator_states.0.iter_mut().zip(self.oscillators.iter());
+            for (osc_state, osc) in states {
+                if !osc.free_running {
+                    osc_state.phase =
+                        voice_start_phase(self.phase_spread, self.random_start_phase, i, num_voices);
+                }
+                osc_state.freq_warp_phase = 0.0;
             }
         }
     }
 
+    /// Panics if any voice does not have exactly one `oscillator::State` per oscillator.
+    ///
+    /// The synth upholds this itself, so this is mostly useful for testing code that mutates the
+    /// public `voices` or `oscillators` fields directly.
+    pub fn assert_consistent(&self) {
+        let num_oscillators = self.oscillators.len();
+        for (i, voice) in self.voices.iter().enumerate() {
+            assert!(voice.oscillator_states.0.len() == num_oscillators,
+                    "voice {} has {} oscillator states but the synth has {} oscillators",
+                    i, voice.oscillator_states.0.len(), num_oscillators);
+        }
+    }
+
     /// Add an oscillator to a Synth.
     #[inline]
     pub fn oscillator(mut self, oscillator: Oscillator<W, A, F, FW>) -> Self {
@@ -180,21 +560,19 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
     #[inline]
     pub fn oscillators<I: Iterator<Item=Oscillator<W, A, F, FW>>>(mut self, oscillators: I) -> Self
     {
-        let len = self.oscillators.len();
         self.oscillators.extend(oscillators);
-        let target_len = self.oscillators.len();
-        for &mut Voice { ref mut oscillator_states, .. } in &mut self.voices {
-            let new_states = (len..target_len).map(|_| oscillator::State::new());
-            oscillator_states.0.extend(new_states);
-        }
+        self.sync_oscillator_states();
         self
     }
 
     /// Set the Synth's duration.
+    ///
+    /// Negative durations are treated as `0.0`. When rendering, the duration is always at least
+    /// one sample long.
     pub fn duration<D>(mut self, duration_ms: D) -> Self
         where D: Into<time::Ms>,
     {
-        self.duration_ms = duration_ms.into();
+        self.duration_ms = time::Ms(duration_ms.into().0.max(0.0));
         self
     }
 
@@ -222,26 +600,216 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
     // }
 
     /// Set the Synth's base pitch.
+    ///
+    /// The base pitch is clamped to at least `MIN_BASE_PITCH`.
     pub fn base_pitch(mut self, base_pitch: BasePitch) -> Self {
-        self.base_pitch = base_pitch;
+        self.base_pitch = base_pitch.max(MIN_BASE_PITCH);
         self
     }
 
     /// Set the Synth's detune amount.
+    ///
+    /// The detune is measured in steps (semitones) and is passed to the `NoteFreqGenerator` for
+    /// each voice, which offsets the voice's note by up to this many steps. See `detune_cents` for
+    /// a finer grained control.
     pub fn detune(mut self, detune: f32) -> Self {
         self.instrument.detune = detune;
         self
     }
 
+    /// Set the Synth's detune amount in cents, where 100 cents is equal to one step.
+    pub fn detune_cents(self, cents: f32) -> Self {
+        self.detune(cents / 100.0)
+    }
+
+    /// Set the tuning used to map incoming notes to the frequencies played by the synth.
+    ///
+    /// The default `Tuning::EqualTemperament` plays every note at exactly the given frequency.
+    /// Notes are retuned before the instrument's detune is applied.
+    pub fn tuning(mut self, tuning: Tuning) -> Self {
+        self.tuning = tuning;
+        self
+    }
+
+    /// Set whether or not gliding notes should step through each semitone rather than sliding
+    /// continuously, similar to the "glissando" mode found on some classic synths.
+    ///
+    /// The frequency of every voice is rounded to the nearest equal-tempered semitone each frame.
+    /// Note that this also snaps any detune and any notes retuned by a non-equal `Tuning`.
+    pub fn glide_quantized(mut self, glide_quantized: bool) -> Self {
+        self.glide_quantized = glide_quantized;
+        self
+    }
+
+    /// Offset the starting phase of each voice's oscillators according to the index of the voice.
+    ///
+    /// The starting phases are spread evenly across the given fraction of a cycle, so that a
+    /// `phase_spread` of `1.0` distributes the voices across an entire cycle. This reduces the
+    /// "phasey" build-up that occurs when many voices play in unison. Voices return to their
+    /// starting phase whenever they become idle.
+    pub fn phase_spread(mut self, phase_spread: f32) -> Self {
+        self.phase_spread = phase_spread;
+        self.reset_phases();
+        self
+    }
+
+    /// Seed the random number generator of each voice so that noise waveforms are reproducible.
+    ///
+    /// Each oscillator of each voice draws from its own generator, seeded deterministically from
+    /// the given `seed` and the voice's index so that the voices remain independent of one
+    /// another. Unseeded synths draw their noise from the global `rand::random`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.sync_oscillator_states();
+        self
+    }
+
+    /// Set whether or not each note should start its oscillators at a random phase.
+    ///
+    /// This avoids the identical transient at the start of every note. The random phase is added
+    /// to any offset applied via `phase_spread`.
+    pub fn random_start_phase(mut self, random_start_phase: bool) -> Self {
+        self.random_start_phase = random_start_phase;
+        self.reset_phases();
+        self
+    }
+
+    /// Set the duration of the fade applied by `stop` when notes are still sounding.
+    ///
+    /// Stopping the synth mid-note otherwise cuts the output abruptly, producing an audible
+    /// click. A few milliseconds is usually enough. The voices are freed by `fill_slice` once the
+    /// fade has completed. The default of `0.0` stops immediately.
+    pub fn declick_ms<T>(mut self, declick_ms: T) -> Self
+        where T: Into<time::Ms>,
+    {
+        self.declick_ms = declick_ms.into();
+        self
+    }
+
     /// Set the Synth's spread amount.
+    ///
+    /// Voices are spread between the first and last channel of any output with two or more
+    /// channels, using an equal-power pan between each pair of adjacent channels. The positions of
+    /// the voices are determined by the `spread_shape`, which spreads them evenly by default.
     pub fn spread(mut self, spread: f32) -> Self {
         self.spread = spread;
         self
     }
 
+    /// Detune the right channel of a stereo synth relative to the left by the given cents.
+    ///
+    /// Each channel is detuned by half of the amount in opposite directions, producing a
+    /// chorus-like widening of the sound. Note that this renders every oscillator twice per frame for
+    /// stereo output, roughly doubling the CPU cost of the synth. It has no effect on non-stereo
+    /// output.
+    pub fn stereo_detune(mut self, cents: f32) -> Self {
+        self.stereo_detune = cents;
+        self
+    }
+
+    /// Pan each voice according to the pitch of its note, independently of the `spread`.
+    ///
+    /// Each voice is panned by `amount` for every octave its note lies above the `base_pitch`, so
+    /// that notes below the `base_pitch` are panned to the left and notes above it to the right.
+    /// The result is combined with the `spread` and clamped to the range `-1.0..1.0`.
+    pub fn key_pan(mut self, amount: f32) -> Self {
+        self.key_pan = amount;
+        self
+    }
+
+    /// Set the strategy by which voices are positioned across the channels by the `spread`.
+    ///
+    /// By default voices are spread linearly from left to right in order of their index.
+    pub fn spread_shape(mut self, shape: SpreadShape) -> Self {
+        self.spread_shape = shape;
+        self
+    }
+
+    /// Set the pan law used when spreading voices across the channels via `spread` or `key_pan`.
+    ///
+    /// By default stereo output is panned via `panning::stereo::pan`, while output with more
+    /// channels is panned with constant power.
+    pub fn pan_law(mut self, pan_law: PanLaw) -> Self {
+        self.pan_law = Some(pan_law);
+        self
+    }
+
+    /// Solo the oscillator at the given index so that only it sounds, regardless of whether or not
+    /// any of the oscillators are muted. `None` restores the usual mix.
+    ///
+    /// This is useful for auditioning each oscillator while designing a patch.
+    pub fn solo_oscillator(mut self, idx: Option<usize>) -> Self {
+        self.solo_oscillator = idx;
+        self
+    }
+
+    /// Limit how quickly the frequency of each voice may change, in hz per sample.
+    ///
+    /// Large instantaneous jumps in frequency (e.g. from a stepped frequency envelope or a sudden
+    /// pitch bend) are instead ramped at the given rate, avoiding clicks. By default the frequency
+    /// is unlimited.
+    pub fn freq_slew(mut self, max_hz_per_sample: f64) -> Self {
+        self.freq_slew = Some(max_hz_per_sample.abs());
+        self
+    }
+
+    /// Set whether or not a DC blocking filter should be applied to the output.
+    ///
+    /// This removes any DC offset introduced by asymmetric waveforms (i.e. `SawExp`) or frequency
+    /// warping, leaving more headroom for the signal itself.
+    pub fn dc_block(mut self, dc_block: bool) -> Self {
+        self.dc_blocker = if dc_block { Some(DcBlocker::new()) } else { None };
+        self
+    }
+
+    /// Render each frame at `factor` times the sample rate before decimating it back down.
+    ///
+    /// Oversampling reduces the aliasing of waveforms with harmonics above the nyquist (i.e. the
+    /// `Saw` and `Square`) at the cost of rendering `factor` frames for every output frame. A
+    /// `factor` of `2` or `4` is usually enough, while `1` disables oversampling.
+    pub fn oversample(mut self, factor: u8) -> Self {
+        self.oversampler = if factor > 1 { Some(Oversampler::new(factor)) } else { None };
+        self
+    }
+
     /// Set the Synth's volume.
+    ///
+    /// The volume is applied immediately. Changes made to the `volume` field during playback are
+    /// smoothed over the `volume_smoothing_ms`.
     pub fn volume(mut self, vol: f32) -> Self {
         self.volume = vol;
+        self.current_volume = vol;
+        self
+    }
+
+    /// Set whether or not the synth's output is audible.
+    ///
+    /// While disabled the synth continues to advance the phase, playhead and envelopes of each
+    /// voice but yields silence, so that it remains in time with the rest of the output. Unlike
+    /// setting the `volume` to `0.0`, this takes effect immediately without smoothing.
+    pub fn output_enabled(mut self, enabled: bool) -> Self {
+        self.output_enabled = enabled;
+        self
+    }
+
+    /// Set the time constant over which changes to the `volume` are smoothed.
+    ///
+    /// Smoothing avoids the "zipper" noise caused by stepping the volume between buffers. The
+    /// default is 5ms, while `0.0` applies changes immediately.
+    pub fn volume_smoothing_ms<T>(mut self, ms: T) -> Self
+        where T: Into<time::Ms>,
+    {
+        self.volume_smoothing_ms = ms.into();
+        self
+    }
+
+    /// Set whether or not each voice's oscillators should be summed using `f64` precision.
+    ///
+    /// This reduces the accumulated rounding error when many oscillators are stacked, which can be
+    /// worthwhile for high quality offline rendering. The extra conversions make each frame
+    /// slightly more expensive to compute, so this is disabled by default.
+    pub fn high_precision(mut self, high_precision: bool) -> Self {
+        self.high_precision = high_precision;
         self
     }
 
@@ -251,8 +819,79 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
     }
 
     /// Set the loop data for the synth.
+    ///
+    /// Both points are clamped to the range `0.0..1.0` and swapped if the `start` lies after the
+    /// `end`.
     pub fn loop_points(mut self, start: LoopStartPerc, end: LoopEndPerc) -> Self {
-        self.loop_points = Some((start, end));
+        self.loop_points = Some(normalize_loop_points(start, end));
+        self
+    }
+
+    /// Set the loop points in beats at the given tempo in beats per minute.
+    ///
+    /// The beats are converted to percentages of the synth's current duration, so the duration
+    /// should be set before calling this. Points that fall beyond the end of the duration are
+    /// clamped to the end.
+    pub fn loop_beats(self, start_beats: f64, end_beats: f64, bpm: f64) -> Self {
+        let duration_ms = self.duration_ms.0;
+        let beats_to_perc = |beats: f64| {
+            let ms = beats * 60_000.0 / bpm;
+            (ms / duration_ms).max(0.0).min(1.0)
+        };
+        let start = beats_to_perc(start_beats);
+        let end = beats_to_perc(end_beats);
+        self.loop_points(start, end)
+    }
+
+    /// Set the way in which the playhead moves between the loop points.
+    ///
+    /// With `LoopMode::PingPong` the playhead reverses direction at each loop point, so the
+    /// envelopes are also read backwards on every other pass through the loop.
+    pub fn loop_mode(mut self, loop_mode: LoopMode) -> Self {
+        self.loop_mode = loop_mode;
+        self
+    }
+
+    /// Crossfade the end of the loop into the material leading up to the loop start over the
+    /// given duration, removing the click caused by any discontinuity at the loop points.
+    ///
+    /// The crossfade only applies to `LoopMode::Forward` and is limited to the length of the loop
+    /// and the time before the loop start. While crossfading, every oscillator is rendered twice
+    /// per frame, doubling the CPU cost of each looping voice for the duration of the crossfade.
+    pub fn loop_crossfade_ms<T>(mut self, crossfade_ms: T) -> Self
+        where T: Into<time::Ms>,
+    {
+        self.loop_crossfade_ms = crossfade_ms.into();
+        self
+    }
+
+    /// Play each note through the synth's duration exactly once, freeing the voices as soon as
+    /// every sounding note has reached the end so that `is_active` returns `false`.
+    ///
+    /// The loop points are ignored while in one-shot mode. A `note_off` that arrives before the
+    /// end of the duration still fades the note out over the instrument's release as usual, while
+    /// a note that reaches the end is cut immediately as there is nothing left to fade. Voices are
+    /// only freed by `fill_slice`, so the `Frames` iterator alone will not free them.
+    pub fn one_shot(mut self) -> Self {
+        self.one_shot = true;
+        self
+    }
+
+    /// Set whether `note_off` should be ignored so that each note plays its amplitude envelope
+    /// through to completion rather than fading out over the instrument's release.
+    ///
+    /// This is intended for percussive patches and is best combined with `one_shot`, which frees
+    /// each voice once it reaches the end of the duration. Otherwise notes continue to sound until
+    /// the synth is stopped, and offline renders such as `render_note` cut the note off once its
+    /// tail reaches `tail_len_samples`.
+    pub fn ignore_note_off(mut self, ignore: bool) -> Self {
+        self.ignore_note_off = ignore;
+        self
+    }
+
+    /// Set the mode of the synth's instrument, e.g. `instrument::mode::Mono::legato()`.
+    pub fn mode(mut self, mode: M) -> Self {
+        self.instrument.mode = mode;
         self
     }
 
@@ -265,14 +904,42 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
     }
 
     /// Set the start loop point.
-    pub fn loop_start(mut self, start: LoopStartPerc) -> Self {
-        self.loop_points = self.loop_points.map(|(_, end)| (start, end)).or(Some((start, 1.0)));
-        self
+    pub fn loop_start(self, start: LoopStartPerc) -> Self {
+        let end = self.loop_points.map(|(_, end)| end).unwrap_or(1.0);
+        self.loop_points(start, end)
     }
 
     /// Set the end loop point.
-    pub fn loop_end(mut self, end: LoopEndPerc) -> Self {
-        self.loop_points = self.loop_points.map(|(start, _)| (start, end)).or(Some((1.0, end)));
+    ///
+    /// If no loop points were set, the loop starts from the beginning of the duration.
+    pub fn loop_end(self, end: LoopEndPerc) -> Self {
+        let start = self.loop_points.map(|(start, _)| start).unwrap_or(0.0);
+        self.loop_points(start, end)
+    }
+
+    /// Arpeggiate the held notes, stepping to the next note every `rate` in the order described
+    /// by the given `pattern`.
+    ///
+    /// While arpeggiating, `note_on` and `note_off` only update the set of held notes and the
+    /// arpeggiator triggers the notes on the instrument itself. Steps are only taken by
+    /// `fill_slice`, so the `Frames` iterator alone will not advance the arpeggiator.
+    pub fn arpeggiate<R>(mut self, rate: R, pattern: ArpPattern) -> Self
+        where R: Into<time::Ms>,
+    {
+        self.arpeggiator = Some(Arpeggiator::new(rate.into(), pattern));
+        self
+    }
+
+    /// Set the maximum distance in hz between the frequency given to `note_off` and the held note
+    /// that it should release.
+    ///
+    /// The synth remembers the exact frequency of every note passed to `note_on`. `note_off`
+    /// releases the closest held note within the tolerance and forwards that exact frequency to
+    /// the instrument, so the `Mono` note stack (which stores raw hz values) always receives the
+    /// same value for a note's `note_off` as it did for its `note_on`. The default tolerance is
+    /// 0.25 hz.
+    pub fn note_match_tolerance(mut self, hz: pitch::calc::Hz) -> Self {
+        self.note_match_tolerance = hz;
         self
     }
 
@@ -293,17 +960,78 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
     /// Add an oscillator.
     pub fn add_oscillator(&mut self, oscillator: Oscillator<W, A, F, FW>) {
         self.oscillators.push(oscillator);
-        for voice in &mut self.voices {
-            voice.oscillator_states.0.push(oscillator::State::new());
-        }
+        self.sync_oscillator_states();
     }
 
     /// Remove and return the oscillator at the given idx.
+    ///
+    /// Panics if `idx` is out of bounds. See `try_remove_oscillator` for a non-panicking version.
     pub fn remove_oscillator(&mut self, idx: usize) -> Oscillator<W, A, F, FW> {
+        self.try_remove_oscillator(idx)
+            .expect("The given oscillator index is out of bounds.")
+    }
+
+    /// Remove and return the oscillator at the given idx along with its state for each voice.
+    ///
+    /// Returns `None` and leaves the synth untouched if `idx` is out of bounds.
+    pub fn try_remove_oscillator(&mut self, idx: usize) -> Option<Oscillator<W, A, F, FW>> {
+        if idx >= self.oscillators.len() {
+            return None;
+        }
         for voice in &mut self.voices {
             voice.oscillator_states.0.remove(idx);
         }
-        self.oscillators.remove(idx)
+        Some(self.oscillators.remove(idx))
+    }
+
+    /// An iterator yielding a reference to each of the synth's oscillators.
+    pub fn oscillators_iter(&self) -> std::slice::Iter<Oscillator<W, A, F, FW>> {
+        self.oscillators.iter()
+    }
+
+    /// An iterator yielding a mutable reference to each of the synth's oscillators.
+    ///
+    /// This is useful for editing many oscillators at once.
+    ///
+    /// ```
+    /// extern crate synth;
+    ///
+    /// use synth::{Oscillator, Synth};
+    /// use synth::oscillator::waveform::Sine;
+    ///
+    /// fn main() {
+    ///     let mut synth = Synth::poly(())
+    ///         .oscillator(Oscillator::new(Sine, 1.0, 440.0, ()))
+    ///         .oscillator(Oscillator::new(Sine, 0.5, 880.0, ()));
+    ///
+    ///     for osc in synth.oscillators_mut() {
+    ///         osc.is_muted = true;
+    ///     }
+    ///
+    ///     assert!(synth.oscillators_iter().all(|osc| osc.is_muted));
+    /// }
+    /// ```
+    pub fn oscillators_mut(&mut self) -> std::slice::IterMut<Oscillator<W, A, F, FW>> {
+        self.oscillators.iter_mut()
+    }
+
+    /// Replace the oscillator at the given idx, returning the previous oscillator.
+    ///
+    /// The per-voice state of the oscillator is preserved, so that the new oscillator continues
+    /// from the phase of the one it replaces. Returns `None` and leaves the synth untouched if
+    /// `idx` is out of bounds.
+    pub fn set_oscillator(&mut self, idx: usize, oscillator: Oscillator<W, A, F, FW>)
+        -> Option<Oscillator<W, A, F, FW>>
+    {
+        self.oscillators.get_mut(idx).map(|osc| std::mem::replace(osc, oscillator))
+    }
+
+    /// Remove all oscillators along with their state for each voice.
+    pub fn clear_oscillators(&mut self) {
+        self.oscillators.clear();
+        for voice in &mut self.voices {
+            voice.oscillator_states.0.clear();
+        }
     }
 
     /// Return whether or not there are any currently active voices.
@@ -311,6 +1039,78 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
         self.instrument.is_active()
     }
 
+    /// Return the number of voices that are currently playing a note.
+    ///
+    /// This is `0` if and only if the synth is not active.
+    pub fn num_active_voices(&self) -> usize {
+        self.instrument.voices.iter().filter(|voice| voice.note.is_some()).count()
+    }
+
+    /// The progress of each voice through the instrument's release in the range `0.0..1.0`.
+    ///
+    /// There is one element for each voice, which is `None` if the voice is idle or its note is
+    /// still playing. Useful for scheduling note stealing or drawing fades in a UI. No playheads
+    /// are advanced.
+    pub fn voice_release_progress(&self, sample_hz: f64) -> Vec<Option<f32>> {
+        let release = self.instrument.release_ms.samples(self.render_hz(sample_hz));
+        self.instrument.voices.iter()
+            .map(|voice| match voice.note {
+                Some((instrument::NoteState::Released(playhead), _, _)) => {
+                    if release <= 0 {
+                        Some(1.0)
+                    } else {
+                        Some((playhead as f32 / release as f32).max(0.0).min(1.0))
+                    }
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The phase of the given voice's oscillator at `osc_idx`, wrapped to the range `0.0..1.0`.
+    ///
+    /// Returns `None` if there is no such voice or oscillator state. Useful for visualising the
+    /// oscillators of each voice.
+    pub fn normalized_phase(&self, voice_idx: usize, osc_idx: usize) -> Option<f64> {
+        self.voices.get(voice_idx)
+            .and_then(|voice| voice.oscillator_states.normalized_phase(osc_idx))
+    }
+
+    /// The playhead of the most recently triggered voice as a percentage of the duration.
+    ///
+    /// This is the sounding voice whose loop playhead is furthest behind, which is typically the
+    /// voice playing the newest note. Useful for drawing a cursor over the envelopes in a UI.
+    ///
+    /// Returns `None` if no voices are currently sounding.
+    pub fn current_playhead_perc(&self, sample_hz: f64) -> Option<f64> {
+        let duration = self.duration_ms.samples(self.render_hz(sample_hz));
+        if duration <= 0 {
+            return None;
+        }
+        self.voices.iter()
+            .map(|voice| voice.loop_playhead)
+            .filter(|&playhead| playhead > 0)
+            .min()
+            .map(|playhead| playhead as f64 / duration as f64)
+    }
+
+    /// The number of samples that the synth may continue to sound for after the last `note_off`.
+    ///
+    /// This is the instrument's release converted to samples at the given rate. Hosts may use this
+    /// to determine when a rendered region has fallen truly silent.
+    ///
+    /// When `ignore_note_off` is combined with `one_shot`, notes instead play through to the end of
+    /// the duration, so the tail is at least the duration long.
+    pub fn tail_len_samples(&self, sample_hz: f64) -> usize {
+        let release = self.instrument.release_ms.samples(sample_hz);
+        let tail = if self.ignore_note_off && self.one_shot {
+            std::cmp::max(release, self.duration_ms.samples(sample_hz))
+        } else {
+            release
+        };
+        std::cmp::max(0, tail) as usize
+    }
+
     /// Begin playback of a note. Synth will try to use a free `Voice` to do this.
     /// If no `Voice`s are free, the one playing the oldest note will be chosen to
     /// play the new note instead.
@@ -319,60 +1119,511 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
         where M: instrument::Mode,
               T: Into<pitch::Hz>
     {
-        self.instrument.note_on(note_hz.into().hz(), note_vel);
+        // A new note cuts short any declicked stop that is in progress.
+        if self.declick_elapsed_ms.is_some() {
+            self.stop_now();
+        }
+        let hz = self.tuning.retune(note_hz.into().hz());
+        // A note that is pressed again is no longer waiting for the sustain pedal.
+        if let Some(idx) = self.sustained_notes.iter().position(|&held| held == hz) {
+            self.sustained_notes.remove(idx);
+        }
+        if let Some(idx) = self.sostenuto_released_notes.iter().position(|&held| held == hz) {
+            self.sostenuto_released_notes.remove(idx);
+        }
+        self.held_notes.push(hz);
+        match self.arpeggiator {
+            Some(ref mut arp) => arp.note_on(hz, note_vel),
+            None => self.instrument.note_on(hz, note_vel),
+        }
     }
 
-    /// Stop playback of the note that was triggered with the matching frequency.
-    #[inline]
-    pub fn note_off<T>(&mut self, note_hz: T)
+    /// Begin playback of every note in a chord at once.
+    ///
+    /// Free voices are used first, after which the instrument's stealing policy steals the
+    /// voices of the oldest notes that were playing before the chord. The chord never steals
+    /// its own notes: if the chord has more notes than the synth has voices, only the first
+    /// `voices.len()` notes are played.
+    pub fn chord_on<T>(&mut self, notes: &[(T, NoteVelocity)])
         where M: instrument::Mode,
-              T: Into<pitch::Hz>
+              T: Into<pitch::Hz> + Copy,
     {
-        self.instrument.note_off(note_hz.into().hz());
+        let num_voices = self.voices.len();
+        for &(note_hz, note_vel) in notes.iter().take(num_voices) {
+            self.note_on(note_hz, note_vel);
+        }
     }
 
-    /// Stop playback and clear the current notes.
-    #[inline]
-    pub fn stop(&mut self)
+    /// Begin playback of a note as with `note_on`, scaling the note's amplitude by the given
+    /// `gain` independently of its velocity.
+    ///
+    /// The gain applies to the voice that plays the note until the voice is freed. Notes played by
+    /// an arpeggiator are not affected by the gain.
+    pub fn note_on_with_gain<T>(&mut self, note_hz: T, note_vel: NoteVelocity, gain: f32)
         where M: instrument::Mode,
+              T: Into<pitch::Hz>,
+              instrument::Voice<NFG::NoteFreq>: Clone + PartialEq,
     {
-        self.instrument.stop();
-        for voice in &mut self.voices {
-            for osc_state in &mut voice.oscillator_states.0 {
-                *osc_state = oscillator::State::new();
-            }
+        // Apply the gain to any voice whose note was changed by the `note_on`.
+        for idx in self.note_on_changed_voices(note_hz, note_vel) {
+            self.voices[idx].gain = gain;
         }
     }
 
-    /// Map the `Instrument` to a new `Instrument` in place.
+    /// Begin playback of a note as with `note_on`, returning the index of the voice that was
+    /// assigned the note.
     ///
-    /// This is useful for providing wrapper builder methods for the Synth.
-    #[inline]
-    pub fn map_instrument<Map, NewM, NewNFG>(self, map: Map) -> Synth<NewM, NewNFG, W, A, F, FW>
-        where Map: FnOnce(Instrument<M, NFG>) -> Instrument<NewM, NewNFG>,
-              NewNFG: NoteFreqGenerator,
+    /// This is useful for displaying which voice is playing each note. Returns `None` if no voice
+    /// was assigned, e.g. when the note is played by an arpeggiator.
+    pub fn note_on_tracked<T>(&mut self, note_hz: T, note_vel: NoteVelocity) -> Option<usize>
+        where M: instrument::Mode,
+              T: Into<pitch::Hz>,
+              instrument::Voice<NFG::NoteFreq>: Clone + PartialEq,
     {
-        let Synth {
-            oscillators,
-            voices,
-            duration_ms,
-            base_pitch,
-            volume,
-            spread,
-            instrument,
-            loop_points,
-        } = self;
+        self.note_on_changed_voices(note_hz, note_vel).into_iter().next()
+    }
 
-        Synth {
-            oscillators: oscillators,
+    /// Begin playback of a note as with `note_on`, returning the index of each voice whose note
+    /// was changed.
+    fn note_on_changed_voices<T>(&mut self, note_hz: T, note_vel: NoteVelocity) -> Vec<usize>
+        where M: instrument::Mode,
+              T: Into<pitch::Hz>,
+              instrument::Voice<NFG::NoteFreq>: Clone + PartialEq,
+    {
+        let voices_before = self.instrument.voices.clone();
+        self.note_on(note_hz, note_vel);
+        self.instrument.voices.iter()
+            .zip(voices_before.iter())
+            .enumerate()
+            .filter(|&(_, (new, old))| new != old && new.note.is_some())
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Glide the frequency of every sounding voice through the given sequence of steps, replacing
+    /// any sequence already in progress.
+    ///
+    /// Each step is a target frequency and the time taken to glide to it from the previous step.
+    /// The sequence runs regardless of `note_on` and `note_off`, holding its last target until it
+    /// is cleared via `clear_note_sequence`.
+    pub fn note_sequence(&mut self, steps: Vec<(pitch::Hz, time::Ms)>) {
+        let steps = steps.into_iter().map(|(hz, ms)| (hz.hz(), ms.0)).collect();
+        self.glide_sequence = Some(GlideSequence::new(steps));
+    }
+
+    /// Stop any sequence started via `note_sequence`, returning each voice to its own note.
+    pub fn clear_note_sequence(&mut self) {
+        self.glide_sequence = None;
+    }
+
+    /// Stop playback of the note that was triggered with the matching frequency.
+    ///
+    /// The closest held note within the `note_match_tolerance` is released.
+    ///
+    /// If the sustain pedal is held, the note is released once the pedal is released instead. The
+    /// same applies to notes that were held when the sostenuto pedal was pressed.
+    #[inline]
+    pub fn note_off<T>(&mut self, note_hz: T)
+        where M: instrument::Mode,
+              T: Into<pitch::Hz>
+    {
+        let hz = self.tuning.retune(note_hz.into().hz());
+        self.release_note(hz);
+    }
+
+    /// Release the note with the given frequency, which has already been retuned by the `tuning`.
+    ///
+    /// `Tuning::retune` is not idempotent, so notes that are already retuned (i.e. the
+    /// `held_notes`) must be released via this method rather than `note_off`.
+    fn release_note(&mut self, hz: pitch::calc::Hz)
+        where M: instrument::Mode,
+    {
+        if self.sustain {
+            self.sustained_notes.push(hz);
+            return;
+        }
+        if self.sostenuto {
+            let tolerance = self.note_match_tolerance;
+            if self.sostenuto_notes.iter().any(|&held| (held - hz).abs() <= tolerance) {
+                self.sostenuto_released_notes.push(hz);
+                return;
+            }
+        }
+        let hz = self.take_held_note(hz);
+        let ignore_note_off = self.ignore_note_off;
+        match self.arpeggiator {
+            Some(ref mut arp) => {
+                arp.note_off(hz);
+                if arp.held_notes.is_empty() {
+                    if let Some(playing) = arp.playing.take() {
+                        if !ignore_note_off {
+                            self.instrument.note_off(playing);
+                        }
+                    }
+                }
+            },
+            None => if !ignore_note_off {
+                self.instrument.note_off(hz);
+            },
+        }
+    }
+
+    /// Remove the held note closest to the given `hz` if it lies within the
+    /// `note_match_tolerance`, returning its exact frequency.
+    ///
+    /// If no held note matches, the given `hz` is returned unchanged.
+    fn take_held_note(&mut self, hz: pitch::calc::Hz) -> pitch::calc::Hz {
+        let tolerance = self.note_match_tolerance;
+        let closest = self.held_notes.iter()
+            .map(|&held| (held - hz).abs())
+            .enumerate()
+            .filter(|&(_, distance)| distance <= tolerance)
+            .fold(None, |closest: Option<(usize, pitch::calc::Hz)>, (idx, distance)| {
+                match closest {
+                    Some((_, closest_distance)) if closest_distance <= distance => closest,
+                    _ => Some((idx, distance)),
+                }
+            });
+        match closest {
+            Some((idx, _)) => self.held_notes.remove(idx),
+            None => hz,
+        }
+    }
+
+    /// Begin playback of the given MIDI note number, where 69 is A4 (440hz).
+    ///
+    /// Note numbers outside of the MIDI range 0..127 are ignored.
+    #[inline]
+    pub fn note_on_midi(&mut self, note: u8, note_vel: NoteVelocity)
+        where M: instrument::Mode,
+    {
+        if let Some(hz) = midi_note_hz(note) {
+            self.note_on(pitch::Hz(hz), note_vel);
+        }
+    }
+
+    /// Stop playback of the given MIDI note number, where 69 is A4 (440hz).
+    ///
+    /// Note numbers outside of the MIDI range 0..127 are ignored.
+    #[inline]
+    pub fn note_off_midi(&mut self, note: u8)
+        where M: instrument::Mode,
+    {
+        if let Some(hz) = midi_note_hz(note) {
+            self.note_off(pitch::Hz(hz));
+        }
+    }
+
+    /// Bend the pitch of every voice by the given number of steps (semitones).
+    ///
+    /// The bend replaces any previous bend and may be negative.
+    #[inline]
+    pub fn pitch_bend(&mut self, steps: f32) {
+        self.pitch_bend = steps;
+    }
+
+    /// Press or release the sustain pedal.
+    ///
+    /// While the pedal is held, released notes continue to sound. Releasing the pedal stops every
+    /// note that was released while it was held.
+    #[inline]
+    pub fn sustain(&mut self, sustain: bool)
+        where M: instrument::Mode,
+    {
+        self.sustain = sustain;
+        if !sustain {
+            let sustained_notes = std::mem::replace(&mut self.sustained_notes, Vec::new());
+            for hz in sustained_notes {
+                self.release_note(hz);
+            }
+        }
+    }
+
+    /// Press or release the sostenuto pedal.
+    ///
+    /// Pressing the pedal captures the notes that are currently held. While the pedal is held,
+    /// only those notes continue to sound once released, while notes played after the pedal was
+    /// pressed are released as normal. Releasing the pedal stops every captured note that was
+    /// released while it was held.
+    pub fn sostenuto(&mut self, sostenuto: bool)
+        where M: instrument::Mode,
+    {
+        if sostenuto == self.sostenuto {
+            return;
+        }
+        self.sostenuto = sostenuto;
+        if sostenuto {
+            self.sostenuto_notes = self.held_notes.clone();
+        } else {
+            self.sostenuto_notes.clear();
+            let released_notes = std::mem::replace(&mut self.sostenuto_released_notes, Vec::new());
+            for hz in released_notes {
+                self.release_note(hz);
+            }
+        }
+    }
+
+    /// Drive the synth with a single raw MIDI message.
+    ///
+    /// Note On (with a velocity of `0` treated as Note Off), Note Off, Pitch Bend, Sustain (CC64),
+    /// Sostenuto (CC66), All Sound Off (CC120) and All Notes Off (CC123) messages are recognised
+    /// on every channel.
+    /// The pitch bend range is `MIDI_PITCH_BEND_RANGE` steps in either direction. All other
+    /// messages are ignored.
+    pub fn handle_midi(&mut self, msg: &[u8])
+        where M: instrument::Mode,
+    {
+        if msg.len() < 3 {
+            return;
+        }
+        match msg[0] & 0xF0 {
+            0x80 => self.note_off_midi(msg[1]),
+            0x90 => match msg[2] {
+                0 => self.note_off_midi(msg[1]),
+                vel => self.note_on_midi(msg[1], vel as NoteVelocity / 127.0),
+            },
+            0xB0 if msg[1] == 64 => self.sustain(msg[2] >= 64),
+            0xB0 if msg[1] == 66 => self.sostenuto(msg[2] >= 64),
+            0xB0 if msg[1] == 120 => self.panic(),
+            0xB0 if msg[1] == 123 => self.release_all(),
+            0xE0 => {
+                let value = ((msg[2] as u16 & 0x7F) << 7) | (msg[1] as u16 & 0x7F);
+                let perc = (value as f32 - 8192.0) / 8192.0;
+                self.pitch_bend(perc * MIDI_PITCH_BEND_RANGE);
+            },
+            _ => (),
+        }
+    }
+
+    /// Release every held note so that each fades out via the instrument's release rather than
+    /// being cut off. This is the behaviour of the MIDI "All Notes Off" message (CC123).
+    ///
+    /// Unlike `stop`, the oscillator state is left untouched and any release tails continue to
+    /// sound. Notes held by the sustain pedal continue to sound until the pedal is released.
+    pub fn release_all(&mut self)
+        where M: instrument::Mode,
+    {
+        let held_notes = self.held_notes.clone();
+        for hz in held_notes {
+            self.release_note(hz);
+        }
+    }
+
+    /// Stop playback and clear the current notes.
+    ///
+    /// Every voice is stopped and its playhead and oscillator phases are reset. If a `declick_ms`
+    /// is set, the output first fades out over that duration.
+    ///
+    /// There are three ways of silencing the synth:
+    ///
+    /// - `release_all` is a graceful stop, letting each note fade out via its release.
+    /// - `stop` is a hard stop, shortened to the `declick_ms` fade.
+    /// - `panic` is an immediate hard stop that ignores the `declick_ms`.
+    #[inline]
+    pub fn stop(&mut self)
+        where M: instrument::Mode,
+    {
+        self.clear_notes();
+        // Fade out any sounding notes before stopping if a declick is set.
+        if self.declick_ms.0 > 0.0 && self.instrument.is_active() {
+            if self.declick_elapsed_ms.is_none() {
+                self.declick_elapsed_ms = Some(0.0);
+            }
+            return;
+        }
+        self.stop_now();
+    }
+
+    /// Immediately stop playback and clear the current notes, ignoring any `declick_ms`.
+    ///
+    /// Every voice is freed and its playhead and oscillator phases are reset within the call.
+    /// This is the behaviour of the MIDI "All Sound Off" message (CC120). See `stop` for the
+    /// other ways of silencing the synth.
+    pub fn panic(&mut self)
+        where M: instrument::Mode,
+    {
+        self.clear_notes();
+        self.stop_now();
+    }
+
+    /// Forget every held, sustained and arpeggiated note.
+    fn clear_notes(&mut self) {
+        self.held_notes.clear();
+        self.sustained_notes.clear();
+        self.sostenuto_notes.clear();
+        self.sostenuto_released_notes.clear();
+        if let Some(ref mut arp) = self.arpeggiator {
+            arp.clear();
+        }
+    }
+
+    /// Immediately stop playback of all notes, ignoring the declick.
+    fn stop_now(&mut self)
+        where M: instrument::Mode,
+    {
+        self.instrument.stop();
+        self.declick_elapsed_ms = None;
+        for voice in &mut self.voices {
+            voice.loop_playhead = 0;
+            voice.loop_reversed = false;
+            voice.gain = 1.0;
+        }
+        self.reset_phases();
+    }
+
+    /// A reference to the synth's `Instrument`.
+    pub fn instrument(&self) -> &Instrument<M, NFG> {
+        &self.instrument
+    }
+
+    /// A mutable reference to the synth's `Instrument`.
+    ///
+    /// This is useful for adjusting instrument settings that the synth has no builder for. The
+    /// number of voices should be changed via `set_num_voices` instead, as the synth keeps state
+    /// for each voice.
+    ///
+    /// ```
+    /// extern crate synth;
+    ///
+    /// use synth::{Oscillator, Synth};
+    /// use synth::oscillator::waveform::Sine;
+    ///
+    /// fn main() {
+    ///     let mut synth = Synth::poly(()).oscillator(Oscillator::new(Sine, 1.0, 440.0, ()));
+    ///     synth.instrument_mut().detune = 0.25;
+    ///     assert_eq!(synth.instrument().detune, 0.25);
+    /// }
+    /// ```
+    pub fn instrument_mut(&mut self) -> &mut Instrument<M, NFG> {
+        &mut self.instrument
+    }
+
+    /// Map the `Instrument` to a new `Instrument` in place.
+    ///
+    /// This is useful for providing wrapper builder methods for the Synth.
+    #[inline]
+    pub fn map_instrument<Map, NewM, NewNFG>(self, map: Map) -> Synth<NewM, NewNFG, W, A, F, FW>
+        where Map: FnOnce(Instrument<M, NFG>) -> Instrument<NewM, NewNFG>,
+              NewNFG: NoteFreqGenerator,
+    {
+        let Synth {
+            oscillators,
+            voices,
+            duration_ms,
+            base_pitch,
+            volume,
+            spread,
+            instrument,
+            loop_points,
+            arpeggiator,
+            note_match_tolerance,
+            held_notes,
+            high_precision,
+            loop_mode,
+            one_shot,
+            key_pan,
+            dc_blocker,
+            tuning,
+            glide_quantized,
+            phase_spread,
+            random_start_phase,
+            declick_ms,
+            declick_elapsed_ms,
+            volume_smoothing_ms,
+            current_volume,
+            pitch_bend,
+            sustain,
+            sustained_notes,
+            stereo_detune,
+            loop_crossfade_ms,
+            oversampler,
+            seed,
+            pan_law,
+            sostenuto,
+            sostenuto_notes,
+            sostenuto_released_notes,
+            solo_oscillator,
+            freq_slew,
+            spread_shape,
+            output_enabled,
+            ignore_note_off,
+            glide_sequence,
+        } = self;
+
+        Synth {
+            oscillators: oscillators,
             voices: voices,
             volume: volume,
             spread: spread,
             duration_ms: duration_ms,
             base_pitch: base_pitch,
             loop_points: loop_points,
-            instrument: map(instrument)
+            instrument: map(instrument),
+            arpeggiator: arpeggiator,
+            note_match_tolerance: note_match_tolerance,
+            held_notes: held_notes,
+            high_precision: high_precision,
+            loop_mode: loop_mode,
+            one_shot: one_shot,
+            key_pan: key_pan,
+            dc_blocker: dc_blocker,
+            tuning: tuning,
+            glide_quantized: glide_quantized,
+            phase_spread: phase_spread,
+            random_start_phase: random_start_phase,
+            declick_ms: declick_ms,
+            declick_elapsed_ms: declick_elapsed_ms,
+            volume_smoothing_ms: volume_smoothing_ms,
+            current_volume: current_volume,
+            pitch_bend: pitch_bend,
+            sustain: sustain,
+            sustained_notes: sustained_notes,
+            stereo_detune: stereo_detune,
+            loop_crossfade_ms: loop_crossfade_ms,
+            oversampler: oversampler,
+            seed: seed,
+            pan_law: pan_law,
+            sostenuto: sostenuto,
+            sostenuto_notes: sostenuto_notes,
+            sostenuto_released_notes: sostenuto_released_notes,
+            solo_oscillator: solo_oscillator,
+            freq_slew: freq_slew,
+            spread_shape: spread_shape,
+            output_enabled: output_enabled,
+            ignore_note_off: ignore_note_off,
+            glide_sequence: glide_sequence,
+        }
+    }
+
+    /// Check the synth for any misconfiguration that would produce silent or degenerate output.
+    pub fn validate(&self) -> Result<(), SynthError> {
+        if self.oscillators.is_empty() {
+            return Err(SynthError::NoOscillators);
+        }
+        if self.voices.is_empty() {
+            return Err(SynthError::NoVoices);
+        }
+        if let Some((start, end)) = self.loop_points {
+            let is_perc = |perc: f64| perc >= 0.0 && perc <= 1.0;
+            if !is_perc(start) || !is_perc(end) || start > end {
+                return Err(SynthError::InvalidLoopPoints(start, end));
+            }
         }
+        Ok(())
+    }
+
+    /// Produces the same `Iterator` as `frames`, but only once the synth's configuration has been
+    /// checked with `validate`.
+    ///
+    /// This is useful for diagnosing a synth that is unexpectedly silent.
+    pub fn try_frames<FRM>(&mut self, sample_hz: f64)
+        -> Result<Frames<FRM, NFG::NoteFreq, W, A, F, FW>, SynthError>
+        where FRM: Frame,
+              <FRM::Sample as Sample>::Float: sample::FromSample<f32>,
+              <FRM::Sample as Sample>::Signed: sample::FromSample<f32>,
+    {
+        try!(self.validate());
+        Ok(self.frames(sample_hz))
     }
 
     /// Produces an `Iterator` that endlessly yields new `Frame`s
@@ -381,22 +1632,52 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
               <FRM::Sample as Sample>::Float: sample::FromSample<f32>,
               <FRM::Sample as Sample>::Signed: sample::FromSample<f32>,
     {
+        // When oversampling, frames are rendered at a multiple of the output rate.
+        let sample_hz = self.render_hz(sample_hz);
+
         let Synth {
             ref mut oscillators,
             ref mut voices,
             ref mut instrument,
+            ref mut dc_blocker,
+            ref mut declick_elapsed_ms,
+            ref mut current_volume,
+            ref mut glide_sequence,
+            declick_ms,
+            volume_smoothing_ms,
             duration_ms,
             base_pitch,
             loop_points,
+            loop_mode,
             spread,
             volume,
+            high_precision,
+            one_shot,
+            key_pan,
+            glide_quantized,
+            phase_spread,
+            random_start_phase,
+            pitch_bend,
+            stereo_detune,
+            loop_crossfade_ms,
+            ref mut oversampler,
+            pan_law,
+            ref held_notes,
+            solo_oscillator,
+            freq_slew,
+            spread_shape,
+            output_enabled,
+            ..
         } = *self;
 
         // Convert the duration from milliseconds to samples.
-        let duration = duration_ms.samples(sample_hz);
+        let duration = duration_samples(duration_ms, sample_hz);
 
         // Convert the loop points from duration percentages to samples.
-        let loop_points_samples = loop_points.map(|(start_perc, end_perc)| {
+        let loop_points = if one_shot { None } else { loop_points };
+        let loop_points_samples = loop_points.map(|(start, end)| {
+            // The loop points field may have been set directly, so they are normalized here too.
+            let (start_perc, end_perc) = normalize_loop_points(start, end);
             ((start_perc * duration as f64).round() as time::calc::Samples,
              (end_perc * duration as f64).round() as time::calc::Samples)
         });
@@ -406,16 +1687,42 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
             oscillators: oscillators,
             voices: voices,
             duration: duration,
-            base_pitch: base_pitch,
+            // The base pitch field may have been set directly, so it is clamped here too.
+            base_pitch: base_pitch.max(MIN_BASE_PITCH),
             loop_points: loop_points_samples,
+            loop_mode: loop_mode,
             instrument_frames: instrument.frames(sample_hz),
             spread: spread,
             volume: volume,
+            high_precision: high_precision,
+            key_pan: key_pan,
+            dc_blocker: dc_blocker.as_mut(),
+            glide_quantized: glide_quantized,
+            phase_spread: phase_spread,
+            random_start_phase: random_start_phase,
+            declick_ms: declick_ms.0,
+            current_volume: current_volume,
+            volume_smoothing_ms: volume_smoothing_ms.0,
+            declick_elapsed_ms: declick_elapsed_ms.as_mut(),
+            pitch_bend: pitch_bend,
+            stereo_detune: stereo_detune,
+            loop_crossfade: loop_crossfade_ms.samples(sample_hz),
+            oversampler: oversampler.as_mut(),
+            pan_law: pan_law,
+            held_notes: held_notes,
+            solo_oscillator: solo_oscillator,
+            freq_slew: freq_slew,
+            spread_shape: spread_shape,
+            output_enabled: output_enabled,
+            glide_sequence: glide_sequence.as_mut(),
             frame: std::marker::PhantomData,
         }
     }
 
     /// Additively fill the given slice of `Frame`s with the `Synth::frames` method.
+    ///
+    /// The synth's output is added to the existing contents of `output`, allowing many synths to
+    /// be mixed into the same buffer. Use `fill_slice_replace` to overwrite the contents instead.
     pub fn fill_slice<FRM>(&mut self, output: &mut [FRM], sample_hz: f64)
         where FRM: sample::Frame,
               <FRM::Sample as Sample>::Float: sample::FromSample<f32>,
@@ -427,96 +1734,893 @@ impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
               F: oscillator::Frequency,
               FW: oscillator::FreqWarp,
     {
-        let mut frames = self.frames::<FRM>(sample_hz);
-        sample::slice::map_in_place(output, |f| {
-            f.zip_map(frames.next_frame(), |a, b| a.add_amp(b.to_sample()))
-        });
+        // Fill the output in chunks, stepping the arpeggiator (if there is one) between chunks.
+        let mut output = output;
+        while !output.is_empty() {
+            let len = self.step_arpeggiator(sample_hz, output.len());
+            let (chunk, rest) = { output }.split_at_mut(len);
+            let mut frames = self.frames::<FRM>(sample_hz);
+            sample::slice::map_in_place(chunk, |f| {
+                f.zip_map(frames.next_frame(), |a, b| a.add_amp(b.to_sample()))
+            });
+            output = rest;
+            self.free_finished_one_shots(sample_hz);
+            self.finish_declick();
+        }
     }
 
-}
-
-
-impl<'a, FRM, NF, W, A, F, FW> Frames<'a, FRM, NF, W, A, F, FW>
-    where FRM: Frame,
-          <FRM::Sample as Sample>::Float: sample::FromSample<f32>,
-          <FRM::Sample as Sample>::Signed: sample::FromSample<f32>,
-          NF: NoteFreq,
-          W: Waveform,
-          A: Amplitude,
-          F: Frequency,
-          FW: FreqWarp,
-{
-    /// Yields the next frame
-    #[inline]
-    pub fn next_frame(&mut self) -> FRM {
-        let Frames {
-            ref mut oscillators,
-            ref mut instrument_frames,
-            ref mut voices,
-            sample_hz,
-            loop_points,
-            duration,
-            base_pitch,
-            volume,
-            spread,
-            ..
-        } = *self;
-
-        // Count the number of voices currently playing a note.
-        let num_active_voices = instrument_frames.num_active_voices();
-        let frame_per_voice = instrument_frames.next_frame_per_voice();
-        let iter = voices.iter_mut()
-            .zip(frame_per_voice)
-            .filter_map(|(v, amp_hz)| amp_hz.map(|amp_hz| (v, amp_hz)))
-            .enumerate();
-        let should_spread = FRM::n_channels() == 2 && spread > 0.0;
-
-        let mut frame = FRM::equilibrium();
-        for (i, (voice, (amp, hz))) in iter {
-            let Voice { ref mut loop_playhead, ref mut oscillator_states } = *voice;
-            if *loop_playhead < duration {
-                let freq_multi = hz as f64 / base_pitch as f64;
-                let playhead_perc = *loop_playhead as f64 / duration as f64;
+    /// Fill the given slice of `Frame`s with the `Synth::frames` method, overwriting its contents.
+    ///
+    /// Unlike `fill_slice`, any existing contents of `output` are discarded, leaving silence
+    /// wherever the synth is silent.
+    pub fn fill_slice_replace<FRM>(&mut self, output: &mut [FRM], sample_hz: f64)
+        where FRM: sample::Frame,
+              <FRM::Sample as Sample>::Float: sample::FromSample<f32>,
+              <FRM::Sample as Sample>::Signed: sample::FromSample<f32>,
+              M: instrument::Mode,
+              NFG: instrument::NoteFreqGenerator,
+              W: oscillator::Waveform,
+              A: oscillator::Amplitude,
+              F: oscillator::Frequency,
+              FW: oscillator::FreqWarp,
+    {
+        sample::slice::equilibrium(output);
+        self.fill_slice(output, sample_hz);
+    }
 
-                let osc_iter = oscillators.iter_mut().zip(oscillator_states.0.iter_mut());
-                let wave = osc_iter.fold(0.0, |amp, (osc, state)| {
-                    amp + osc.next_frame_amp(sample_hz, playhead_perc, freq_multi, state)
-                }) * amp;
+    /// Additively fill the given slice of interleaved samples with the `Synth::frames` method.
+    ///
+    /// This behaves like `fill_slice` for output whose channel count is only known at runtime.
+    /// Returns an error without touching the output if `channels` is unsupported or if the length
+    /// of `output` is not a multiple of `channels`.
+    pub fn fill_interleaved(&mut self, output: &mut [f32], channels: usize, sample_hz: f64)
+        -> Result<(), SynthError>
+        where M: instrument::Mode,
+              NFG: instrument::NoteFreqGenerator,
+              W: oscillator::Waveform,
+              A: oscillator::Amplitude,
+              F: oscillator::Frequency,
+              FW: oscillator::FreqWarp,
+    {
+        if channels == 0 || channels > MAX_INTERLEAVED_CHANNELS {
+            return Err(SynthError::UnsupportedChannels(channels));
+        }
+        if output.len() % channels != 0 {
+            return Err(SynthError::InvalidInterleavedLength(output.len(), channels));
+        }
+        match channels {
+            1 => self.fill_interleaved_frames::<[f32; 1]>(output, sample_hz),
+            2 => self.fill_interleaved_frames::<[f32; 2]>(output, sample_hz),
+            3 => self.fill_interleaved_frames::<[f32; 3]>(output, sample_hz),
+            4 => self.fill_interleaved_frames::<[f32; 4]>(output, sample_hz),
+            5 => self.fill_interleaved_frames::<[f32; 5]>(output, sample_hz),
+            6 => self.fill_interleaved_frames::<[f32; 6]>(output, sample_hz),
+            7 => self.fill_interleaved_frames::<[f32; 7]>(output, sample_hz),
+            _ => self.fill_interleaved_frames::<[f32; 8]>(output, sample_hz),
+        }
+        Ok(())
+    }
 
-                // If we have a stereo stream, calculate the spread.
-                frame = if should_spread {
-                    let pan = match num_active_voices {
-                        1 => 0.0,
-                        _ => ((i as f32 / (num_active_voices-1) as f32) - 0.5) * (spread * 2.0),
-                    };
-                    let panned = stereo::pan(pan);
+    /// Additively fill the given interleaved samples via `fill_slice`, rendering a block of
+    /// frames of the given type at a time.
+    fn fill_interleaved_frames<FRM>(&mut self, output: &mut [f32], sample_hz: f64)
+        where FRM: sample::Frame<Sample=f32>,
+              M: instrument::Mode,
+              NFG: instrument::NoteFreqGenerator,
+              W: oscillator::Waveform,
+              A: oscillator::Amplitude,
+              F: oscillator::Frequency,
+              FW: oscillator::FreqWarp,
+    {
+        const BLOCK_FRAMES: usize = 64;
+        let n_channels = FRM::n_channels();
+        for output_block in output.chunks_mut(BLOCK_FRAMES * n_channels) {
+            let mut frames = [FRM::equilibrium(); BLOCK_FRAMES];
+            let frames = &mut frames[..output_block.len() / n_channels];
+            self.fill_slice(frames, sample_hz);
+            for (output_frame, frame) in output_block.chunks_mut(n_channels).zip(frames.iter()) {
+                for (idx, sample) in output_frame.iter_mut().enumerate() {
+                    *sample += *frame.channel(idx).unwrap();
+                }
+            }
+        }
+    }
 
-                    // Multiply the pan result with the amp_per_channel to get the voice's amp.
-                    FRM::from_fn(|idx| {
-                        let amp = wave * panned[idx];
-                        frame.channel(idx).unwrap().add_amp(amp.to_sample())
-                    })
-                } else {
-                    frame.map(|s| s.add_amp(wave.to_sample()))
-                };
+    /// The rate at which frames are rendered for the given output `sample_hz`.
+    ///
+    /// This is a multiple of the `sample_hz` when oversampling.
+    fn render_hz(&self, sample_hz: f64) -> f64 {
+        match self.oversampler {
+            Some(ref oversampler) => sample_hz * oversampler.factor as f64,
+            None => sample_hz,
+        }
+    }
 
-                // Iterate the loop_playhead. If the loop_playhead passes the loop_end, reset the
-                // playhead to the start.
-                *loop_playhead += 1;
-                if let Some((loop_start, loop_end)) = loop_points {
-                    if *loop_playhead >= loop_end {
-                        *loop_playhead = (*loop_playhead - loop_end) + loop_start;
-                    }
-                }
+    /// Stop the synth if the fade of a declicked stop has completed.
+    fn finish_declick(&mut self)
+        where M: instrument::Mode,
+    {
+        if let Some(elapsed_ms) = self.declick_elapsed_ms {
+            if elapsed_ms >= self.declick_ms.0 {
+                self.stop_now();
             }
         }
+    }
 
-        frame.scale_amp(volume.to_sample())
+    /// If in one-shot mode and every sounding voice has reached the end of the duration, stop the
+    /// instrument so that the voices are freed.
+    ///
+    /// The voices are freed immediately, bypassing any `declick_ms`, as the one-shot has already
+    /// played to its end.
+    fn free_finished_one_shots(&mut self, sample_hz: f64)
+        where M: instrument::Mode,
+    {
+        if !self.one_shot || !self.instrument.is_active() {
+            return;
+        }
+        // Voices that are not sounding have their playhead rewound to 0 by `Frames::next_frame`.
+        let duration = duration_samples(self.duration_ms, self.render_hz(sample_hz));
+        let all_finished = self.voices.iter()
+            .all(|voice| voice.loop_playhead == 0 || voice.loop_playhead >= duration);
+        if all_finished {
+            self.clear_notes();
+            self.stop_now();
+        }
     }
-}
 
-impl<'a, FRM, NF, W, A, F, FW> Iterator for Frames<'a, FRM, NF, W, A, F, FW>
-    where FRM: Frame,
+    /// Render a single note to an owned buffer of frames.
+    ///
+    /// The note is held for the given `duration` before being released, after which frames
+    /// continue to be collected until the synth is no longer active. The returned buffer therefore
+    /// contains the full release tail. Any notes that were already playing must also finish
+    /// before rendering stops.
+    ///
+    /// The tail is capped at `tail_len_samples` (plus one block of `TAIL_BLOCK_FRAMES` to allow the
+    /// instrument to free its voices) so that rendering always ends, even if the note is never
+    /// released, e.g. while the sustain pedal is held.
+    pub fn render_note<FRM, T, D>(&mut self,
+                                  note_hz: T,
+                                  note_vel: NoteVelocity,
+                                  duration: D,
+                                  sample_hz: f64) -> Vec<FRM>
+        where FRM: sample::Frame,
+              <FRM::Sample as Sample>::Float: sample::FromSample<f32>,
+              <FRM::Sample as Sample>::Signed: sample::FromSample<f32>,
+              T: Into<pitch::Hz>,
+              D: Into<time::Ms>,
+              M: instrument::Mode,
+              NFG: instrument::NoteFreqGenerator,
+              W: oscillator::Waveform,
+              A: oscillator::Amplitude,
+              F: oscillator::Frequency,
+              FW: oscillator::FreqWarp,
+    {
+        let hz = note_hz.into().hz();
+        let num_held_frames = std::cmp::max(0, duration.into().samples(sample_hz)) as usize;
+        let mut output = vec![FRM::equilibrium(); num_held_frames];
+
+        self.note_on(pitch::Hz(hz), note_vel);
+        self.fill_slice(&mut output, sample_hz);
+        self.note_off(pitch::Hz(hz));
+
+        // Collect the release tail a block at a time until the synth falls silent or the tail
+        // reaches its maximum length.
+        let max_tail_frames = self.tail_len_samples(sample_hz) + TAIL_BLOCK_FRAMES;
+        let mut tail_frames = 0;
+        while self.is_active() && tail_frames < max_tail_frames {
+            let len = std::cmp::min(TAIL_BLOCK_FRAMES, max_tail_frames - tail_frames);
+            let start = output.len();
+            output.resize(start + len, FRM::equilibrium());
+            self.fill_slice(&mut output[start..], sample_hz);
+            tail_frames += len;
+
+            // Drop the silence rendered after the synth fell silent part way through the block.
+            if !self.is_active() {
+                while output.len() > start && output[output.len() - 1] == FRM::equilibrium() {
+                    output.pop();
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Trigger the arpeggiator's next note if a step is due and return the number of frames that
+    /// may be rendered (up to `max_frames`) before the next step.
+    fn step_arpeggiator(&mut self, sample_hz: f64, max_frames: usize) -> usize
+        where M: instrument::Mode,
+    {
+        let Synth { ref mut arpeggiator, ref mut instrument, .. } = *self;
+        let arp = match *arpeggiator {
+            Some(ref mut arp) => arp,
+            None => return max_frames,
+        };
+        if arp.samples_until_step <= 0 {
+            if let Some(hz) = arp.playing.take() {
+                instrument.note_off(hz);
+            }
+            if let Some((hz, vel)) = arp.next_note() {
+                instrument.note_on(hz, vel);
+                arp.playing = Some(hz);
+            }
+            arp.samples_until_step = std::cmp::max(1, arp.rate.samples(sample_hz));
+        }
+        let len = std::cmp::min(max_frames as time::calc::Samples, arp.samples_until_step);
+        arp.samples_until_step -= len;
+        len as usize
+    }
+
+}
+
+
+impl DcBlocker {
+
+    /// Constructor for a new DcBlocker.
+    pub fn new() -> Self {
+        DcBlocker {
+            last_input: Vec::new(),
+            last_output: Vec::new(),
+        }
+    }
+
+    /// The filter coefficient that places the cutoff at `DC_BLOCKER_CUTOFF_HZ`.
+    pub fn coefficient(sample_hz: f64) -> f32 {
+        (1.0 - 2.0 * std::f64::consts::PI * DC_BLOCKER_CUTOFF_HZ / sample_hz) as f32
+    }
+
+    /// Filter the next sample of the given channel.
+    #[inline]
+    pub fn next_sample(&mut self, channel: usize, input: f32, coefficient: f32) -> f32 {
+        if channel >= self.last_input.len() {
+            self.last_input.resize(channel + 1, 0.0);
+            self.last_output.resize(channel + 1, 0.0);
+        }
+        let output = input - self.last_input[channel] + coefficient * self.last_output[channel];
+        self.last_input[channel] = input;
+        self.last_output[channel] = output;
+        output
+    }
+
+}
+
+
+impl Oversampler {
+
+    /// Constructor for a new Oversampler rendering `factor` frames for every output frame.
+    pub fn new(factor: u8) -> Self {
+        let factor = std::cmp::max(1, factor);
+        Oversampler {
+            factor: factor,
+            taps: Oversampler::decimation_taps(factor),
+            history: Vec::new(),
+        }
+    }
+
+    /// The number of taps in the decimation filter.
+    pub fn num_taps(&self) -> usize {
+        self.taps.len()
+    }
+
+    /// The taps of a blackman-windowed sinc low-pass filter with its cutoff at the output nyquist
+    /// of a synth oversampled by the given `factor`.
+    pub fn decimation_taps(factor: u8) -> Vec<f32> {
+        use std::f64::consts::PI;
+        let n = OVERSAMPLER_TAPS_PER_FACTOR * factor as usize;
+        let cutoff = 0.5 / factor as f64;
+        let center = (n - 1) as f64 / 2.0;
+        let taps: Vec<f64> = (0..n).map(|i| {
+            let x = i as f64 - center;
+            let sinc = if x == 0.0 { 1.0 } else { (2.0 * PI * cutoff * x).sin() / (2.0 * PI * cutoff * x) };
+            let w = 2.0 * PI * i as f64 / (n - 1) as f64;
+            sinc * (0.42 - 0.5 * w.cos() + 0.08 * (2.0 * w).cos())
+        }).collect();
+        // Normalise for unity gain at DC.
+        let sum: f64 = taps.iter().fold(0.0, |sum, &t| sum + t);
+        taps.iter().map(|&t| (t / sum) as f32).collect()
+    }
+
+    /// Push the next oversampled input sample of the given channel.
+    #[inline]
+    pub fn push_sample(&mut self, channel: usize, input: f32) {
+        let num_taps = self.num_taps();
+        if channel >= self.history.len() {
+            self.history.resize(channel + 1, VecDeque::with_capacity(num_taps));
+        }
+        let history = &mut self.history[channel];
+        if history.len() >= num_taps {
+            history.pop_front();
+        }
+        history.push_back(input);
+    }
+
+    /// Filter the history of the given channel with the decimation filter's `taps`.
+    #[inline]
+    pub fn filtered(&self, channel: usize) -> f32 {
+        match self.history.get(channel) {
+            Some(history) => history.iter().rev()
+                .zip(self.taps.iter())
+                .fold(0.0, |sum, (&x, &t)| sum + x * t),
+            None => 0.0,
+        }
+    }
+
+}
+
+
+impl PanLaw {
+
+    /// The gain of the given channel for a signal panned across `n_channels` channels.
+    ///
+    /// A `pan` of `-1.0` lies on the first channel and `1.0` on the last, with the signal shared
+    /// between the two channels adjacent to any position in between.
+    pub fn channel_gain(&self, pan: f32, n_channels: usize, channel: usize) -> f32 {
+        if n_channels < 2 {
+            return 1.0;
+        }
+        let position = (pan.max(-1.0).min(1.0) + 1.0) * 0.5 * (n_channels - 1) as f32;
+        let distance = (position - channel as f32).abs();
+        if distance >= 1.0 {
+            return 0.0;
+        }
+        match *self {
+            PanLaw::ConstantPower => (distance * std::f32::consts::PI * 0.5).cos(),
+            PanLaw::ConstantGain => 1.0 - distance,
+        }
+    }
+
+}
+
+impl GlideSequence {
+
+    /// Constructor for a new GlideSequence that begins from its first step.
+    pub fn new(steps: Vec<(pitch::calc::Hz, f64)>) -> Self {
+        GlideSequence {
+            steps: steps,
+            elapsed_ms: 0.0,
+        }
+    }
+
+    /// The frequency of a voice playing `note_hz` at the sequence's current `elapsed_ms`.
+    ///
+    /// Glides are interpolated in pitch rather than frequency so that each semitone takes the
+    /// same amount of time.
+    pub fn hz(&self, note_hz: pitch::calc::Hz) -> pitch::calc::Hz {
+        let mut from_hz = note_hz;
+        let mut start_ms = 0.0;
+        for &(to_hz, glide_ms) in &self.steps {
+            let end_ms = start_ms + glide_ms.max(0.0);
+            if self.elapsed_ms < end_ms {
+                let perc = ((self.elapsed_ms - start_ms) / (end_ms - start_ms)) as f32;
+                return from_hz * (to_hz / from_hz).powf(perc);
+            }
+            from_hz = to_hz;
+            start_ms = end_ms;
+        }
+        from_hz
+    }
+
+}
+
+impl SpreadShape {
+
+    /// The pan position in `-1.0..1.0` of the active voice at `idx` for the given `spread`.
+    pub fn pan(&self, idx: usize, num_active_voices: usize, spread: f32) -> f32 {
+        if num_active_voices < 2 {
+            return 0.0;
+        }
+        match *self {
+            SpreadShape::Linear =>
+                ((idx as f32 / (num_active_voices - 1) as f32) - 0.5) * (spread * 2.0),
+            SpreadShape::Centered => {
+                let step = ((idx + 1) / 2) as f32 / (num_active_voices / 2) as f32;
+                let sign = if idx % 2 == 1 { -1.0 } else { 1.0 };
+                sign * step * spread
+            },
+            SpreadShape::Alternating => if idx % 2 == 0 { -spread } else { spread },
+        }
+    }
+
+}
+
+impl std::fmt::Display for SynthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SynthError::NoOscillators => write!(f, "the synth has no oscillators"),
+            SynthError::NoVoices => write!(f, "the synth has no voices"),
+            SynthError::InvalidLoopPoints(start, end) =>
+                write!(f, "invalid loop points {} to {} (expected 0.0 <= start <= end <= 1.0)",
+                       start, end),
+            SynthError::UnsupportedChannels(channels) =>
+                write!(f, "unsupported number of channels {} (expected 1 to {})",
+                       channels, MAX_INTERLEAVED_CHANNELS),
+            SynthError::InvalidInterleavedLength(len, channels) =>
+                write!(f, "interleaved length {} is not a multiple of {} channels", len, channels),
+        }
+    }
+}
+
+impl std::error::Error for SynthError {
+    fn description(&self) -> &str {
+        match *self {
+            SynthError::NoOscillators => "the synth has no oscillators",
+            SynthError::NoVoices => "the synth has no voices",
+            SynthError::InvalidLoopPoints(_, _) => "invalid loop points",
+            SynthError::UnsupportedChannels(_) => "unsupported number of channels",
+            SynthError::InvalidInterleavedLength(_, _) => "invalid interleaved length",
+        }
+    }
+}
+
+
+/// Convert a MIDI note number to its equal-tempered frequency where 69 is A4 (440hz).
+///
+/// Returns `None` for note numbers outside of the MIDI range 0..127.
+fn midi_note_hz(note: u8) -> Option<pitch::calc::Hz> {
+    if note > 127 {
+        return None;
+    }
+    Some(440.0 * 2.0f32.powf((note as f32 - 69.0) / 12.0))
+}
+
+/// Clamp the given loop points to the range `0.0..1.0`, swapping them if `start` lies after `end`.
+fn normalize_loop_points(start: LoopStartPerc, end: LoopEndPerc) -> (LoopStartPerc, LoopEndPerc) {
+    let clamp = |perc: f64| perc.max(0.0).min(1.0);
+    let (start, end) = (clamp(start), clamp(end));
+    if start > end { (end, start) } else { (start, end) }
+}
+
+/// Convert the duration from milliseconds to samples. The duration divides the playhead, so it
+/// must be at least one sample long.
+fn duration_samples(duration_ms: time::Ms, sample_hz: f64) -> time::calc::Samples {
+    std::cmp::max(1, duration_ms.samples(sample_hz))
+}
+
+/// The phase at which an oscillator of the voice at the given index begins.
+fn voice_start_phase(phase_spread: f32,
+                     random_start_phase: bool,
+                     voice_idx: usize,
+                     num_voices: usize) -> f64
+{
+    let phase = phase_spread as f64 * voice_idx as f64 / std::cmp::max(1, num_voices) as f64;
+    if random_start_phase {
+        phase + ::rand::random::<f64>()
+    } else {
+        phase
+    }
+}
+
+/// The seed of the random number generator for the given oscillator of the given voice.
+fn voice_rng_seed(seed: u64, voice_idx: usize, osc_idx: usize) -> u64 {
+    seed ^ (voice_idx as u64).wrapping_mul(0x9E3779B97F4A7C15)
+        ^ (osc_idx as u64).wrapping_mul(0xC2B2AE3D27D4EB4F)
+}
+
+/// Sum the next amplitude of each unmuted oscillator, scaled by the voice's `amp`.
+///
+/// Muted oscillators are skipped entirely, leaving their phase untouched. If an oscillator is
+/// soloed, every other oscillator is skipped instead.
+///
+/// Returns the sums for the left and right channels, which only differ for oscillators in
+/// `quadrature`.
+fn sum_oscillators<W, A, F, FW>(oscillators: &mut [Oscillator<W, A, F, FW>],
+                                states: &mut [oscillator::State],
+                                sample_hz: f64,
+                                playhead_perc: f64,
+                                freq_multi: f64,
+                                amp: f32,
+                                high_precision: bool,
+                                solo_oscillator: Option<usize>) -> (f32, f32)
+    where W: Waveform,
+          A: Amplitude,
+          F: Frequency,
+          FW: FreqWarp,
+{
+    sum_oscillator_states(oscillators, states.iter_mut(), sample_hz, playhead_perc, freq_multi,
+                          amp, high_precision, solo_oscillator)
+}
+
+/// Sum the next amplitude of each oscillator exactly as `sum_oscillators` does, but step a
+/// scratch copy of each oscillator's state so that the given `states` are left unchanged.
+///
+/// The copies live on the stack, so no allocation is required.
+fn preview_oscillators<W, A, F, FW>(oscillators: &mut [Oscillator<W, A, F, FW>],
+                                    states: &[oscillator::State],
+                                    sample_hz: f64,
+                                    playhead_perc: f64,
+                                    freq_multi: f64,
+                                    amp: f32,
+                                    high_precision: bool,
+                                    solo_oscillator: Option<usize>) -> (f32, f32)
+    where W: Waveform,
+          A: Amplitude,
+          F: Frequency,
+          FW: FreqWarp,
+{
+    sum_oscillator_states(oscillators, states.iter().cloned(), sample_hz, playhead_perc,
+                          freq_multi, amp, high_precision, solo_oscillator)
+}
+
+/// The shared implementation of `sum_oscillators` and `preview_oscillators`, generic over whether
+/// each state is borrowed or a scratch copy.
+fn sum_oscillator_states<W, A, F, FW, I, S>(oscillators: &mut [Oscillator<W, A, F, FW>],
+                                            states: I,
+                                            sample_hz: f64,
+                                            playhead_perc: f64,
+                                            freq_multi: f64,
+                                            amp: f32,
+                                            high_precision: bool,
+                                            solo_oscillator: Option<usize>) -> (f32, f32)
+    where W: Waveform,
+          A: Amplitude,
+          F: Frequency,
+          FW: FreqWarp,
+          I: Iterator<Item=S>,
+          S: std::borrow::BorrowMut<oscillator::State>,
+{
+    let osc_iter = oscillators.iter_mut()
+        .zip(states)
+        .enumerate()
+        .filter(|&(i, (ref osc, _))| match solo_oscillator {
+            Some(solo_idx) => i == solo_idx,
+            None => !osc.is_muted,
+        })
+        .map(|(_, osc_and_state)| osc_and_state);
+    if high_precision {
+        let (l, r) = osc_iter.fold((0.0f64, 0.0f64), |(l, r), (osc, mut state)| {
+            let (amp_l, amp_r) = osc.next_frame_amp_stereo(sample_hz, playhead_perc, freq_multi,
+                                                           state.borrow_mut());
+            (l + amp_l as f64, r + amp_r as f64)
+        });
+        ((l * amp as f64) as f32, (r * amp as f64) as f32)
+    } else {
+        let (l, r) = osc_iter.fold((0.0, 0.0), |(l, r), (osc, mut state)| {
+            let (amp_l, amp_r) = osc.next_frame_amp_stereo(sample_hz, playhead_perc, freq_multi,
+                                                           state.borrow_mut());
+            (l + amp_l, r + amp_r)
+        });
+        (l * amp, r * amp)
+    }
+}
+
+/// Whether the given voice frequency belongs to one of the held notes.
+///
+/// A voice's frequency may be detuned or gliding away from its note, so the voice is matched to any
+/// held note within half a semitone.
+fn is_note_held(held_notes: &[pitch::calc::Hz], hz: pitch::calc::Hz) -> bool {
+    held_notes.iter().any(|&held| (12.0 * (hz / held).log2()).abs() < 0.5)
+}
+
+/// Round the given frequency to that of the nearest equal-tempered semitone.
+fn nearest_semitone_hz(hz: pitch::calc::Hz) -> pitch::calc::Hz {
+    let step = (12.0 * (hz / 440.0).log2()).round();
+    440.0 * 2.0f32.powf(step / 12.0)
+}
+
+
+impl<'a, FRM, NF, W, A, F, FW> Frames<'a, FRM, NF, W, A, F, FW>
+    where FRM: Frame,
+          <FRM::Sample as Sample>::Float: sample::FromSample<f32>,
+          <FRM::Sample as Sample>::Signed: sample::FromSample<f32>,
+          NF: NoteFreq,
+          W: Waveform,
+          A: Amplitude,
+          F: Frequency,
+          FW: FreqWarp,
+{
+    /// Yields the next frame
+    #[inline]
+    pub fn next_frame(&mut self) -> FRM {
+        let frame = self.oversampled_frame();
+        if self.output_enabled { frame } else { FRM::equilibrium() }
+    }
+
+    /// Renders the next frame at the output rate, decimating the oversampled frames if necessary.
+    #[inline]
+    fn oversampled_frame(&mut self) -> FRM {
+        let factor = match self.oversampler {
+            Some(ref oversampler) => oversampler.factor,
+            None => return self.render_frame(),
+        };
+
+        // Render `factor` frames into the oversampler before decimating them to a single frame.
+        for _ in 0..factor {
+            let frame = self.render_frame();
+            if let Some(ref mut oversampler) = self.oversampler {
+                for idx in 0..FRM::n_channels() {
+                    let input = frame.channel(idx).unwrap().to_float_sample().to_sample::<f32>();
+                    oversampler.push_sample(idx, input);
+                }
+            }
+        }
+        let oversampler = self.oversampler.as_ref().unwrap();
+        FRM::from_fn(|idx| {
+            let output = oversampler.filtered(idx);
+            <FRM::Sample as Sample>::equilibrium().add_amp(output.to_sample())
+        })
+    }
+
+    /// Renders the next frame at the synth's rendering rate (a multiple of the output rate when
+    /// oversampling).
+    #[inline]
+    fn render_frame(&mut self) -> FRM {
+        let Frames {
+            ref mut oscillators,
+            ref mut instrument_frames,
+            ref mut voices,
+            ref mut dc_blocker,
+            ref mut declick_elapsed_ms,
+            ref mut current_volume,
+            ref mut glide_sequence,
+            declick_ms,
+            volume_smoothing_ms,
+            sample_hz,
+            loop_points,
+            loop_mode,
+            duration,
+            base_pitch,
+            volume,
+            spread,
+            high_precision,
+            key_pan,
+            glide_quantized,
+            phase_spread,
+            random_start_phase,
+            pitch_bend,
+            stereo_detune,
+            loop_crossfade,
+            pan_law,
+            held_notes,
+            solo_oscillator,
+            freq_slew,
+            spread_shape,
+            ..
+        } = *self;
+
+        // Count the number of voices currently playing a note.
+        let num_active_voices = instrument_frames.num_active_voices();
+        let frame_per_voice = instrument_frames.next_frame_per_voice();
+        let num_voices = voices.len();
+        let n_channels = FRM::n_channels();
+        let should_spread = n_channels >= 2 && (spread > 0.0 || key_pan != 0.0);
+        let should_detune = n_channels == 2 && stereo_detune != 0.0;
+        let should_quadrature = n_channels == 2 && oscillators.iter().any(|osc| osc.quadrature);
+
+        let mut frame = FRM::equilibrium();
+        // The index of the voice among those that are sounding.
+        let mut i = 0;
+        for ((voice_idx, voice), amp_hz) in voices.iter_mut().enumerate().zip(frame_per_voice) {
+            let (amp, hz) = match amp_hz {
+                Some(amp_hz) => amp_hz,
+                // Rewind the playhead and phases of voices that are not sounding so that the next
+                // note played by the voice starts from the beginning.
+                // Voices are only reset once upon becoming idle.
+                None => {
+                    if voice.loop_playhead != 0 || voice.loop_reversed {
+                        voice.loop_playhead = 0;
+                        voice.loop_reversed = false;
+                        voice.gain = 1.0;
+                        voice.stereo_oscillator_states.0.clear();
+                        // The next note begins at its own frequency rather than slewing to it.
+                        for osc_state in voice.oscillator_states.0.iter_mut() {
+                            osc_state.last_hz = None;
+                        }
+                        // Free running oscillators keep their phase.
+                        let states = voice.oscillator_states.0.iter_mut().zip(oscillators.iter());
+                        for (osc_state, _) in states.filter(|&(_, ref osc)| !osc.free_running) {
+                            osc_state.phase = voice_start_phase(phase_spread,
+                                                                random_start_phase,
+                                                                voice_idx,
+                                                                num_voices);
+                        }
+                    }
+                    continue;
+                },
+            };
+            let Voice {
+                ref mut loop_playhead,
+                ref mut oscillator_states,
+                ref mut loop_reversed,
+                ref mut stereo_oscillator_states,
+                gain,
+            } = *voice;
+            if *loop_playhead < duration {
+                let amp = amp * gain;
+                let hz = if glide_quantized { nearest_semitone_hz(hz) } else { hz };
+                let note_held = is_note_held(held_notes, hz);
+                let hz = match *glide_sequence {
+                    Some(ref seq) => seq.hz(hz),
+                    None => hz,
+                };
+                let hz = if pitch_bend != 0.0 { hz * 2.0f32.powf(pitch_bend / 12.0) } else { hz };
+                let freq_multi = hz as f64 / base_pitch as f64;
+                let playhead_perc = *loop_playhead as f64 / duration as f64;
+
+                // Let the amplitude envelopes know whether the voice's note is still held, and the
+                // oscillators how far their frequency may move.
+                let states = oscillator_states.0.iter_mut()
+                    .chain(stereo_oscillator_states.0.iter_mut());
+                for state in states {
+                    state.note_held = note_held;
+                    state.max_hz_per_sample = freq_slew;
+                }
+
+                // When stereo detuning, the left and right channels are rendered separately, each
+                // detuned by half of the `stereo_detune` in opposite directions.
+                let (freq_multi_l, freq_multi_r) = if should_detune {
+                    // The right channel's states begin from the left's whenever they are out of sync.
+                    if stereo_oscillator_states.0.len() != oscillator_states.0.len() {
+                        *stereo_oscillator_states = oscillator_states.clone();
+                    }
+                    let detune_multi = 2.0f64.powf(stereo_detune as f64 / 2_400.0);
+                    (freq_multi / detune_multi, freq_multi * detune_multi)
+                } else {
+                    (freq_multi, freq_multi)
+                };
+                let (wave, wave_quadrature) =
+                    sum_oscillators(oscillators, &mut oscillator_states.0, sample_hz,
+                                    playhead_perc, freq_multi_l, amp, high_precision,
+                                    solo_oscillator);
+                let wave_r = if should_detune {
+                    sum_oscillators(oscillators, &mut stereo_oscillator_states.0, sample_hz,
+                                    playhead_perc, freq_multi_r, amp, high_precision,
+                                    solo_oscillator).1
+                } else if should_quadrature {
+                    wave_quadrature
+                } else {
+                    wave
+                };
+
+                // Approaching the end of a forward loop, crossfade into the material leading up to
+                // the loop start so that the jump back to the loop start is seamless. The material
+                // is rendered from a copy of the oscillator states so that their phase is unchanged.
+                let crossfade = match loop_points {
+                    Some((loop_start, loop_end)) if loop_mode == LoopMode::Forward => {
+                        let len = std::cmp::min(loop_crossfade,
+                                                std::cmp::min(loop_start, loop_end - loop_start));
+                        let fade_start = loop_end - len;
+                        if len > 0 && *loop_playhead >= fade_start && *loop_playhead < loop_end {
+                            let pre_playhead = *loop_playhead - (loop_end - loop_start);
+                            let fade = (*loop_playhead - fade_start) as f32 / len as f32;
+                            Some((pre_playhead as f64 / duration as f64, fade))
+                        } else {
+                            None
+                        }
+                    },
+                    _ => None,
+                };
+                let (wave, wave_r) = match crossfade {
+                    Some((pre_perc, fade)) => {
+                        let (pre, pre_quadrature) =
+                            preview_oscillators(oscillators, &oscillator_states.0, sample_hz,
+                                                pre_perc, freq_multi_l, amp, high_precision,
+                                                solo_oscillator);
+                        let pre_r = if should_detune {
+                            preview_oscillators(oscillators, &stereo_oscillator_states.0,
+                                                sample_hz, pre_perc, freq_multi_r, amp,
+                                                high_precision, solo_oscillator).1
+                        } else if should_quadrature {
+                            pre_quadrature
+                        } else {
+                            pre
+                        };
+                        (wave + (pre - wave) * fade, wave_r + (pre_r - wave_r) * fade)
+                    },
+                    None => (wave, wave_r),
+                };
+
+                // If we have a multi-channel stream, calculate the spread.
+                frame = if should_spread {
+                    let spread_pan = spread_shape.pan(i, num_active_voices, spread);
+                    let key_pan = (hz / base_pitch).log2() * key_pan;
+                    let pan = (spread_pan + key_pan).max(-1.0).min(1.0);
+                    let panned = match pan_law {
+                        None if n_channels == 2 => stereo::pan(pan),
+                        _ => [0.0; 2],
+                    };
+
+                    // Multiply the pan result with the amp_per_channel to get the voice's amp.
+                    FRM::from_fn(|idx| {
+                        let wave = if idx == 0 { wave } else { wave_r };
+                        let gain = match pan_law {
+                            None if n_channels == 2 => panned[idx],
+                            None => PanLaw::ConstantPower.channel_gain(pan, n_channels, idx),
+                            Some(law) => law.channel_gain(pan, n_channels, idx),
+                        };
+                        let amp = wave * gain;
+                        frame.channel(idx).unwrap().add_amp(amp.to_sample())
+                    })
+                } else if should_detune || should_quadrature {
+                    FRM::from_fn(|idx| {
+                        let wave = if idx == 0 { wave } else { wave_r };
+                        frame.channel(idx).unwrap().add_amp(wave.to_sample())
+                    })
+                } else {
+                    frame.map(|s| s.add_amp(wave.to_sample()))
+                };
+
+                // Iterate the loop_playhead. If the loop_playhead passes the loop_end, reset the
+                // playhead to the start (or reverse its direction if ping-ponging).
+                if *loop_reversed {
+                    *loop_playhead -= 1;
+                } else {
+                    *loop_playhead += 1;
+                }
+                match loop_points {
+                    Some((loop_start, loop_end)) => match loop_mode {
+                        LoopMode::Forward => {
+                            if *loop_playhead >= loop_end {
+                                *loop_playhead = (*loop_playhead - loop_end) + loop_start;
+                            }
+                        },
+                        LoopMode::PingPong => {
+                            let last = std::cmp::max(loop_start, loop_end - 1);
+                            if !*loop_reversed && *loop_playhead > last {
+                                *loop_playhead = std::cmp::max(loop_start, 2 * last - *loop_playhead);
+                                *loop_reversed = true;
+                            } else if *loop_reversed && *loop_playhead < loop_start {
+                                *loop_playhead = std::cmp::min(last, 2 * loop_start - *loop_playhead);
+                                *loop_reversed = false;
+                            }
+                        },
+                    },
+                    // Without loop points there is nothing to bounce between.
+                    None => if *loop_reversed {
+                        *loop_playhead += 1;
+                        *loop_reversed = false;
+                    },
+                }
+            }
+            i += 1;
+        }
+
+        // Step any waveform crossfades once every voice has rendered the frame.
+        for osc in oscillators.iter_mut() {
+            osc.step_waveform_fade(sample_hz);
+        }
+
+        // Step the note sequence forward, if there is one.
+        if let Some(ref mut seq) = *glide_sequence {
+            seq.elapsed_ms += 1_000.0 / sample_hz;
+        }
+
+        // Fade out the output if a declicked stop is in progress.
+        let declick_amp = match *declick_elapsed_ms {
+            Some(ref mut elapsed_ms) => {
+                let amp = (1.0 - **elapsed_ms / declick_ms).max(0.0) as f32;
+                **elapsed_ms += 1_000.0 / sample_hz;
+                amp
+            },
+            None => 1.0,
+        };
+
+        // Smooth the applied volume toward the target volume to avoid zipper noise.
+        let smoothing_samples = volume_smoothing_ms * sample_hz / 1_000.0;
+        if smoothing_samples > 1.0 {
+            let coefficient = (-1.0 / smoothing_samples).exp() as f32;
+            **current_volume = volume + (**current_volume - volume) * coefficient;
+        } else {
+            **current_volume = volume;
+        }
+
+        let frame = frame.scale_amp((**current_volume * declick_amp).to_sample());
+
+        // Remove any DC offset from each channel of the final output.
+        match *dc_blocker {
+            Some(ref mut dc_blocker) => {
+                let coefficient = DcBlocker::coefficient(sample_hz);
+                FRM::from_fn(|idx| {
+                    let input = frame.channel(idx).unwrap().to_float_sample().to_sample::<f32>();
+                    let output = dc_blocker.next_sample(idx, input, coefficient);
+                    <FRM::Sample as Sample>::equilibrium().add_amp(output.to_sample())
+                })
+            },
+            None => frame,
+        }
+    }
+}
+
+impl<'a, FRM, NF, W, A, F, FW> Iterator for Frames<'a, FRM, NF, W, A, F, FW>
+    where FRM: Frame,
           <FRM::Sample as Sample>::Float: sample::FromSample<f32>,
           <FRM::Sample as Sample>::Signed: sample::FromSample<f32>,
           NF: NoteFreq,
@@ -530,3 +2634,1361 @@ impl<'a, FRM, NF, W, A, F, FW> Iterator for Frames<'a, FRM, NF, W, A, F, FW>
         Some(self.next_frame())
     }
 }
+
+
+#[test]
+fn test_midi_note_hz() {
+    assert_eq!(midi_note_hz(69), Some(440.0));
+    assert_eq!(midi_note_hz(57), Some(220.0));
+    assert_eq!(midi_note_hz(81), Some(880.0));
+    assert!((midi_note_hz(60).unwrap() - 261.6256).abs() < 0.001);
+    assert_eq!(midi_note_hz(128), None);
+}
+
+#[test]
+fn test_render_note() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .fade(10.0, 100.0);
+    let frames: Vec<[f32; 2]> = synth.render_note(pitch::Hz(440.0), 1.0, 200.0, 1_000.0);
+
+    // 200ms held plus a 100ms release at 1khz.
+    let expected_len = 300;
+    assert!((frames.len() as i64 - expected_len).abs() <= 1);
+    assert!(!synth.is_active());
+}
+
+#[test]
+fn test_render_note_sustained() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .fade(10.0, 100.0);
+    synth.sustain = true;
+    let frames: Vec<[f32; 2]> = synth.render_note(pitch::Hz(440.0), 1.0, 200.0, 1_000.0);
+
+    // The sustained note is never released, so the tail is cut off at its maximum length.
+    assert_eq!(frames.len(), 200 + 100 + TAIL_BLOCK_FRAMES);
+    assert!(synth.is_active());
+}
+
+#[test]
+fn test_ping_pong_loop() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .duration(100.0)
+        .loop_points(0.25, 0.5)
+        .loop_mode(LoopMode::PingPong);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+
+    // At 1khz the 100ms duration is 100 samples, so the loop spans samples 25..50.
+    let mut playheads = Vec::new();
+    for _ in 0..200 {
+        synth.frames::<[f32; 2]>(1_000.0).next_frame();
+        playheads.push(synth.voices[0].loop_playhead);
+    }
+
+    let looped = &playheads[50..];
+    assert!(looped.iter().all(|&playhead| playhead >= 25 && playhead < 50));
+    assert!(looped.windows(2).any(|w| w[1] < w[0]));
+    assert!(looped.windows(2).any(|w| w[1] > w[0]));
+}
+
+#[test]
+fn test_one_shot() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .duration(50.0)
+        .one_shot();
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    assert!(synth.is_active());
+
+    // At 1khz the 50ms duration is 50 samples.
+    let mut buffer = [[0.0f32; 2]; 64];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(!synth.is_active());
+}
+
+#[test]
+fn test_one_shot_declick() {
+    use oscillator::waveform;
+
+    // A finished one-shot is freed at once rather than faded out by the declick.
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .duration(50.0)
+        .declick_ms(20.0)
+        .one_shot();
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 64];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(!synth.is_active());
+    assert_eq!(synth.declick_elapsed_ms, None);
+}
+
+    let synth = Synth::<_, _, (), (), (), ()>::poly(()).detune_cents(1200.0);
+    assert_eq!(synth.instrument.detune, 12.0);
+
+    // 1200 cents either side of a note should be exactly an octave above and below.
+    let step = pitch::Hz(440.0).step();
+    let up = pitch::Step(step + synth.instrument.detune).hz();
+    let down = pitch::Step(step - synth.instrument.detune).hz();
+    assert!((up - 880.0).abs() < 0.01);
+    assert!((down - 220.0).abs() < 0.01);
+}
+
+#[test]
+fn test_detune_spread() {
+    use oscillator::waveform;
+
+    // Play the same note on every voice and read back the frequency generated for each.
+    let num_voices = 32;
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .num_voices(num_voices)
+        .detune_cents(50.0);
+    for _ in 0..num_voices {
+        synth.note_on(pitch::Hz(440.0), 1.0);
+    }
+    let offsets: Vec<f32> = synth.instrument.voices.iter()
+        .filter_map(|voice| voice.note.as_ref())
+        .map(|&(_, ref note_freq, _)| pitch::Hz(note_freq.hz()).step() - pitch::Hz(440.0).step())
+        .collect();
+    assert_eq!(offsets.len(), num_voices);
+
+    // Every voice lies within the detune of the played note, spread both above and below it.
+    assert!(offsets.iter().all(|offset| offset.abs() <= 0.5 + 1e-3));
+    assert!(offsets.iter().any(|&offset| offset > 0.0));
+    assert!(offsets.iter().any(|&offset| offset < 0.0));
+}
+
+#[test]
+fn test_key_pan() {
+    use oscillator::waveform;
+
+    // Sum the absolute amplitude of each channel while playing the given note.
+    fn channel_amps(hz: f32) -> (f32, f32) {
+        let mut synth = Synth::poly(())
+            .oscillator(Oscillator::new(waveform::Square, 1.0, 440.0, ()))
+            .base_pitch(440.0)
+            .key_pan(0.5);
+        synth.note_on(pitch::Hz(hz), 1.0);
+        let mut buffer = [[0.0f32; 2]; 64];
+        synth.fill_slice(&mut buffer, 44_100.0);
+        buffer.iter().fold((0.0, 0.0), |(l, r), f| (l + f[0].abs(), r + f[1].abs()))
+    }
+
+    // Two octaves below and above the base pitch should be panned hard left and right.
+    let (low_l, low_r) = channel_amps(110.0);
+    let (high_l, high_r) = channel_amps(1_760.0);
+    assert!(low_l > low_r);
+    assert!(high_r > high_l);
+}
+
+#[test]
+fn test_dc_blocker() {
+    let mut dc_blocker = DcBlocker::new();
+    let coefficient = DcBlocker::coefficient(1_000.0);
+
+    // A constant offset should decay towards zero.
+    let outputs: Vec<f32> = (0..1_000).map(|_| dc_blocker.next_sample(0, 0.5, coefficient)).collect();
+    let tail = &outputs[900..];
+    let mean = tail.iter().fold(0.0, |sum, s| sum + s) / tail.len() as f32;
+    assert!(mean.abs() < 0.001);
+}
+
+#[test]
+fn test_current_playhead_perc() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .duration(100.0);
+    assert_eq!(synth.current_playhead_perc(1_000.0), None);
+
+    // At 1khz the 100ms duration is 100 samples.
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 25];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert_eq!(synth.current_playhead_perc(1_000.0), Some(0.25));
+}
+
+#[test]
+fn test_nearest_semitone_hz() {
+    // Frequencies part way through a glide from A4 to C5 should land on semitones.
+    for &(hz, expected) in [(440.0, 440.0), (450.0, 440.0), (460.0, 466.1638), (510.0, 523.2511)].iter() {
+        assert!((nearest_semitone_hz(hz) - expected).abs() < 0.01);
+    }
+}
+
+#[test]
+fn test_voice_release_progress() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .num_voices(2)
+        .fade(0.0, 100.0);
+    assert_eq!(synth.voice_release_progress(1_000.0), vec![None, None]);
+
+    // A playing note has no release progress.
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 1]; 10];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert_eq!(synth.voice_release_progress(1_000.0), vec![None, None]);
+
+    // Once released, the progress increases as the release is rendered.
+    synth.note_off(pitch::Hz(440.0));
+    let mut buffer = [[0.0f32; 1]; 20];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    let first = synth.voice_release_progress(1_000.0);
+    assert_eq!(first.iter().filter(|progress| progress.is_some()).count(), 1);
+    let first = first.iter().filter_map(|&progress| progress).next().unwrap();
+    assert!(first > 0.0 && first < 1.0);
+
+    // Querying the progress does not advance the release.
+    let again = synth.voice_release_progress(1_000.0);
+    assert_eq!(again.iter().filter_map(|&progress| progress).next(), Some(first));
+
+    let mut buffer = [[0.0f32; 1]; 30];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    let second = synth.voice_release_progress(1_000.0).iter()
+        .filter_map(|&progress| progress).next().unwrap();
+    assert!(second > first && second < 1.0);
+}
+
+#[test]
+fn test_num_active_voices() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .num_voices(4);
+    assert_eq!(synth.num_active_voices(), 0);
+    assert!(!synth.is_active());
+
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    synth.note_on(pitch::Hz(550.0), 1.0);
+    synth.note_on(pitch::Hz(660.0), 1.0);
+    assert_eq!(synth.num_active_voices(), 3);
+    assert!(synth.is_active());
+
+    synth.stop();
+    assert_eq!(synth.num_active_voices(), 0);
+    assert!(!synth.is_active());
+}
+
+#[test]
+fn test_zero_voices() {
+    let synth = Synth::<_, _, (), (), (), ()>::poly(()).num_voices(0);
+    assert_eq!(synth.voices.len(), 1);
+    assert_eq!(synth.instrument.voices.len(), 1);
+}
+
+#[test]
+fn test_try_remove_oscillator() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .oscillator(Oscillator::new(waveform::Sine, 0.5, 880.0, ()));
+    let voices = synth.voices.clone();
+    assert_eq!(synth.try_remove_oscillator(2), None);
+    assert_eq!(synth.oscillators.len(), 2);
+    assert_eq!(synth.voices, voices);
+
+    let removed = synth.try_remove_oscillator(0);
+    assert_eq!(removed, Some(Oscillator::new(waveform::Sine, 1.0, 440.0, ())));
+    assert_eq!(synth.oscillators.len(), 1);
+    assert!(synth.voices.iter().all(|voice| voice.oscillator_states.0.len() == 1));
+}
+
+#[test]
+fn test_consistent_oscillator_states() {
+    use oscillator::waveform;
+
+    let osc = Oscillator::new(waveform::Sine, 1.0, 440.0, ());
+    let mut synth = Synth::poly(()).num_voices(2).oscillator(osc.clone());
+    synth.assert_consistent();
+    synth.set_num_voices(5);
+    synth.assert_consistent();
+    synth.add_oscillator(osc.clone());
+    synth.assert_consistent();
+    synth.set_num_voices(1);
+    synth.add_oscillator(osc.clone());
+    synth.set_num_voices(3);
+    synth.assert_consistent();
+    synth.remove_oscillator(1);
+    synth.assert_consistent();
+    assert_eq!(synth.voices.len(), 3);
+    assert!(synth.voices.iter().all(|voice| voice.oscillator_states.0.len() == 2));
+}
+
+#[test]
+fn test_phase_spread() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .num_voices(4)
+        .phase_spread(1.0);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let phases: Vec<f64> = synth.voices.iter().map(|v| v.oscillator_states.0[0].phase).collect();
+    assert_eq!(phases, vec![0.0, 0.25, 0.5, 0.75]);
+}
+
+#[test]
+fn test_random_start_phase() {
+    use oscillator::waveform;
+
+    // Return the starting phase of two consecutive notes.
+    fn start_phases(random_start_phase: bool) -> (f64, f64) {
+        let mut synth = Synth::poly(())
+            .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+            .num_voices(1)
+            .random_start_phase(random_start_phase);
+        synth.note_on(pitch::Hz(440.0), 1.0);
+        let a = synth.voices[0].oscillator_states.0[0].phase;
+        let mut buffer = [[0.0f32; 2]; 16];
+        synth.fill_slice(&mut buffer, 44_100.0);
+        synth.stop();
+        synth.note_on(pitch::Hz(440.0), 1.0);
+        let b = synth.voices[0].oscillator_states.0[0].phase;
+        (a, b)
+    }
+
+    assert_eq!(start_phases(false), (0.0, 0.0));
+    let (a, b) = start_phases(true);
+    assert!(a != b);
+}
+
+#[test]
+fn test_declick() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Square, 1.0, 100.0, ()))
+        .duration(1_000.0)
+        .declick_ms(5.0);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 100];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    let last_amp = buffer[99][0].abs();
+
+    // At 1khz the 5ms declick is 5 samples, after which the output should be silent.
+    synth.stop();
+    assert!(synth.is_active());
+    let mut buffer = [[0.0f32; 2]; 8];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    let amps: Vec<f32> = buffer.iter().map(|f| f[0].abs()).collect();
+    assert!(amps[0] <= last_amp);
+    assert!(amps.windows(2).all(|w| w[1] <= w[0]));
+    assert!(amps[1] > 0.0);
+    assert_eq!(amps[5], 0.0);
+    assert!(!synth.is_active());
+}
+
+#[test]
+fn test_volume_smoothing() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Square, 1.0, 100.0, ()))
+        .duration(1_000.0)
+        .volume_smoothing_ms(10.0);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 100];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    let last_amp = buffer[99][0].abs();
+
+    // The output should fall gradually rather than stepping straight to silence.
+    synth.volume = 0.0;
+    let mut buffer = [[0.0f32; 2]; 10];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    let amps: Vec<f32> = buffer.iter().map(|f| f[0].abs()).collect();
+    assert!(amps[0] > last_amp * 0.5);
+    assert!(amps.windows(2).all(|w| w[1] < w[0]));
+    assert!(amps[9] > 0.0);
+}
+
+#[test]
+fn test_clear_oscillators() {
+    use oscillator::waveform;
+
+    let osc = Oscillator::new(waveform::Sine, 1.0, 440.0, ());
+    let mut synth = Synth::poly(())
+        .num_voices(4)
+        .oscillator(osc.clone())
+        .oscillator(osc.clone())
+        .oscillator(osc.clone());
+    synth.clear_oscillators();
+    assert!(synth.oscillators.is_empty());
+    assert!(synth.voices.iter().all(|voice| voice.oscillator_states.0.is_empty()));
+}
+
+#[test]
+fn test_set_oscillator() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 220.0, ()))
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 880.0, ()));
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 16];
+    synth.fill_slice(&mut buffer, 44_100.0);
+    let voices = synth.voices.clone();
+
+    let new_osc = Oscillator::new(waveform::Sine, 0.5, 660.0, ());
+    let old_osc = synth.set_oscillator(1, new_osc.clone());
+    assert_eq!(old_osc, Some(Oscillator::new(waveform::Sine, 1.0, 440.0, ())));
+    assert_eq!(synth.oscillators[1], new_osc);
+    assert_eq!(synth.voices, voices);
+    assert_eq!(synth.set_oscillator(3, new_osc), None);
+}
+
+#[test]
+fn test_handle_midi() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .num_voices(4);
+
+    synth.handle_midi(&[0x90, 69, 100]);
+    assert_eq!(synth.num_active_voices(), 1);
+    synth.handle_midi(&[0x80, 69, 0]);
+    assert_eq!(synth.num_active_voices(), 0);
+
+    // A note on with a velocity of zero is a note off.
+    synth.handle_midi(&[0x91, 60, 100]);
+    assert_eq!(synth.num_active_voices(), 1);
+    synth.handle_midi(&[0x91, 60, 0]);
+    assert_eq!(synth.num_active_voices(), 0);
+
+    // Notes released while the sustain pedal is held sound until the pedal is released.
+    synth.handle_midi(&[0xB0, 64, 127]);
+    synth.handle_midi(&[0x90, 69, 100]);
+    synth.handle_midi(&[0x80, 69, 0]);
+    assert_eq!(synth.num_active_voices(), 1);
+    synth.handle_midi(&[0xB0, 64, 0]);
+    assert_eq!(synth.num_active_voices(), 0);
+
+    synth.handle_midi(&[0xE0, 0x00, 0x40]);
+    assert_eq!(synth.pitch_bend, 0.0);
+    synth.handle_midi(&[0xE0, 0x00, 0x00]);
+    assert_eq!(synth.pitch_bend, -MIDI_PITCH_BEND_RANGE);
+}
+
+#[test]
+fn test_muted_oscillator() {
+    use oscillator::waveform;
+
+    let osc = Oscillator::new(waveform::Sine, 0.5, 440.0, ());
+    let mut muted = osc.clone();
+    muted.is_muted = true;
+    let mut synth = Synth::poly(()).oscillator(osc.clone()).oscillator(muted);
+    let mut single = Synth::poly(()).oscillator(osc);
+
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    single.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 64];
+    let mut expected = [[0.0f32; 2]; 64];
+    synth.fill_slice(&mut buffer, 44_100.0);
+    single.fill_slice(&mut expected, 44_100.0);
+    assert_eq!(&buffer[..], &expected[..]);
+}
+
+#[test]
+fn test_stereo_detune() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .stereo_detune(20.0);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 4_410];
+    synth.fill_slice(&mut buffer, 44_100.0);
+
+    // Both channels begin from the same phase and drift apart over time.
+    assert_eq!(buffer[0][0], buffer[0][1]);
+    let max_difference = buffer.iter().fold(0.0f32, |max, f| max.max((f[0] - f[1]).abs()));
+    assert!(max_difference > 0.1);
+
+    let mut synth = Synth::poly(()).oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()));
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 4_410];
+    synth.fill_slice(&mut buffer, 44_100.0);
+    assert!(buffer.iter().all(|f| f[0] == f[1]));
+}
+
+#[test]
+fn test_multichannel_spread() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .num_voices(4)
+        .spread(1.0);
+    for &hz in &[220.0, 330.0, 440.0, 550.0] {
+        synth.note_on(pitch::Hz(hz), 1.0);
+    }
+    let mut buffer = [[0.0f32; 4]; 64];
+    synth.fill_slice(&mut buffer, 44_100.0);
+
+    // Each of the four voices is panned to its own channel.
+    for channel in 0..4 {
+        assert!(buffer.iter().any(|f| f[channel] != 0.0));
+    }
+
+    // Adjacent channels share the signal with an equal-power pan.
+    let left = PanLaw::ConstantPower.channel_gain(0.0, 3, 0);
+    let centre = PanLaw::ConstantPower.channel_gain(0.0, 3, 1);
+    assert_eq!(left, 0.0);
+    assert_eq!(centre, 1.0);
+    let a = PanLaw::ConstantPower.channel_gain(-2.0 / 3.0, 4, 0);
+    let b = PanLaw::ConstantPower.channel_gain(-2.0 / 3.0, 4, 1);
+    assert!((a * a + b * b - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_note_on_with_gain() {
+    use oscillator::waveform;
+
+    let peak = |gain: f32| {
+        let mut synth = Synth::poly(()).oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()));
+        synth.note_on_with_gain(pitch::Hz(440.0), 1.0, gain);
+        let mut buffer = [[0.0f32; 1]; 441];
+        synth.fill_slice(&mut buffer, 44_100.0);
+        buffer.iter().fold(0.0f32, |max, f| max.max(f[0].abs()))
+    };
+    let full = peak(1.0);
+    let half = peak(0.5);
+    assert!(full > 0.0);
+    assert!((half - full * 0.5).abs() < 1e-4);
+}
+
+#[test]
+fn test_loop_crossfade() {
+    use envelope::Point;
+    use oscillator::waveform;
+
+    // The amplitude ramps up across the duration, so looping jumps from a high to low amplitude.
+    let render = |crossfade_ms: f64| {
+        let env: oscillator::AmpEnvelope =
+            vec![Point::new(0.0, 0.0, 0.0), Point::new(1.0, 1.0, 0.0)].into();
+        let mut synth = Synth::poly(())
+            .oscillator(Oscillator::new(waveform::Square, env, 0.001, ()))
+            .duration(100.0)
+            .loop_points(0.25, 0.75)
+            .loop_crossfade_ms(crossfade_ms);
+        synth.note_on(pitch::Hz(synth.base_pitch), 1.0);
+        let mut buffer = [[0.0f32; 1]; 300];
+        synth.fill_slice(&mut buffer, 1_000.0);
+        buffer.windows(2).fold(0.0f32, |max, w| max.max((w[1][0] - w[0][0]).abs()))
+    };
+    assert!(render(0.0) > 0.4);
+    assert!(render(10.0) < 0.1);
+}
+
+#[test]
+fn test_chord_on() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .num_voices(4);
+    synth.note_on(pitch::Hz(110.0), 1.0);
+    synth.note_on(pitch::Hz(220.0), 1.0);
+
+    let chord = [(pitch::Hz(261.63), 1.0), (pitch::Hz(329.63), 1.0),
+                 (pitch::Hz(392.0), 1.0), (pitch::Hz(523.25), 1.0)];
+    synth.chord_on(&chord);
+    assert_eq!(synth.num_active_voices(), 4);
+
+    // A chord with more notes than voices never steals its own notes.
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .num_voices(2);
+    synth.chord_on(&chord);
+    assert_eq!(synth.num_active_voices(), 2);
+    assert_eq!(synth.held_notes, vec![261.63, 329.63]);
+}
+
+#[test]
+fn test_release_all() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .fade(0.0, 100.0);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    synth.note_on(pitch::Hz(550.0), 1.0);
+    synth.release_all();
+
+    // The notes are released but continue to sound throughout their release.
+    assert!(synth.held_notes.is_empty());
+    assert!(synth.is_active());
+    let mut buffer = [[0.0f32; 1]; 50];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(synth.is_active());
+    assert!(buffer.iter().any(|f| f[0] != 0.0));
+
+    let mut buffer = [[0.0f32; 1]; 100];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(!synth.is_active());
+}
+
+#[test]
+fn test_release_all_retuned() {
+    use oscillator::waveform;
+
+    // Under just intonation E4 is retuned, so the held notes must not be retuned a second time
+    // when they are released.
+    let c4 = 261.6256;
+    let e4 = 329.6276;
+    let new_synth = || {
+        Synth::poly(())
+            .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+            .tuning(Tuning::just_intonation())
+            .fade(0.0, 10.0)
+    };
+    let mut buffer = [[0.0f32; 1]; 20];
+
+    let mut synth = new_synth();
+    synth.note_on(pitch::Hz(c4), 1.0);
+    synth.note_on(pitch::Hz(e4), 1.0);
+    synth.release_all();
+    assert!(synth.held_notes.is_empty());
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(!synth.is_active());
+
+    // Notes released while the sustain pedal is held are stopped once it is released.
+    let mut synth = new_synth();
+    synth.sustain(true);
+    synth.note_on(pitch::Hz(e4), 1.0);
+    synth.release_all();
+    assert_eq!(synth.sustained_notes, vec![Tuning::just_intonation().retune(e4)]);
+    synth.sustain(false);
+    assert!(synth.held_notes.is_empty());
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(!synth.is_active());
+
+    // The same applies to an All Notes Off message.
+    let mut synth = new_synth();
+    synth.note_on_midi(64, 1.0);
+    synth.handle_midi(&[0xB0, 123, 0]);
+    assert!(synth.held_notes.is_empty());
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(!synth.is_active());
+}
+
+#[test]
+fn test_panic() {
+    use oscillator::waveform;
+
+    let new_synth = || {
+        let mut synth = Synth::poly(())
+            .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+            .fade(0.0, 100.0)
+            .declick_ms(10.0);
+        synth.note_on(pitch::Hz(440.0), 1.0);
+        let mut buffer = [[0.0f32; 1]; 10];
+        synth.fill_slice(&mut buffer, 1_000.0);
+        synth
+    };
+
+    // A panic zeroes everything immediately, ignoring the declick.
+    let mut synth = new_synth();
+    synth.panic();
+    assert!(!synth.is_active());
+    assert!(synth.held_notes.is_empty());
+    assert!(synth.voices.iter().all(|v| v.loop_playhead == 0));
+    assert!(synth.voices.iter().all(|v| v.oscillator_states.0.iter().all(|s| s.phase == 0.0)));
+
+    // A stop fades out over the declick before zeroing everything.
+    let mut synth = new_synth();
+    synth.stop();
+    assert!(synth.is_active());
+    let mut buffer = [[0.0f32; 1]; 20];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(!synth.is_active());
+
+    // Releasing all notes leaves the voices in their release.
+    let mut synth = new_synth();
+    synth.release_all();
+    assert!(synth.is_active());
+    assert!(synth.voices.iter().any(|v| v.loop_playhead > 0));
+}
+
+#[test]
+fn test_bake_wavetables() {
+    use oscillator::waveform::{Bakeable, Dynamic};
+
+    let synth = Synth::poly(())
+        .oscillator(Oscillator::new(Bakeable::from(Dynamic::Sine), 0.5, 440.0, ()))
+        .oscillator(Oscillator::new(Bakeable::from(Dynamic::Noise), 0.5, 440.0, ()));
+    let mut baked = synth.clone();
+    baked.bake_wavetables(2048);
+    match baked.oscillators[0].waveform {
+        Bakeable::Wavetable(ref table) => assert_eq!(table.0.len(), 2048),
+        ref waveform => panic!("expected a wavetable but found {:?}", waveform),
+    }
+    assert_eq!(baked.oscillators[1].waveform, Bakeable::Dynamic(Dynamic::Noise));
+
+    // The baked sine closely follows the analytic one.
+    let mut synth = synth;
+    synth.remove_oscillator(1);
+    baked.remove_oscillator(1);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    baked.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 256];
+    let mut expected = [[0.0f32; 2]; 256];
+    baked.fill_slice(&mut buffer, 44_100.0);
+    synth.fill_slice(&mut expected, 44_100.0);
+    for (a, b) in buffer.iter().zip(expected.iter()) {
+        assert!((a[0] - b[0]).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_oversample() {
+    use oscillator::waveform;
+
+    // The energy of the given signal at the given frequency.
+    fn energy_at(signal: &[f32], hz: f64, sample_hz: f64) -> f64 {
+        let w = 2.0 * std::f64::consts::PI * hz / sample_hz;
+        let (re, im) = signal.iter().enumerate().fold((0.0, 0.0), |(re, im), (i, &s)| {
+            (re + s as f64 * (w * i as f64).cos(), im + s as f64 * (w * i as f64).sin())
+        });
+        (re * re + im * im) / signal.len() as f64
+    }
+
+    // A naive 5khz saw at 44.1khz folds its 8th and 9th harmonics back to 4.1khz and 900hz.
+    let alias_energy = |factor: u8| {
+        let osc = Oscillator::new(waveform::Saw, 0.5, 5_000.0, ()).fixed(5_000.0);
+        let mut synth = Synth::poly(()).oscillator(osc).oversample(factor);
+        synth.note_on(pitch::Hz(440.0), 1.0);
+        let mut buffer = vec![[0.0f32; 1]; 8_820];
+        synth.fill_slice(&mut buffer, 44_100.0);
+        let signal: Vec<f32> = buffer[4_410..].iter().map(|f| f[0]).collect();
+        let fundamental = energy_at(&signal, 5_000.0, 44_100.0);
+        let alias = energy_at(&signal, 900.0, 44_100.0) + energy_at(&signal, 4_100.0, 44_100.0);
+        alias / fundamental
+    };
+    assert!(alias_energy(4) < alias_energy(1) * 0.1);
+}
+
+#[test]
+fn test_free_running() {
+    use oscillator::waveform;
+
+    let osc = Oscillator::new(waveform::Sine, 0.5, 440.0, ());
+    let mut synth = Synth::retrigger(())
+        .oscillator(osc.clone())
+        .oscillator(osc.free_running(true));
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 100];
+    synth.fill_slice(&mut buffer, 44_100.0);
+    synth.note_off(pitch::Hz(440.0));
+    while synth.is_active() {
+        synth.fill_slice(&mut buffer, 44_100.0);
+    }
+    // Render once more while idle so that the voice is reset for its next note.
+    synth.fill_slice(&mut buffer, 44_100.0);
+
+    let states = &synth.voices[0].oscillator_states.0;
+    assert_eq!(states[0].phase, 0.0);
+    assert!(states[1].phase != 0.0);
+
+    // The free-running oscillator continues from where it left off when the note is retriggered.
+    let phase = states[1].phase;
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    synth.fill_slice(&mut buffer[..1], 44_100.0);
+    assert!(synth.voices[0].oscillator_states.0[1].phase > phase);
+}
+
+#[test]
+fn test_seed() {
+    use oscillator::waveform;
+
+    let render = |seed: u64| {
+        let mut synth = Synth::poly(())
+            .num_voices(4)
+            .oscillator(Oscillator::new(waveform::Noise, 0.5, 440.0, ()))
+            .seed(seed);
+        let mut voices = vec![];
+        for &hz in [220.0, 330.0, 440.0, 550.0].iter() {
+            synth.note_on(pitch::Hz(hz), 1.0);
+            let mut buffer = [[0.0f32; 1]; 64];
+            synth.fill_slice(&mut buffer, 44_100.0);
+            voices.push(buffer.to_vec());
+        }
+        (voices, synth.voices.clone())
+    };
+
+    // The same seed reproduces the output of every voice.
+    let (a, a_voices) = render(42);
+    let (b, _) = render(42);
+    assert_eq!(a, b);
+    let (c, _) = render(7);
+    assert!(a != c);
+
+    // Each voice draws from its own generator.
+    let rngs: Vec<_> = a_voices.iter().map(|v| v.oscillator_states.0[0].rng).collect();
+    for (i, rng) in rngs.iter().enumerate() {
+        assert!(rng.is_some());
+        assert!(rngs[i + 1..].iter().all(|other| other != rng));
+    }
+}
+
+#[test]
+fn test_zero_base_pitch() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 0.5, 440.0, ()))
+        .spread(1.0)
+        .key_pan(0.5)
+        .base_pitch(0.0);
+    assert_eq!(synth.base_pitch, MIN_BASE_PITCH);
+
+    synth.base_pitch = -1.0;
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 256];
+    synth.fill_slice(&mut buffer, 44_100.0);
+    assert!(buffer.iter().all(|f| f[0].is_finite() && f[1].is_finite()));
+}
+
+#[test]
+fn test_zero_duration() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 0.5, 440.0, ()))
+        .duration(0.0)
+        .loop_points(0.0, 1.0);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 256];
+    synth.fill_slice(&mut buffer, 44_100.0);
+    assert!(buffer.iter().all(|f| f[0].is_finite() && f[1].is_finite()));
+}
+
+#[test]
+fn test_try_frames() {
+    use oscillator::waveform;
+
+    let mut synth: Synth<instrument::mode::Poly, (), waveform::Sine, f32, f64, ()> =
+        Synth::poly(());
+    assert_eq!(synth.try_frames::<[f32; 2]>(44_100.0).err(), Some(SynthError::NoOscillators));
+
+    // The builders normalize the loop points, but the field itself may be set to anything.
+    let mut synth = synth.oscillator(Oscillator::new(waveform::Sine, 0.5, 440.0, ()));
+    synth.loop_points = Some((0.9, 0.1));
+    assert_eq!(synth.try_frames::<[f32; 2]>(44_100.0).err(),
+               Some(SynthError::InvalidLoopPoints(0.9, 0.1)));
+
+    let mut synth = synth.loop_points(0.1, 0.9);
+    assert!(synth.try_frames::<[f32; 2]>(44_100.0).is_ok());
+}
+
+#[test]
+fn test_normalize_loop_points() {
+    let synth: Synth<instrument::mode::Poly, (), oscillator::waveform::Sine, f32, f64, ()> =
+        Synth::poly(());
+    assert_eq!(synth.clone().loop_points(1.2, -0.3).loop_points, Some((0.0, 1.0)));
+    assert_eq!(synth.clone().loop_points(0.9, 0.1).loop_points, Some((0.1, 0.9)));
+    assert_eq!(synth.clone().loop_end(0.5).loop_points, Some((0.0, 0.5)));
+    assert_eq!(synth.loop_start(0.25).loop_points, Some((0.25, 1.0)));
+}
+
+#[test]
+fn test_pan_law() {
+    use oscillator::waveform;
+
+    // Summed power and gain in the centre.
+    let centre = |law: PanLaw| (law.channel_gain(0.0, 2, 0), law.channel_gain(0.0, 2, 1));
+    let (l, r) = centre(PanLaw::ConstantPower);
+    assert!((l * l + r * r - 1.0).abs() < 1e-6);
+    let (l, r) = centre(PanLaw::ConstantGain);
+    assert!((l + r - 1.0).abs() < 1e-6);
+
+    // Two voices spread hard left and right each lie entirely within a single channel.
+    for &law in [PanLaw::ConstantPower, PanLaw::ConstantGain].iter() {
+        let mut synth = Synth::poly(())
+            .num_voices(2)
+            .oscillator(Oscillator::new(waveform::Square, 0.5, 440.0, ()))
+            .spread(1.0)
+            .pan_law(law);
+        synth.note_on(pitch::Hz(440.0), 1.0);
+        synth.note_on(pitch::Hz(440.0 * 1.5), 1.0);
+        let mut buffer = [[0.0f32; 3]; 64];
+        synth.fill_slice(&mut buffer, 44_100.0);
+        assert!(buffer.iter().all(|f| f[1] == 0.0));
+        assert!(buffer.iter().any(|f| f[0] != 0.0) && buffer.iter().any(|f| f[2] != 0.0));
+    }
+}
+
+#[test]
+fn test_sostenuto() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()));
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    synth.sostenuto(true);
+
+    // Notes played after the pedal was pressed are released as normal.
+    synth.note_on(pitch::Hz(550.0), 1.0);
+    synth.note_off(pitch::Hz(550.0));
+    assert_eq!(synth.held_notes, vec![440.0]);
+
+    // Notes held when the pedal was pressed continue to sound until it is released.
+    synth.note_off(pitch::Hz(440.0));
+    assert_eq!(synth.held_notes, vec![440.0]);
+    assert_eq!(synth.sostenuto_released_notes, vec![440.0]);
+    synth.sostenuto(false);
+    assert!(synth.held_notes.is_empty());
+    assert!(synth.sostenuto_released_notes.is_empty());
+
+    // CC66 drives the pedal.
+    synth.handle_midi(&[0xB0, 66, 127]);
+    assert!(synth.sostenuto);
+    synth.handle_midi(&[0xB0, 66, 0]);
+    assert!(!synth.sostenuto);
+}
+
+#[test]
+fn test_envelope_sustain() {
+    use envelope::Envelope;
+    use oscillator::waveform;
+
+    let mut env = Envelope::adsr(100.0, 100.0, 0.5, 200.0, 1000.0);
+    env.sustain_point = Some(2);
+    let mut synth = Synth::retrigger(())
+        .oscillator(Oscillator::new(waveform::Square, env, 440.0, ()))
+        .duration(1000.0);
+
+    // At 1khz the 1000ms duration is 1000 samples, so the release stage begins after 800 samples.
+    // While the note is held the amplitude stays at the sustain level instead.
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 1]; 900];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(buffer[300..].iter().all(|f| (f[0].abs() - 0.5).abs() < 1e-3));
+    assert!(synth.voices[0].oscillator_states.0[0].note_held);
+}
+
+#[test]
+fn test_phase_offset() {
+    use oscillator::waveform;
+
+    // Two sines half a cycle apart cancel each other out.
+    let osc = Oscillator::new(waveform::Sine, 0.5, 440.0, ());
+    let mut synth = Synth::retrigger(())
+        .oscillator(osc.clone())
+        .oscillator(osc.phase_offset(1.5));
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 1]; 256];
+    synth.fill_slice(&mut buffer, 44_100.0);
+    assert!(buffer.iter().all(|f| f[0].abs() < 1e-5));
+}
+
+#[test]
+fn test_solo_oscillator() {
+    use oscillator::waveform;
+
+    let sine = Oscillator::new(waveform::Dynamic::Sine, 0.5, 440.0, ());
+    let mut muted_sine = sine.clone();
+    muted_sine.is_muted = true;
+    let saw = Oscillator::new(waveform::Dynamic::Saw, 0.5, 440.0, ());
+    let render = |mut synth: Synth<_, _, _, _, _, _>| {
+        synth.note_on(pitch::Hz(440.0), 1.0);
+        let mut buffer = [[0.0f32; 1]; 64];
+        synth.fill_slice(&mut buffer, 44_100.0);
+        buffer
+    };
+
+    // Soloing the first oscillator leaves only its contribution, even though it is muted.
+    let single = render(Synth::retrigger(()).oscillator(sine.clone()));
+    let soloed = render(Synth::retrigger(()).oscillator(muted_sine).oscillator(saw.clone())
+        .solo_oscillator(Some(0)));
+    assert_eq!(&soloed[..], &single[..]);
+
+    // Without a solo, every unmuted oscillator is mixed.
+    let mixed = render(Synth::retrigger(()).oscillator(sine).oscillator(saw));
+    assert!(mixed[..] != single[..]);
+}
+
+#[test]
+fn test_freq_slew() {
+    use oscillator::frequency::NoteSequence;
+    use oscillator::waveform;
+
+    // A frequency that steps from 220hz to 880hz half way through the duration.
+    let step = NoteSequence { notes: vec![(0.0, 220.0), (0.5, 880.0)], glide: false };
+    let realized_hz = |synth: Synth<_, _, _, _, _, _>| {
+        let mut synth = synth.oscillator(Oscillator::new(waveform::Sine, 1.0, step.clone(), ()))
+            .base_pitch(440.0)
+            .duration(100.0);
+        synth.note_on(pitch::Hz(440.0), 1.0);
+        let mut buffer = [[0.0f32; 1]; 1];
+        (0..90).map(|_| {
+            synth.fill_slice(&mut buffer, 1_000.0);
+            synth.voices[0].oscillator_states.0[0].last_hz.unwrap()
+        }).collect::<Vec<_>>()
+    };
+    let max_step = |hz: &[f64]| hz.windows(2).fold(0.0f64, |max, w| max.max((w[1] - w[0]).abs()));
+
+    // Without a limit the frequency jumps, while with a limit it ramps toward the new frequency.
+    let unlimited = realized_hz(Synth::retrigger(()));
+    assert!(max_step(&unlimited) > 600.0);
+    let limited = realized_hz(Synth::retrigger(()).freq_slew(10.0));
+    assert!(max_step(&limited) <= 10.0 + 1e-9);
+    assert!(limited[60] > 300.0 && limited[60] < 880.0);
+}
+
+#[test]
+fn test_note_on_tracked() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .num_voices(4)
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()));
+
+    // Each note is given its own voice until the voices are exhausted.
+    let mut indices: Vec<usize> = [220.0, 330.0, 440.0, 550.0].iter()
+        .map(|&hz| synth.note_on_tracked(pitch::Hz(hz), 1.0).unwrap())
+        .collect();
+    indices.sort();
+    assert_eq!(indices, vec![0, 1, 2, 3]);
+
+    // Once exhausted, a voice is stolen.
+    let idx = synth.note_on_tracked(pitch::Hz(660.0), 1.0).unwrap();
+    assert!(idx < 4);
+}
+
+#[test]
+fn test_fill_interleaved() {
+    use oscillator::waveform;
+
+    let new_synth = || {
+        let mut synth = Synth::poly(())
+            .oscillator(Oscillator::new(waveform::Sine, 0.5, 440.0, ()))
+            .spread(1.0);
+        synth.note_on(pitch::Hz(440.0), 1.0);
+        synth.note_on(pitch::Hz(660.0), 1.0);
+        synth
+    };
+
+    // The interleaved output matches the frame based output, added to the existing samples.
+    let mut frames = [[0.25f32; 2]; 100];
+    new_synth().fill_slice(&mut frames, 44_100.0);
+    let mut interleaved = [0.25f32; 200];
+    new_synth().fill_interleaved(&mut interleaved, 2, 44_100.0).unwrap();
+    let expected: Vec<f32> = frames.iter().flat_map(|f| f.iter().cloned()).collect();
+    assert_eq!(&interleaved[..], &expected[..]);
+
+    let mut synth = new_synth();
+    assert_eq!(synth.fill_interleaved(&mut interleaved[..199], 2, 44_100.0),
+               Err(SynthError::InvalidInterleavedLength(199, 2)));
+    assert_eq!(synth.fill_interleaved(&mut interleaved, 0, 44_100.0),
+               Err(SynthError::UnsupportedChannels(0)));
+}
+
+#[test]
+fn test_fill_slice_replace() {
+    use oscillator::waveform;
+
+    let new_synth = || {
+        let mut synth = Synth::poly(())
+            .oscillator(Oscillator::new(waveform::Sine, 0.5, 440.0, ()));
+        synth.note_on(pitch::Hz(440.0), 1.0);
+        synth
+    };
+    let mut expected = [[0.0f32; 2]; 64];
+    new_synth().fill_slice(&mut expected, 44_100.0);
+
+    // `fill_slice` adds to the existing contents, while `fill_slice_replace` overwrites them.
+    let mut added = [[0.5f32; 2]; 64];
+    new_synth().fill_slice(&mut added, 44_100.0);
+    let mut replaced = [[0.5f32; 2]; 64];
+    new_synth().fill_slice_replace(&mut replaced, 44_100.0);
+    assert_eq!(&replaced[..], &expected[..]);
+    for (added, expected) in added.iter().zip(expected.iter()) {
+        assert_eq!(added[0], expected[0] + 0.5);
+    }
+
+    // Where the synth is silent the output is silenced.
+    let mut silent = Synth::poly(()).oscillator(Oscillator::new(waveform::Sine, 0.5, 440.0, ()));
+    let mut replaced = [[0.5f32; 2]; 64];
+    silent.fill_slice_replace(&mut replaced, 44_100.0);
+    assert!(replaced.iter().all(|f| *f == [0.0; 2]));
+}
+
+#[test]
+fn test_spread_shape() {
+    use oscillator::waveform;
+
+    let pans = |shape: SpreadShape| (0..4).map(|i| shape.pan(i, 4, 1.0)).collect::<Vec<_>>();
+    assert_eq!(pans(SpreadShape::Alternating), vec![-1.0, 1.0, -1.0, 1.0]);
+    assert_eq!(pans(SpreadShape::Centered), vec![0.0, -0.5, 0.5, -1.0]);
+    assert_eq!(SpreadShape::Alternating.pan(0, 1, 1.0), 0.0);
+
+    // With four alternating voices each lies entirely within either the first or last channel.
+    let mut synth = Synth::poly(())
+        .num_voices(4)
+        .oscillator(Oscillator::new(waveform::Square, 0.5, 440.0, ()))
+        .spread(1.0)
+        .spread_shape(SpreadShape::Alternating)
+        .pan_law(PanLaw::ConstantGain);
+    for &hz in [220.0, 330.0, 440.0, 550.0].iter() {
+        synth.note_on(pitch::Hz(hz), 1.0);
+    }
+    let mut buffer = [[0.0f32; 3]; 64];
+    synth.fill_slice(&mut buffer, 44_100.0);
+    assert!(buffer.iter().all(|f| f[1] == 0.0));
+    assert!(buffer.iter().any(|f| f[0] != 0.0) && buffer.iter().any(|f| f[2] != 0.0));
+}
+
+#[test]
+fn test_output_enabled() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 0.5, 440.0, ()))
+        .duration(1_000.0)
+        .output_enabled(false);
+    synth.note_on(pitch::Hz(440.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 250];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(buffer.iter().all(|f| *f == [0.0; 2]));
+    assert_eq!(synth.current_playhead_perc(1_000.0), Some(0.25));
+
+    // Re-enabling the output resumes from where the voice has advanced to.
+    synth.output_enabled = true;
+    synth.fill_slice(&mut buffer, 1_000.0);
+    assert!(buffer.iter().any(|f| *f != [0.0; 2]));
+    assert_eq!(synth.current_playhead_perc(1_000.0), Some(0.5));
+}
+
+#[test]
+fn test_tail_len_samples() {
+    use oscillator::waveform;
+
+    let synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 0.5, 440.0, ()))
+        .release(250.0);
+    assert_eq!(synth.tail_len_samples(1_000.0), 250);
+    assert_eq!(synth.tail_len_samples(44_100.0), 11_025);
+    assert_eq!(synth.release(0.0).tail_len_samples(44_100.0), 0);
+}
+
+#[test]
+fn test_normalized_phase() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .num_voices(1)
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .duration(1_000_000.0)
+        .base_pitch(440.0);
+    assert_eq!(synth.normalized_phase(1, 0), None);
+
+    // At 1khz a 250hz note steps a quarter of a cycle every frame.
+    synth.note_on(pitch::Hz(250.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 4_001];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    let phase = synth.normalized_phase(0, 0).unwrap();
+    assert!(phase >= 0.0 && phase < 1.0);
+    assert!((phase - 0.25).abs() < 1e-6);
+    assert_eq!(synth.normalized_phase(0, 1), None);
+}
+
+#[test]
+fn test_quadrature() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()).quadrature(true))
+        .base_pitch(440.0);
+    synth.note_on(pitch::Hz(10.0), 1.0);
+    let mut buffer = [[0.0f32; 2]; 200];
+    synth.fill_slice(&mut buffer, 1_000.0);
+
+    // The left channel is a sine and the right a cosine, so the angle between them is the phase.
+    let mut num_checked = 0;
+    for (i, f) in buffer.iter().enumerate() {
+        let (l, r) = (f[0] as f64, f[1] as f64);
+        if l * l + r * r < 1e-6 {
+            continue;
+        }
+        let expected = 2.0 * std::f64::consts::PI * i as f64 * 10.0 / 1_000.0;
+        let diff = l.atan2(r) - expected;
+        assert!(diff.sin().abs() < 1e-3 && diff.cos() > 0.0);
+        num_checked += 1;
+    }
+    assert!(num_checked > 100);
+
+    // Mono output is unaffected.
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()).quadrature(true))
+        .base_pitch(440.0);
+    synth.note_on(pitch::Hz(10.0), 1.0);
+    let mut mono = [[0.0f32; 1]; 200];
+    synth.fill_slice(&mut mono, 1_000.0);
+    for (m, f) in mono.iter().zip(buffer.iter()) {
+        assert_eq!(m[0], f[0]);
+    }
+}
+
+#[test]
+fn test_ignore_note_off() {
+    use oscillator::waveform;
+
+    let new_synth = |ignore_note_off: bool| {
+        let mut synth = Synth::poly(())
+            .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+            .duration(50.0)
+            .release(20.0)
+            .one_shot()
+            .ignore_note_off(ignore_note_off);
+        synth.note_on(pitch::Hz(440.0), 1.0);
+        synth
+    };
+
+    // At 1khz the 50ms duration is 50 samples.
+    let render = |mut synth: Synth<_, _, _, _, _, _>, note_off: bool| {
+        let mut buffer = [[0.0f32; 2]; 64];
+        synth.fill_slice(&mut buffer[..10], 1_000.0);
+        if note_off {
+            synth.note_off(pitch::Hz(440.0));
+        }
+        synth.fill_slice(&mut buffer[10..], 1_000.0);
+        assert!(!synth.is_active());
+        buffer
+    };
+
+    // The ignored note_off has no effect on the note, which completes its duration.
+    let held = render(new_synth(true), false);
+    let released = render(new_synth(true), true);
+    assert_eq!(&held[..], &released[..]);
+    assert!(held[10..50].iter().any(|f| f[0] != 0.0));
+
+    // Otherwise the note fades out over the release.
+    assert!(&render(new_synth(false), true)[..] != &held[..]);
+}
+
+#[test]
+fn test_ignore_note_off_render_note() {
+    use oscillator::waveform;
+
+    let new_synth = || Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .duration(50.0)
+        .release(20.0)
+        .ignore_note_off(true);
+
+    // A one-shot note plays through to the end of its duration despite the early note_off.
+    let mut synth = new_synth().one_shot();
+    assert_eq!(synth.tail_len_samples(1_000.0), 50);
+    let frames: Vec<[f32; 2]> = synth.render_note(pitch::Hz(440.0), 1.0, 10.0, 1_000.0);
+    assert!((frames.len() as i64 - 50).abs() <= 1);
+    assert!(!synth.is_active());
+
+    // Otherwise the note is never released, so the render is cut off at the end of its tail.
+    let mut synth = new_synth();
+    let frames: Vec<[f32; 2]> = synth.render_note(pitch::Hz(440.0), 1.0, 10.0, 1_000.0);
+    assert_eq!(frames.len(), 10 + 20 + TAIL_BLOCK_FRAMES);
+    assert!(synth.is_active());
+}
+
+#[test]
+fn test_note_sequence() {
+    use oscillator::waveform;
+
+    let mut synth = Synth::poly(())
+        .num_voices(1)
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .duration(1_000_000.0)
+        .base_pitch(440.0);
+    synth.note_on(pitch::Hz(220.0), 1.0);
+    synth.note_sequence(vec![(pitch::Hz(440.0), time::Ms(100.0)),
+                             (pitch::Hz(880.0), time::Ms(50.0)),
+                             (pitch::Hz(330.0), time::Ms(100.0))]);
+
+    // At 10khz each frame steps the phase by the voice's frequency divided by 10,000.
+    let mut buffer = [[0.0f32; 2]; 1];
+    let mut last_phase = 0.0;
+    let mut hz_per_ms = vec![];
+    for i in 0..3_000 {
+        synth.fill_slice(&mut buffer, 10_000.0);
+        let phase = synth.normalized_phase(0, 0).unwrap();
+        if i % 10 == 0 {
+            hz_per_ms.push((phase - last_phase + 1.0) % 1.0 * 10_000.0);
+        }
+        last_phase = phase;
+    }
+
+    // Each target is reached at the end of its glide, which is interpolated in pitch.
+    let hz = |ms: usize| hz_per_ms[ms];
+    assert!((hz(50) - 220.0 * 2.0f64.sqrt()).abs() < 0.5);
+    assert!((hz(100) - 440.0).abs() < 0.5);
+    assert!((hz(125) - 440.0 * 2.0f64.sqrt()).abs() < 0.5);
+    assert!((hz(150) - 880.0).abs() < 0.5);
+    assert!((hz(250) - 330.0).abs() < 0.5);
+
+    // The last target is held until the sequence is cleared.
+    assert!((hz(299) - 330.0).abs() < 0.5);
+    synth.clear_note_sequence();
+    assert_eq!(synth.glide_sequence, None);
+}
+
+#[test]
+fn test_arpeggiator_patterns() {
+    use oscillator::waveform;
+
+    let new_synth = |pattern: ArpPattern| {
+        let mut synth = Synth::poly(())
+            .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+            .arpeggiate(10.0, pattern);
+        for &hz in &[330.0, 220.0, 440.0] {
+            synth.note_on(pitch::Hz(hz), 1.0);
+        }
+        synth
+    };
+
+    // At 1khz each 10ms step lasts 10 frames, so record the note playing every 10th frame.
+    let played_notes = |pattern: ArpPattern| {
+        let mut synth = new_synth(pattern);
+        let mut buffer = [[0.0f32; 2]; 1];
+        let mut notes = Vec::new();
+        for i in 0..60 {
+            synth.fill_slice(&mut buffer, 1_000.0);
+            let playing = synth.arpeggiator.as_ref().unwrap().playing;
+            if i % 10 == 0 {
+                notes.push(playing.unwrap());
+            } else {
+                assert_eq!(playing, notes.last().cloned());
+            }
+        }
+        notes
+    };
+    assert_eq!(played_notes(ArpPattern::Up), vec![220.0, 330.0, 440.0, 220.0, 330.0, 440.0]);
+    assert_eq!(played_notes(ArpPattern::Down), vec![440.0, 330.0, 220.0, 440.0, 330.0, 220.0]);
+    assert_eq!(played_notes(ArpPattern::UpDown), vec![220.0, 330.0, 440.0, 330.0, 220.0, 330.0]);
+
+    // Rendering the same span in a single call takes the same number of steps.
+    let mut synth = new_synth(ArpPattern::Up);
+    let mut buffer = [[0.0f32; 2]; 60];
+    synth.fill_slice(&mut buffer, 1_000.0);
+    let arp = synth.arpeggiator.as_ref().unwrap();
+    assert_eq!(arp.step, 6);
+    assert_eq!(arp.playing, Some(440.0));
+}
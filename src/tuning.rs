@@ -0,0 +1,57 @@
+//!
+//! Tunings for mapping incoming notes onto scales other than 12-tone equal temperament.
+//!
+
+use pitch;
+
+
+/// The cents offsets of 5-limit just intonation from equal temperament, starting from C.
+pub const JUST_INTONATION_CENTS: [f32; 12] = [
+    0.0, 11.73, 3.91, 15.64, -13.69, -1.96, -9.78, 1.96, 13.69, -15.64, 17.60, -11.73,
+];
+
+/// Describes how incoming notes are mapped to the frequencies played by the `Synth`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize), serde(crate="serde_v1"))]
+pub enum Tuning {
+    /// 12-tone equal temperament. Notes are played at exactly the given frequency.
+    EqualTemperament,
+    /// An offset in cents for each of the 12 chromatic degrees, starting from C.
+    ///
+    /// Each note is retuned by the offset of the equal-tempered degree nearest to it.
+    Degrees([f32; 12]),
+}
+
+
+impl Tuning {
+
+    /// 5-limit just intonation relative to C.
+    pub fn just_intonation() -> Self {
+        Tuning::Degrees(JUST_INTONATION_CENTS)
+    }
+
+    /// Map the given frequency to its frequency within the tuning.
+    pub fn retune(&self, hz: pitch::calc::Hz) -> pitch::calc::Hz {
+        match *self {
+            Tuning::EqualTemperament => hz,
+            Tuning::Degrees(ref cents) => {
+                // The nearest MIDI note number, where 60 is C4 and 69 is A4 (440hz).
+                let note = (12.0 * (hz / 440.0).log2() + 69.0).round() as i64;
+                let degree = ((note % 12 + 12) % 12) as usize;
+                hz * 2.0f32.powf(cents[degree] / 1200.0)
+            },
+        }
+    }
+
+}
+
+
+#[test]
+fn test_just_major_third() {
+    let c4 = 261.6256;
+    let e4 = 329.6276;
+    let tuning = Tuning::just_intonation();
+    assert_eq!(Tuning::EqualTemperament.retune(e4), e4);
+    assert_eq!(tuning.retune(c4), c4);
+    assert!((tuning.retune(e4) - c4 * 5.0 / 4.0).abs() < 0.01);
+}
@@ -0,0 +1,80 @@
+//!
+//! Rendering a `Synth` to a WAV file via `hound`, enabled by the `wav` feature.
+//!
+
+extern crate hound;
+
+use {Synth, instrument, oscillator};
+use instrument::unit::NoteVelocity;
+use pitch;
+use std::path::Path;
+use time;
+
+/// The number of channels written by `Synth::render_to_wav`.
+const CHANNELS: u16 = 2;
+
+impl<M, NFG, W, A, F, FW> Synth<M, NFG, W, A, F, FW>
+    where M: instrument::Mode,
+          NFG: instrument::NoteFreqGenerator,
+          W: oscillator::Waveform,
+          A: oscillator::Amplitude,
+          F: oscillator::Frequency,
+          FW: oscillator::FreqWarp,
+{
+
+    /// Render a single note (including its release tail) via `render_note` and write it to a
+    /// stereo, 32-bit float WAV file at the given `path`.
+    ///
+    /// As with `render_note`, the tail is cut off once it reaches its maximum length, so notes
+    /// that are never released still produce a finite file.
+    pub fn render_to_wav<P, T, D>(&mut self,
+                                  path: P,
+                                  note_hz: T,
+                                  note_vel: NoteVelocity,
+                                  duration: D,
+                                  sample_hz: f64) -> Result<(), hound::Error>
+        where P: AsRef<Path>,
+              T: Into<pitch::Hz>,
+              D: Into<time::Ms>,
+    {
+        let frames: Vec<[f32; 2]> = self.render_note(note_hz, note_vel, duration, sample_hz);
+        let spec = hound::WavSpec {
+            channels: CHANNELS,
+            sample_rate: sample_hz as u32,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = try!(hound::WavWriter::create(path, spec));
+        for frame in &frames {
+            for &sample in frame {
+                try!(writer.write_sample(sample));
+            }
+        }
+        writer.finalize()
+    }
+
+}
+
+#[test]
+fn test() {
+    use oscillator::{Oscillator, waveform};
+
+    // The file name is unique to the process so that concurrent test runs do not collide.
+    let file_name = format!("synth_render_to_wav_test_{}.wav", ::std::process::id());
+    let path = ::std::env::temp_dir().join(file_name);
+    let mut synth = Synth::poly(())
+        .oscillator(Oscillator::new(waveform::Sine, 1.0, 440.0, ()))
+        .fade(1.0, 10.0);
+    synth.render_to_wav(&path, pitch::Hz(440.0), 1.0, 50.0, 44_100.0).unwrap();
+
+    let (spec, len) = {
+        let reader = hound::WavReader::open(&path).unwrap();
+        (reader.spec(), reader.len())
+    };
+    ::std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(spec.channels, CHANNELS);
+    assert_eq!(spec.sample_rate, 44_100);
+    assert_eq!(spec.sample_format, hound::SampleFormat::Float);
+    assert!(len > 0);
+}